@@ -4,13 +4,19 @@ use actix_web::web::Data;
 use actix_web::{error, post, web, Error, HttpRequest, HttpResponse, Scope};
 use celery::Celery;
 use redis::Commands;
-use serenity::all::{ChannelId, CreateEmbed, CreateEmbedAuthor};
+use serenity::all::{
+    ChannelId, CreateAllowedMentions, CreateEmbed, CreateEmbedAuthor, CreateEmbedFooter, RoleId,
+    UserId,
+};
 use serenity::builder::CreateMessage;
 use serenity::http::Http;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::task::spawn_local;
+use trackscape_discord_shared::database::clan_mates::ClanMates;
 use trackscape_discord_shared::database::BotMongoDb;
-use trackscape_discord_shared::ge_api::ge_api::get_item_mapping;
+use trackscape_discord_shared::embed_templates::{render_guild_broadcast_embed, RenderedEmbed};
+use trackscape_discord_shared::ge_api::ge_api::{get_item_mapping, get_latest_prices};
 use trackscape_discord_shared::helpers::hash_string;
 use trackscape_discord_shared::jobs::CeleryJobQueue;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::{
@@ -18,8 +24,11 @@ use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extracto
 };
 use trackscape_discord_shared::osrs_broadcast_handler::OSRSBroadcastHandler;
 use trackscape_discord_shared::redis_helpers::{fetch_redis, write_to_cache_with_seconds};
+use trackscape_discord_shared::role_pings::role_ping_off_cooldown;
 use trackscape_discord_shared::wiki_api::wiki_api::get_quests_and_difficulties;
-use trackscape_discord_shared::wiki_api::wiki_api::get_clogs_and_percentages;
+use trackscape_discord_shared::wiki_api::wiki_api::{
+    get_cached_drop_rates, get_clogs_and_percentages,
+};
 use web::Json;
 
 #[derive(Debug)]
@@ -121,8 +130,14 @@ async fn new_clan_chats(
         .expect("Could not connect to redis");
 
     let mut clan_chat_queue: Vec<CreateEmbed> = vec![];
-    let mut broadcast_queue: Vec<CreateEmbed> = vec![];
-    let mut leagues_broadcast_queue: Vec<CreateEmbed> = vec![];
+    //One queue per destination channel so each gets a single message
+    let mut broadcast_queues: HashMap<u64, Vec<CreateEmbed>> = HashMap::new();
+    //Discord users linked to the players in each channels broadcasts
+    let mut broadcast_mentions: HashMap<u64, Vec<u64>> = HashMap::new();
+    //Roles the guild pings for each channels broadcasts
+    let mut broadcast_role_pings: HashMap<u64, Vec<u64>> = HashMap::new();
+    let mut broadcast_count: usize = 0;
+    let mut leagues_broadcast_count: usize = 0;
 
     for mut chat in new_chat.clone() {
         if chat.sender.clone() == "" && chat.clan_name.clone() == "" {
@@ -177,15 +192,26 @@ async fn new_clan_chats(
                     false => get_wiki_clan_rank_image_url(chat.rank.clone()),
                 };
 
-                clan_chat_queue.push(
-                    CreateEmbed::new()
-                        .title("")
-                        .author(CreateEmbedAuthor::new(chat.sender.clone()).icon_url(author_image))
-                        //HACK
-                        .description(chat.message.clone().replace(LEAGUES_ICON_TAG, ""))
-                        .color(0x0000FF)
-                        .timestamp(right_now),
-                );
+                let mut clan_chat_embed = CreateEmbed::new()
+                    .title("")
+                    .author(CreateEmbedAuthor::new(chat.sender.clone()).icon_url(author_image))
+                    //HACK
+                    .description(chat.message.clone().replace(LEAGUES_ICON_TAG, ""))
+                    .color(0x0000FF)
+                    .timestamp(right_now);
+
+                //Shows the ironman badges next to the timestamp for clan mates chats
+                if chat.clan_name.clone() != chat.sender.clone() {
+                    if let Some(account_type) = chat.sender_account_type() {
+                        if let Some(account_icon) = account_type.icon_url() {
+                            clan_chat_embed = clan_chat_embed.footer(
+                                CreateEmbedFooter::new(account_type.to_string())
+                                    .icon_url(account_icon),
+                            );
+                        }
+                    }
+                }
+                clan_chat_queue.push(clan_chat_embed);
             }
             _ => {}
         }
@@ -211,6 +237,7 @@ async fn new_clan_chats(
                         chat.sender.clone(),
                         chat.rank.clone(),
                         registered_guild.guild_id,
+                        chat.sender_account_type(),
                     ),
                 )
                 .await;
@@ -220,6 +247,10 @@ async fn new_clan_chats(
         //TODO may remove this since the handler does some loging for the website now
         if registered_guild.broadcast_channel.is_none()
             && registered_guild.clan_chat_channel.is_none()
+            && !registered_guild
+                .broadcast_type_channels
+                .as_ref()
+                .is_some_and(|channels| !channels.is_empty())
         {
             //If there is not any broadcast_channels set just continue
             continue;
@@ -235,10 +266,15 @@ async fn new_clan_chats(
 
         let item_mapping_from_redis = get_item_mapping(&mut redis_connection).await;
 
+        let prices_from_redis = get_latest_prices(&mut redis_connection).await;
+
         let quests_from_redis = get_quests_and_difficulties(&mut redis_connection).await;
 
         let clogs_from_redis = get_clogs_and_percentages(&mut redis_connection).await;
 
+        //Only read from the cache, the wiki is crawled at startup and by the cron worker
+        let drop_rates_from_redis = get_cached_drop_rates(&mut redis_connection).await;
+
         let cloned_celery = Arc::clone(&**celery);
         let celery_job_queue = Arc::new(CeleryJobQueue {
             celery: cloned_celery,
@@ -247,13 +283,16 @@ async fn new_clan_chats(
         let handler = OSRSBroadcastHandler::new(
             chat.clone(),
             item_mapping_from_redis,
+            prices_from_redis,
             quests_from_redis,
             clogs_from_redis,
+            drop_rates_from_redis,
             registered_guild.clone(),
             league_world,
             mongodb.drop_logs.clone(),
             mongodb.clan_mate_collection_log_totals.clone(),
             mongodb.clan_mates.clone(),
+            mongodb.pb_records.clone(),
             celery_job_queue,
         );
         let possible_broadcast = handler.extract_message().await;
@@ -264,23 +303,44 @@ async fn new_clan_chats(
                     //This checks for leagues only broadcasts. Like new area, etc
                     let possible_leagues_message = handler.extract_leagues_message().await;
                     if let Some(leagues_message) = possible_leagues_message {
-                        let mut broadcast_embed = CreateEmbed::new()
-                            .title(leagues_message.title.clone())
-                            .description(leagues_message.message.clone())
-                            .color(0x0000FF)
-                            .timestamp(right_now);
-                        match leagues_message.icon_url {
-                            None => {}
-                            Some(icon_url) => {
-                                broadcast_embed = broadcast_embed.image(icon_url);
+                        let broadcast_embed = create_broadcast_embed(
+                            render_guild_broadcast_embed(&registered_guild, &leagues_message),
+                            right_now,
+                        );
+
+                        let destination = leagues_message.route_to_channel.or(registered_guild
+                            .channel_for_broadcast(&leagues_message.type_of_broadcast, true));
+                        //Only send if theres a channel for it
+                        if let Some(channel_id) = destination {
+                            broadcast_queues
+                                .entry(channel_id)
+                                .or_insert_with(Vec::new)
+                                .push(broadcast_embed);
+                            if let Some(discord_user_id) = linked_discord_user(
+                                &mongodb,
+                                registered_guild.guild_id,
+                                leagues_message.player_it_happened_to.clone(),
+                            )
+                            .await
+                            {
+                                broadcast_mentions
+                                    .entry(channel_id)
+                                    .or_insert_with(Vec::new)
+                                    .push(discord_user_id);
                             }
-                        }
-
-                        //Only send if theres a leagues channel
-                        if let Some(_channel_to_send_broadcast) =
-                            registered_guild.leagues_broadcast_channel
-                        {
-                            leagues_broadcast_queue.push(broadcast_embed);
+                            if let Some(role_id) = role_ping_off_cooldown(
+                                &mut redis_connection,
+                                &registered_guild,
+                                &leagues_message,
+                            )
+                            .await
+                            {
+                                broadcast_role_pings
+                                    .entry(channel_id)
+                                    .or_insert_with(Vec::new)
+                                    .push(role_id);
+                            }
+                            leagues_broadcast_count += 1;
                         }
                     }
                 }
@@ -290,30 +350,45 @@ async fn new_clan_chats(
                     .broadcasts
                     .create_broadcast(registered_guild.guild_id, broadcast.clone())
                     .await;
-                let mut broadcast_embed = CreateEmbed::new()
-                    .title(broadcast.title.clone())
-                    .description(broadcast.message.clone())
-                    .color(0x0000FF)
-                    .timestamp(right_now);
-                match broadcast.icon_url {
-                    None => {}
-                    Some(icon_url) => {
-                        broadcast_embed = broadcast_embed.image(icon_url);
-                    }
-                }
+                let broadcast_embed = create_broadcast_embed(
+                    render_guild_broadcast_embed(&registered_guild, &broadcast),
+                    right_now,
+                );
 
-                match league_world {
-                    true => {
-                        if registered_guild.leagues_broadcast_channel.is_some() {
-                            leagues_broadcast_queue.push(broadcast_embed);
-                        }
+                //A rule's channel wins, then the broadcast type's channel, then the default one
+                let destination = broadcast.route_to_channel.or(registered_guild
+                    .channel_for_broadcast(&broadcast.type_of_broadcast, league_world));
+                if let Some(channel_id) = destination {
+                    broadcast_queues
+                        .entry(channel_id)
+                        .or_insert_with(Vec::new)
+                        .push(broadcast_embed);
+                    if let Some(discord_user_id) = linked_discord_user(
+                        &mongodb,
+                        registered_guild.guild_id,
+                        broadcast.player_it_happened_to.clone(),
+                    )
+                    .await
+                    {
+                        broadcast_mentions
+                            .entry(channel_id)
+                            .or_insert_with(Vec::new)
+                            .push(discord_user_id);
                     }
-                    false => {
-                        if registered_guild.broadcast_channel.is_some() {
-                            broadcast_queue.push(broadcast_embed);
-                        }
+                    if let Some(role_id) =
+                        role_ping_off_cooldown(&mut redis_connection, &registered_guild, &broadcast)
+                            .await
+                    {
+                        broadcast_role_pings
+                            .entry(channel_id)
+                            .or_insert_with(Vec::new)
+                            .push(role_id);
                     }
-                };
+                    match league_world {
+                        true => leagues_broadcast_count += 1,
+                        false => broadcast_count += 1,
+                    }
+                }
             }
         };
     }
@@ -341,51 +416,94 @@ async fn new_clan_chats(
             .expect("failed to execute INCR for 'Clan Chat'");
     }
 
-    if broadcast_queue.len() > 0 {
-        let broadcast_key = format!("{}:{}", redis_broadcast_stats_prefix, "broadcast");
-        let broadcast_queue_length = broadcast_queue.len();
-        if let Some(channel_id) = registered_guild.broadcast_channel {
-            let result = ChannelId::new(channel_id)
-                .send_message(
-                    &*discord_http_client,
-                    CreateMessage::new().embeds(broadcast_queue),
-                )
-                .await;
-            if let Err(_e) = result {
-                // error!("Error sending broadcast: {:?}", e);
-            }
+    for (channel_id, broadcasts) in broadcast_queues {
+        let mut discord_user_ids = broadcast_mentions.remove(&channel_id).unwrap_or_default();
+        discord_user_ids.sort();
+        discord_user_ids.dedup();
+        let mut role_ids = broadcast_role_pings.remove(&channel_id).unwrap_or_default();
+        role_ids.sort();
+        role_ids.dedup();
+        let mentions: Vec<String> = role_ids
+            .iter()
+            .map(|role_id| format!("<@&{}>", role_id))
+            .chain(
+                discord_user_ids
+                    .iter()
+                    .map(|discord_user_id| format!("<@{}>", discord_user_id)),
+            )
+            .collect();
+
+        //Only the exact users and roles picked here can be pinged, never @everyone or @here
+        let mut message = CreateMessage::new().embeds(broadcasts).allowed_mentions(
+            CreateAllowedMentions::new()
+                .everyone(false)
+                .all_users(false)
+                .all_roles(false)
+                .users(discord_user_ids.into_iter().map(UserId::new))
+                .roles(role_ids.into_iter().map(RoleId::new)),
+        );
+        if !mentions.is_empty() {
+            message = message.content(mentions.join(" "));
         }
+        let result = ChannelId::new(channel_id)
+            .send_message(&*discord_http_client, message)
+            .await;
+        if let Err(_e) = result {
+            // error!("Error sending broadcast: {:?}", e);
+        }
+    }
+
+    if broadcast_count > 0 {
+        let broadcast_key = format!("{}:{}", redis_broadcast_stats_prefix, "broadcast");
         let _: () = redis_connection
-            .incr(broadcast_key.as_str(), broadcast_queue_length)
+            .incr(broadcast_key.as_str(), broadcast_count)
             .expect("failed to execute INCR for 'Broadcast'");
     }
 
-    if leagues_broadcast_queue.len() > 0 {
-        let leagues_broadcast_queue_length = leagues_broadcast_queue.len();
-        if let Some(channel_id) = registered_guild.leagues_broadcast_channel {
-            let result = ChannelId::new(channel_id)
-                .send_message(
-                    &*discord_http_client,
-                    CreateMessage::new().embeds(leagues_broadcast_queue),
-                )
-                .await;
-            if let Err(_e) = result {
-                // error!("Error sending leagues broadcast: {:?}", e);
-            }
-        }
+    if leagues_broadcast_count > 0 {
         let leagues_broadcast_key =
             format!("{}:{}", redis_broadcast_stats_prefix, "leagues_broadcast");
         let _: () = redis_connection
-            .incr(
-                leagues_broadcast_key.as_str(),
-                leagues_broadcast_queue_length,
-            )
+            .incr(leagues_broadcast_key.as_str(), leagues_broadcast_count)
             .expect("failed to execute INCR for 'Broadcast'");
     }
 
     return Ok("Message processed".to_string());
 }
 
+/// The discord user that linked the player with /link, if they are in this clan and have not
+/// turned the mentions off
+async fn linked_discord_user(mongodb: &BotMongoDb, guild_id: u64, player: String) -> Option<u64> {
+    match mongodb
+        .clan_mates
+        .find_by_current_name(guild_id, player)
+        .await
+    {
+        Ok(Some(clan_mate)) if clan_mate.discord_mentions != Some(false) => {
+            clan_mate.discord_user_id
+        }
+        _ => None,
+    }
+}
+
+fn create_broadcast_embed(
+    rendered_embed: RenderedEmbed,
+    timestamp: serenity::model::timestamp::Timestamp,
+) -> CreateEmbed {
+    let mut broadcast_embed = CreateEmbed::new()
+        .title(rendered_embed.title)
+        .description(rendered_embed.description)
+        .color(rendered_embed.color)
+        .timestamp(timestamp);
+    if let Some(image_url) = rendered_embed.image_url {
+        broadcast_embed = broadcast_embed.image(image_url);
+    }
+    if let Some(thumbnail_url) = rendered_embed.thumbnail_url {
+        broadcast_embed = broadcast_embed.thumbnail(thumbnail_url);
+    }
+    broadcast_embed
+}
+
 /// Handshake and start WebSocket handler with heartbeats.
 async fn chat_ws(
     req: HttpRequest,
@@ -146,12 +146,227 @@ async fn collection_log(
     }
 }
 
+#[derive(Deserialize)]
+struct CollectionLogGainsQuery {
+    days: Option<i64>,
+}
+
+/// Slots each clan mate gained in the last days, 30 by default
+#[get("/{id}/collection-log/gains")]
+async fn collection_log_gains(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String,)>,
+    query: web::Query<CollectionLogGainsQuery>,
+) -> Result<HttpResponse, Error> {
+    let id = match bson::oid::ObjectId::from_str(path.into_inner().0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+    let days = query.days.unwrap_or(30);
+    if days < 1 {
+        return Ok(HttpResponse::BadRequest().body("Days must be at least 1."));
+    }
+    let end_date = bson::DateTime::now();
+    let start_date =
+        bson::DateTime::from_millis(end_date.timestamp_millis() - days * 24 * 60 * 60 * 1000);
+
+    match mongodb.guilds.get_by_id(id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .clan_mate_collection_log_totals
+                .get_gains_between_dates(registered_guild.guild_id, start_date, end_date)
+                .await;
+            match result {
+                Ok(gains) => Ok(HttpResponse::Ok().json(gains)),
+                Err(err) => {
+                    error!("Failed to get collection log gains: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
+/// The collection log items each clan mate has had broadcast
+#[get("/{id}/collection-log/items")]
+async fn collection_log_items(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, Error> {
+    let id = match bson::oid::ObjectId::from_str(path.into_inner().0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    match mongodb.guilds.get_by_id(id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .clan_mate_collection_log_totals
+                .get_clan_mate_items(registered_guild.guild_id)
+                .await;
+            match result {
+                Ok(items) => Ok(HttpResponse::Ok().json(items)),
+                Err(err) => {
+                    error!("Failed to get collection log items: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
+/// Every change to a clan mates collection log total oldest first, for graphing progress
+#[get("/{id}/collection-log/progress/{clan_mate_id}")]
+async fn collection_log_progress(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String, String)>,
+) -> Result<HttpResponse, Error> {
+    let get_variables = path.into_inner();
+    let id = match bson::oid::ObjectId::from_str(get_variables.0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+    let clan_mate_id = match bson::oid::ObjectId::from_str(get_variables.1.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    match mongodb.guilds.get_by_id(id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .clan_mate_collection_log_totals
+                .get_clan_mate_progress(registered_guild.guild_id, clan_mate_id)
+                .await;
+            match result {
+                Ok(progress) => Ok(HttpResponse::Ok().json(progress)),
+                Err(err) => {
+                    error!("Failed to get collection log progress: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct BroadcastRequest {
     id: String,
     limit: i64,
 }
 
+#[get("/{id}/leagues")]
+async fn leagues(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, Error> {
+    let id = path.into_inner().0;
+    let possible_parsed_id = bson::oid::ObjectId::from_str(id.as_str());
+    let id = match possible_parsed_id {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    let registered_guild_query = mongodb.guilds.get_by_id(id).await;
+    match registered_guild_query {
+        Ok(possible_registered_guild) => match possible_registered_guild {
+            None => {
+                return Ok(HttpResponse::NotFound().body("Clan not found."));
+            }
+            Some(registered_guild) => {
+                let result = mongodb
+                    .clan_mate_leagues_progress
+                    .get_guild_leaderboard(registered_guild.guild_id)
+                    .await;
+                match result {
+                    Ok(leaderboard) => {
+                        return Ok(HttpResponse::Ok().json(leaderboard));
+                    }
+                    Err(err) => {
+                        error!("Failed to get leagues leaderboard: {}", err);
+                        return Ok(
+                            HttpResponse::BadRequest().body("There was an issue with the request")
+                        );
+                    }
+                }
+            }
+        },
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            return Ok(HttpResponse::BadRequest().body("There was an issue with the request"));
+        }
+    }
+}
+
+#[get("/{id}/pets")]
+async fn pets(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, Error> {
+    let id = path.into_inner().0;
+    let possible_parsed_id = bson::oid::ObjectId::from_str(id.as_str());
+    let id = match possible_parsed_id {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    let registered_guild_query = mongodb.guilds.get_by_id(id).await;
+    match registered_guild_query {
+        Ok(possible_registered_guild) => match possible_registered_guild {
+            None => {
+                return Ok(HttpResponse::NotFound().body("Clan not found."));
+            }
+            Some(registered_guild) => {
+                let result = mongodb
+                    .clan_mate_pets
+                    .get_guild_pet_collection(registered_guild.guild_id)
+                    .await;
+                match result {
+                    Ok(pet_collection) => {
+                        return Ok(HttpResponse::Ok().json(pet_collection));
+                    }
+                    Err(err) => {
+                        error!("Failed to get clan pet collection: {}", err);
+                        return Ok(
+                            HttpResponse::BadRequest().body("There was an issue with the request")
+                        );
+                    }
+                }
+            }
+        },
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            return Ok(HttpResponse::BadRequest().body("There was an issue with the request"));
+        }
+    }
+}
+
 #[get("/{id}/broadcasts/{limit}")]
 async fn broadcasts(
     mongodb: Data<BotMongoDb>,
@@ -241,11 +456,158 @@ async fn personal_bests(
     }
 }
 
+#[derive(Deserialize)]
+struct ProgressionQuery {
+    clan_mate_id: Option<String>,
+}
+
+/// Every personal best the clan has had on the activity oldest first, for charting progression.
+/// Pass clan_mate_id to only get one clan mates
+#[get("/{guild_id}/{activity_id}/personal-bests/progression")]
+async fn personal_best_progression(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String, String)>,
+    query: web::Query<ProgressionQuery>,
+) -> Result<HttpResponse, Error> {
+    let get_variables = path.into_inner();
+    let guild_id = match bson::oid::ObjectId::from_str(get_variables.0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+    let activity_id = match bson::oid::ObjectId::from_str(get_variables.1.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+    let clan_mate_id = match &query.clan_mate_id {
+        Some(clan_mate_id) => match bson::oid::ObjectId::from_str(clan_mate_id.as_str()) {
+            Ok(parsed_id) => Some(parsed_id),
+            Err(_) => {
+                return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+            }
+        },
+        None => None,
+    };
+
+    match mongodb.guilds.get_by_id(guild_id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .pb_records
+                .get_pb_progression(activity_id, registered_guild.guild_id, clan_mate_id)
+                .await;
+            match result {
+                Ok(progression) => Ok(HttpResponse::Ok().json(progression)),
+                Err(err) => {
+                    error!("Failed to get personal best progression: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct SkillLeaderboardQuery {
+    min_level: Option<i64>,
+}
+
+/// The clan mates latest known level and XP in a skill, pass min_level for who has 99 in it
+#[get("/{id}/skills/{skill}")]
+async fn skill_leaderboard(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String, String)>,
+    query: web::Query<SkillLeaderboardQuery>,
+) -> Result<HttpResponse, Error> {
+    let get_variables = path.into_inner();
+    let id = match bson::oid::ObjectId::from_str(get_variables.0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    match mongodb.guilds.get_by_id(id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .clan_mate_skills
+                .get_skill_leaderboard(
+                    registered_guild.guild_id,
+                    get_variables.1.as_str(),
+                    query.min_level,
+                )
+                .await;
+            match result {
+                Ok(skills) => Ok(HttpResponse::Ok().json(skills)),
+                Err(err) => {
+                    error!("Failed to get skill leaderboard: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
+/// The clan mates by their latest known total level, maxed clan mates first
+#[get("/{id}/skills/closest-to-max")]
+async fn closest_to_max(
+    mongodb: Data<BotMongoDb>,
+    path: web::Path<(String,)>,
+) -> Result<HttpResponse, Error> {
+    let id = match bson::oid::ObjectId::from_str(path.into_inner().0.as_str()) {
+        Ok(parsed_id) => parsed_id,
+        Err(_) => {
+            return Ok(HttpResponse::BadRequest().body("Invalid id format."));
+        }
+    };
+
+    match mongodb.guilds.get_by_id(id).await {
+        Ok(None) => Ok(HttpResponse::NotFound().body("Clan not found.")),
+        Ok(Some(registered_guild)) => {
+            let result = mongodb
+                .clan_mate_skills
+                .get_closest_to_max(registered_guild.guild_id)
+                .await;
+            match result {
+                Ok(total_levels) => Ok(HttpResponse::Ok().json(total_levels)),
+                Err(err) => {
+                    error!("Failed to get the clan mates closest to max: {}", err);
+                    Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+                }
+            }
+        }
+        Err(err) => {
+            error!("Failed to get clan by id: {}", err);
+            Ok(HttpResponse::BadRequest().body("There was an issue with the request"))
+        }
+    }
+}
+
 pub fn clan_controller() -> Scope {
     web::scope("/clans")
         .service(list_clans)
         .service(detail)
+        .service(collection_log_gains)
+        .service(collection_log_items)
+        .service(collection_log_progress)
         .service(collection_log)
+        .service(leagues)
+        .service(pets)
         .service(broadcasts)
+        .service(personal_best_progression)
         .service(personal_bests)
+        .service(closest_to_max)
+        .service(skill_leaderboard)
 }
@@ -27,6 +27,10 @@ struct DropRow<'a> {
     price: Option<i64>,
     #[serde(rename = "Date")]
     date: chrono::DateTime<chrono::Utc>,
+    #[serde(rename = "Type")]
+    broadcast_type: String,
+    #[serde(rename = "Source")]
+    source: Option<&'a str>,
 }
 
 #[get("/list/{confirmation_code}/{start_date}/{end_date}")]
@@ -60,7 +64,8 @@ async fn get_drops(
                 match possible_drop_logs {
                     Ok(drop_logs) => {
                         if drop_logs.len() == 0 {
-                            return Ok(HttpResponse::Ok().body("RSN,Item Name,Quantity,Price,Date"));
+                            return Ok(HttpResponse::Ok()
+                                .body("RSN,Item Name,Quantity,Price,Date,Type,Source"));
                         }
                         let mut wtr = Writer::from_writer(vec![]);
                         for drop_log in drop_logs.clone() {
@@ -70,6 +75,8 @@ async fn get_drops(
                                 quantity: drop_log.drop_item.item_quantity,
                                 price: drop_log.drop_item.item_value,
                                 date: drop_log.created_at.to_chrono(),
+                                broadcast_type: drop_log.broadcast_type.to_string(),
+                                source: drop_log.drop_item.drop_source.as_deref(),
                             };
                             wtr.serialize(drop_row).unwrap();
                         }
@@ -101,6 +108,48 @@ async fn get_drops(
     }
 }
 
+#[get("/sources/{confirmation_code}/{start_date}/{end_date}")]
+async fn get_drop_sources(
+    list_drop_request: web::Path<ListDropsRequest>,
+    mongodb: web::Data<BotMongoDb>,
+) -> Result<HttpResponse, Error> {
+    let possible_registered_guild = mongodb
+        .guilds
+        .get_guild_by_code(list_drop_request.confirmation_code.clone())
+        .await;
+
+    match possible_registered_guild {
+        Ok(guild) => match guild {
+            Some(registered_guild) => {
+                let parsed_start_date = parse_with_timezone(&list_drop_request.start_date, &Utc);
+                let parsed_end_date = parse_with_timezone(&list_drop_request.end_date, &Utc);
+                if parsed_start_date.is_err() {
+                    return Ok(HttpResponse::BadRequest().body("Invalid Start Date"));
+                }
+                if parsed_end_date.is_err() {
+                    return Ok(HttpResponse::BadRequest().body("Invalid End Date"));
+                }
+                let start = bson::datetime::DateTime::from_chrono(parsed_start_date.unwrap());
+                let end = bson::datetime::DateTime::from_chrono(parsed_end_date.unwrap());
+                let possible_source_stats = mongodb
+                    .drop_logs
+                    .get_drop_source_stats(registered_guild.guild_id, start, end)
+                    .await;
+
+                match possible_source_stats {
+                    Ok(source_stats) => Ok(HttpResponse::Ok().json(source_stats)),
+                    Err(_) => Ok(HttpResponse::BadRequest()
+                        .body("There was an issue getting the drop sources.")),
+                }
+            }
+            None => Ok(HttpResponse::Unauthorized().body("Invalid Confirmation Code")),
+        },
+        Err(_) => Ok(HttpResponse::BadRequest().body("There was an issue getting the clan.")),
+    }
+}
+
 pub fn drop_log_controller() -> Scope {
-    web::scope("/drops").service(get_drops)
+    web::scope("/drops")
+        .service(get_drops)
+        .service(get_drop_sources)
 }
@@ -29,7 +29,7 @@ use actix_files::{Files, NamedFile};
 use log::{error, info};
 use trackscape_discord_shared::jobs::get_celery_caller;
 use trackscape_discord_shared::wiki_api::wiki_api::get_quests_and_difficulties;
-use trackscape_discord_shared::wiki_api::wiki_api::get_clogs_and_percentages;
+use trackscape_discord_shared::wiki_api::wiki_api::{get_clogs_and_percentages, get_drop_rates};
 
 /// Connection ID.
 pub type ConnId = Uuid;
@@ -59,6 +59,9 @@ async fn actix_web() -> ShuttleActixWeb<impl FnOnce(&mut ServiceConfig) + Send +
     }
 
     let db = BotMongoDb::new_db_instance(mongodb_url).await;
+    db.prepare_on_startup()
+        .await
+        .expect("Could not prepare the database");
     let redis_client = get_redis_client();
     let mut redis_conn = redis_client
         .get_connection()
@@ -95,6 +98,16 @@ async fn actix_web() -> ShuttleActixWeb<impl FnOnce(&mut ServiceConfig) + Send +
         }
     }
 
+    let possible_drop_rates = get_drop_rates(&mut redis_conn).await;
+    match possible_drop_rates {
+        Ok(_) => {
+            info!("Drop rate mapping was out of date, updating cache");
+        }
+        Err(e) => {
+            error!("Error getting drop rates: {}", e)
+        }
+    }
+
     #[allow(clippy::mutex_atomic)] // it's intentional.
     let connected_websockets_counter = Data::new(Mutex::new(0usize));
     let connected_discord_servers = Data::new(AtomicI64::new(0));
@@ -0,0 +1,215 @@
+use crate::database::BotMongoDb;
+use regex::Regex;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{
+    add_rule, BroadcastRule, RuleAction, RuleCondition,
+};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    AccountType, BroadcastType,
+};
+
+pub fn register() -> CreateCommand {
+    let mut broadcast_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "broadcast",
+        "Only match this broadcast type.",
+    );
+    for broadcast_type in BroadcastType::iter() {
+        if let BroadcastType::Unknown = broadcast_type {
+            continue;
+        }
+        broadcast_option = broadcast_option
+            .add_string_choice(broadcast_type.to_string(), broadcast_type.to_slug());
+    }
+
+    let mut account_type_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "account_type",
+        "Only match clan mates of this account type.",
+    );
+    for account_type in AccountType::iter() {
+        account_type_option =
+            account_type_option.add_string_choice(account_type.to_string(), account_type.to_slug());
+    }
+
+    CreateCommand::new("add_broadcast_rule")
+        .description(
+            "Adds a rule that allows, blocks or reroutes broadcasts. Rules are checked in order.",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "action",
+                "What to do with broadcasts that match every condition.",
+            )
+            .add_string_choice("Allow", "allow")
+            .add_string_choice("Deny", "deny")
+            .add_string_choice("Send to channel", "route")
+            .required(true),
+        )
+        .add_option(broadcast_option.required(false))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "player",
+                "Only match this clan mate.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "item_pattern",
+                "Regex matched against the item, pet, quest, diary, task or activity name.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "min_value",
+                "Only match broadcasts worth at least this much gp.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "max_value",
+                "Only match broadcasts worth at most this much gp.",
+            )
+            .required(false),
+        )
+        .add_option(account_type_option.required(false))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Number,
+                "clog_percentage_above",
+                "Only match collection log items more than this % of players have.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "drop_rarer_than",
+                "Only match drops rarer than 1 in this many on the wiki.",
+            )
+            .min_int_value(1)
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "Channel to send matching broadcasts to when the action is Send to channel.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    let mut saved_guild = match saved_guild_query {
+        Ok(saved_guild) => saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id)),
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let mut conditions: Vec<RuleCondition> = Vec::new();
+    let mut action: Option<RuleAction> = None;
+    let mut channel: Option<u64> = None;
+    let mut min_value: Option<i64> = None;
+    let mut max_value: Option<i64> = None;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("action", CommandDataOptionValue::String(selected_action)) => {
+                action = match selected_action.as_str() {
+                    "allow" => Some(RuleAction::Allow),
+                    "deny" => Some(RuleAction::Deny),
+                    //The channel is filled in once every option is read
+                    "route" => Some(RuleAction::Route(0)),
+                    _ => None,
+                }
+            }
+            ("broadcast", CommandDataOptionValue::String(slug)) => {
+                match BroadcastType::iter()
+                    .into_iter()
+                    .find(|broadcast_type| broadcast_type.to_slug() == slug)
+                {
+                    Some(broadcast_type) => {
+                        conditions.push(RuleCondition::BroadcastType(vec![broadcast_type]))
+                    }
+                    None => return Some("Invalid broadcast type.".to_string()),
+                }
+            }
+            ("player", CommandDataOptionValue::String(player)) => {
+                conditions.push(RuleCondition::Player(vec![player]))
+            }
+            ("item_pattern", CommandDataOptionValue::String(pattern)) => {
+                if Regex::new(&pattern).is_err() {
+                    return Some(format!("`{}` is not a valid regex.", pattern));
+                }
+                conditions.push(RuleCondition::ItemMatches(pattern))
+            }
+            ("min_value", CommandDataOptionValue::Integer(value)) => min_value = Some(value),
+            ("max_value", CommandDataOptionValue::Integer(value)) => max_value = Some(value),
+            ("account_type", CommandDataOptionValue::String(account_type)) => conditions.push(
+                RuleCondition::AccountType(vec![AccountType::from_string(account_type)]),
+            ),
+            ("clog_percentage_above", CommandDataOptionValue::Number(percentage)) => {
+                conditions.push(RuleCondition::ClogPercentageAbove(percentage))
+            }
+            ("drop_rarer_than", CommandDataOptionValue::Integer(one_in)) => {
+                conditions.push(RuleCondition::DropRateRarerThan(one_in as f64))
+            }
+            ("channel", CommandDataOptionValue::Channel(channel_id)) => {
+                channel = Some(channel_id.get())
+            }
+            _ => {}
+        }
+    }
+
+    if min_value.is_some() || max_value.is_some() {
+        conditions.push(RuleCondition::ValueBetween {
+            min: min_value,
+            max: max_value,
+        });
+    }
+    let action = match action {
+        Some(RuleAction::Route(_)) => match channel {
+            Some(channel_id) => RuleAction::Route(channel_id),
+            None => return Some("Please pick a channel to send the broadcasts to.".to_string()),
+        },
+        Some(action) => action,
+        None => return Some("Invalid action.".to_string()),
+    };
+
+    let rule = BroadcastRule {
+        conditions,
+        action,
+        setting: None,
+    };
+    let response = format!("Added rule: {}", rule.to_string());
+    add_rule(
+        saved_guild.broadcast_rules.get_or_insert_with(Vec::new),
+        rule,
+    );
+    db.guilds.update_guild(saved_guild).await;
+    Some(response)
+}
@@ -0,0 +1,50 @@
+use crate::database::BotMongoDb;
+use serenity::all::{CommandDataOption, CreateCommand};
+use serenity::client::Context;
+
+//Discord messages can only be 2000 characters
+const MAX_RESPONSE_LENGTH: usize = 1900;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("broadcast_rules")
+        .description("Lists the rules used to decide which broadcasts are sent, in order.")
+}
+
+pub async fn run(
+    _command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild = match db.guilds.get_by_guild_id(guild_id).await {
+        Ok(Some(saved_guild)) => saved_guild,
+        _ => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    //Rules saved by the setting commands say which command changes them
+    let lines: Vec<String> = saved_guild
+        .broadcast_rules
+        .unwrap_or_default()
+        .iter()
+        .enumerate()
+        .map(|(index, rule)| format!("{}. {}", index + 1, rule.to_string()))
+        .collect();
+    if lines.is_empty() {
+        return Some("No rules are set, every broadcast is sent.".to_string());
+    }
+
+    let mut response = String::new();
+    for line in lines {
+        if response.len() + line.len() > MAX_RESPONSE_LENGTH {
+            response.push_str("...");
+            break;
+        }
+        response.push_str(&line);
+        response.push('\n');
+    }
+    Some(response)
+}
@@ -0,0 +1,90 @@
+use crate::database::BotMongoDb;
+use mongodb::bson::DateTime;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use trackscape_discord_shared::database::drop_logs_db::DropLogs;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+
+//Discord messages can only be 2000 characters
+const MAX_RESPONSE_LENGTH: usize = 1900;
+const DEFAULT_DAYS: i64 = 30;
+const MILLIS_PER_DAY: i64 = 24 * 60 * 60 * 1000;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("drop_stats")
+        .description("Shows the clan's drops grouped by the boss or activity they came from.")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "days",
+                "How many days back to look. Defaults to 30.",
+            )
+            .min_int_value(1)
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let days = match command.get(0) {
+        Some(days) => match days.value {
+            CommandDataOptionValue::Integer(days) => days,
+            _ => DEFAULT_DAYS,
+        },
+        None => DEFAULT_DAYS,
+    };
+
+    let end = DateTime::now();
+    let start = DateTime::from_millis(end.timestamp_millis() - days * MILLIS_PER_DAY);
+    let source_stats = match db
+        .drop_logs
+        .get_drop_source_stats(guild_id, start, end)
+        .await
+    {
+        Ok(source_stats) => source_stats,
+        Err(_) => {
+            return Some("There was a technical error. Please try again later.".to_string());
+        }
+    };
+    if source_stats.is_empty() {
+        return Some(format!(
+            "No drops have been recorded in the last {} days.",
+            days
+        ));
+    }
+
+    let mut response = format!("**Drops in the last {} days**\n", days);
+    for source in source_stats {
+        let top_items = source
+            .top_items
+            .iter()
+            .map(|item| format!("{} ({} gp)", item.item_name, item.total_gp))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let source_name = source
+            .drop_source
+            .unwrap_or_else(|| "Unknown source".to_string());
+        //Item drops are most of the list so only the other types are called out
+        let source_name = match source.broadcast_type {
+            BroadcastType::ItemDrop => source_name,
+            broadcast_type => format!("{} ({})", source_name, broadcast_type.to_string()),
+        };
+        let line = format!(
+            "**{}** - {} drops, {} gp. Top: {}\n",
+            source_name, source.total_drops, source.total_gp, top_items
+        );
+        if response.len() + line.len() > MAX_RESPONSE_LENGTH {
+            response.push_str("...");
+            break;
+        }
+        response.push_str(&line);
+    }
+    Some(response)
+}
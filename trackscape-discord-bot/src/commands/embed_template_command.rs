@@ -0,0 +1,193 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    ChannelId, CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption, CreateEmbed, CreateMessage,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use tracing::info;
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::embed_templates::{
+    example_broadcast, render_broadcast_embed, EmbedTemplate, TemplateImage, TEMPLATE_PLACEHOLDERS,
+};
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+
+pub fn register() -> CreateCommand {
+    let mut broadcast_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "broadcast",
+        "The broadcast type to change the look of.",
+    );
+    for broadcast_type in BroadcastType::iter() {
+        if let BroadcastType::Unknown = broadcast_type {
+            continue;
+        }
+        broadcast_option = broadcast_option
+            .add_string_choice(broadcast_type.to_string(), broadcast_type.to_slug());
+    }
+
+    CreateCommand::new("embed_template")
+        .description(
+            "Changes how a broadcast type looks and shows a preview. Run with only the type to preview it.",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(broadcast_option.required(true))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "title",
+                "The title. Can use {player}, {item}, {value}, {kc}, {time} and {message}.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "description",
+                "The description. Can use {player}, {item}, {value}, {kc}, {time} and {message}.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "color",
+                "Hex color of the embed like #0000FF.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "icon",
+                "Where the broadcasts icon is shown.",
+            )
+            .add_string_choice("Large image", "image")
+            .add_string_choice("Thumbnail", "thumbnail")
+            .add_string_choice("Hidden", "hidden")
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "reset",
+                "Go back to the default look for this broadcast type.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+    channel_id: ChannelId,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    let mut saved_guild = match saved_guild_query {
+        Ok(saved_guild) => saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id)),
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let mut broadcast_type: Option<BroadcastType> = None;
+    for option in command {
+        if let ("broadcast", CommandDataOptionValue::String(slug)) =
+            (option.name.as_str(), option.value.clone())
+        {
+            broadcast_type = BroadcastType::iter()
+                .into_iter()
+                .find(|broadcast_type| broadcast_type.to_slug() == slug)
+        }
+    }
+    let broadcast_type = match broadcast_type {
+        Some(broadcast_type) => broadcast_type,
+        None => return Some("Invalid broadcast type.".to_string()),
+    };
+
+    let templates = saved_guild
+        .embed_templates
+        .get_or_insert_with(std::collections::HashMap::new);
+    let mut template = templates.get(&broadcast_type).cloned().unwrap_or_default();
+    let mut changed = false;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("title", CommandDataOptionValue::String(title)) => {
+                template.title = Some(title);
+                changed = true;
+            }
+            ("description", CommandDataOptionValue::String(description)) => {
+                template.description = Some(description);
+                changed = true;
+            }
+            ("color", CommandDataOptionValue::String(color)) => {
+                match u32::from_str_radix(color.trim().trim_start_matches('#'), 16) {
+                    Ok(color) if color <= 0xFFFFFF => template.color = Some(color),
+                    _ => return Some(format!("`{}` is not a valid hex color.", color)),
+                }
+                changed = true;
+            }
+            ("icon", CommandDataOptionValue::String(icon)) => {
+                template.image = match icon.as_str() {
+                    "thumbnail" => Some(TemplateImage::Thumbnail),
+                    "hidden" => Some(TemplateImage::Hidden),
+                    _ => Some(TemplateImage::Image),
+                };
+                changed = true;
+            }
+            ("reset", CommandDataOptionValue::Boolean(true)) => {
+                template = EmbedTemplate::default();
+                changed = true;
+            }
+            _ => {}
+        }
+    }
+
+    let rendered_embed =
+        render_broadcast_embed(Some(&template), &example_broadcast(broadcast_type.clone()));
+    let mut preview_embed = CreateEmbed::new()
+        .title(rendered_embed.title)
+        .description(rendered_embed.description)
+        .color(rendered_embed.color);
+    if let Some(image_url) = rendered_embed.image_url {
+        preview_embed = preview_embed.image(image_url);
+    }
+    if let Some(thumbnail_url) = rendered_embed.thumbnail_url {
+        preview_embed = preview_embed.thumbnail(thumbnail_url);
+    }
+
+    let response = format!(
+        "{} template: {}\nPlaceholders: {}",
+        broadcast_type.to_string(),
+        template.to_string(),
+        TEMPLATE_PLACEHOLDERS.join(", ")
+    );
+    if changed {
+        match template == EmbedTemplate::default() {
+            true => templates.remove(&broadcast_type),
+            false => templates.insert(broadcast_type, template),
+        };
+        db.guilds.update_guild(saved_guild).await;
+    }
+
+    let send_message = channel_id
+        .send_message(
+            &ctx.http,
+            CreateMessage::new()
+                .content("Preview with example values:")
+                .embed(preview_embed),
+        )
+        .await;
+    if let Err(error) = send_message {
+        info!("Error sending preview: {}", error);
+        return Some(format!(
+            "{}\nCould not send the preview to this channel.",
+            response
+        ));
+    }
+    Some(response)
+}
@@ -2,6 +2,7 @@ use crate::database::BotMongoDb;
 use serenity::all::{CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand, CreateCommandOption};
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::custom_drop_filter;
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
 
@@ -55,21 +56,18 @@ pub async fn run(
                 let broadcast_type = 
                 BroadcastType::from_string(broadcast_type.replace("_", " "));
             
-                if let Some(ref filter_map) = saved_guild.custom_drop_broadcast_filter {
-                    if let Some(filter_list) = filter_map.get(&broadcast_type) {
-                        Some(format!(
-                            "The current {} filter list is: `{}`",
-                            broadcast_type.to_string(),
-                            filter_list.join(", ")
-                        ))
-                    } else {
-                        Some(format!(
-                            "No filter list found for {}. Please set a new filter list.",
-                            broadcast_type.to_string()
-                        ))
-                    }
+                let rules = saved_guild.broadcast_rules.unwrap_or_default();
+                if let Some(pattern) = custom_drop_filter(&rules, &broadcast_type) {
+                    Some(format!(
+                        "The current {} filter is: `{}`",
+                        broadcast_type.to_string(),
+                        pattern
+                    ))
                 } else {
-                    Some("Error finding filter lists, try again later.".to_string())
+                    Some(format!(
+                        "No filter list found for {}. Please set a new filter list.",
+                        broadcast_type.to_string()
+                    ))
                 }
             } else {
                 Some("Invalid Broadcast Type".to_string())
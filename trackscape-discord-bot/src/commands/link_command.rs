@@ -0,0 +1,159 @@
+use crate::database::BotMongoDb;
+use mongodb::bson::DateTime;
+use rand::Rng;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use std::env;
+use tracing::error;
+use trackscape_discord_shared::database::clan_mates::{ClanMates, PendingDiscordLink};
+use trackscape_discord_shared::discord_api::DiscordApiClient;
+use trackscape_discord_shared::rank_roles::sync_linked_member_rank_roles;
+
+const LINK_CODE_MINUTES: i64 = 10;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("link")
+        .description("Links your RuneScape account to your discord so broadcasts can mention you.")
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::String, "rsn", "Your RuneScape name.")
+                .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "unlink",
+                "Removes the link to this RuneScape account instead.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Boolean,
+                "mentions",
+                "Turns broadcasts mentioning you on or off for an account linked to you.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+    discord_user_id: u64,
+) -> Option<String> {
+    let mut rsn: Option<String> = None;
+    let mut unlink = false;
+    let mut mentions: Option<bool> = None;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("rsn", CommandDataOptionValue::String(name)) => rsn = Some(name),
+            ("unlink", CommandDataOptionValue::Boolean(remove)) => unlink = remove,
+            ("mentions", CommandDataOptionValue::Boolean(mention)) => mentions = Some(mention),
+            _ => {}
+        }
+    }
+    let rsn = match rsn {
+        Some(rsn) => rsn,
+        None => return Some("Please enter your RuneScape name.".to_string()),
+    };
+
+    let mut clan_mate = match db
+        .clan_mates
+        .find_by_current_name(guild_id, rsn.clone())
+        .await
+    {
+        Ok(Some(clan_mate)) => clan_mate,
+        Ok(_) => {
+            return Some(format!(
+                "Could not find {} in this clan. Send a message in clan chat and try again.",
+                rsn
+            ))
+        }
+        Err(_) => {
+            return Some("There was a technical error. Please try again later.".to_string());
+        }
+    };
+
+    if unlink {
+        if clan_mate.discord_user_id != Some(discord_user_id) {
+            return Some(format!("{} is not linked to you.", rsn));
+        }
+        clan_mate.discord_user_id = None;
+        if db.clan_mates.update_clan_mate(clan_mate).await.is_err() {
+            return Some("There was a technical error. Please try again later.".to_string());
+        }
+        sync_rank_roles(db, guild_id, discord_user_id).await;
+        return Some(format!("{} has been unlinked.", rsn));
+    }
+
+    if let Some(mentions) = mentions {
+        if clan_mate.discord_user_id != Some(discord_user_id) {
+            return Some(format!(
+                "{} is not linked to you, link it first to change its mentions.",
+                rsn
+            ));
+        }
+        clan_mate.discord_mentions = Some(mentions);
+        if db.clan_mates.update_clan_mate(clan_mate).await.is_err() {
+            return Some("There was a technical error. Please try again later.".to_string());
+        }
+        return Some(match mentions {
+            true => format!("Broadcasts for {} will mention you.", rsn),
+            false => format!("Broadcasts for {} will no longer mention you.", rsn),
+        });
+    }
+
+    if clan_mate.discord_user_id == Some(discord_user_id) {
+        return Some(format!("{} is already linked to you.", rsn));
+    }
+
+    //Someone else's code can not be replaced until it runs out, or they could be locked out
+    if let Some(pending_link) = &clan_mate.pending_discord_link {
+        if pending_link.discord_user_id != discord_user_id
+            && pending_link.expires_at > DateTime::now()
+        {
+            return Some(format!(
+                "Someone else is already linking {}. Please try again in {} minutes.",
+                rsn, LINK_CODE_MINUTES
+            ));
+        }
+    }
+
+    let code = rand::thread_rng().gen_range(100_000..1_000_000).to_string();
+    clan_mate.pending_discord_link = Some(PendingDiscordLink {
+        discord_user_id,
+        code: code.clone(),
+        expires_at: DateTime::from_millis(
+            DateTime::now().timestamp_millis() + LINK_CODE_MINUTES * 60 * 1000,
+        ),
+        wrong_codes: 0,
+    });
+    match db.clan_mates.update_clan_mate(clan_mate).await {
+        Ok(_) => Some(format!(
+            "Log in as {} and type `!link {}` in clan chat within {} minutes to finish linking.",
+            rsn, code, LINK_CODE_MINUTES
+        )),
+        Err(_) => Some("There was a technical error. Please try again later.".to_string()),
+    }
+}
+
+/// Takes away the rank roles that came from the unlinked clan mate
+async fn sync_rank_roles(db: &BotMongoDb, guild_id: u64, discord_user_id: u64) {
+    let registered_guild = match db.guilds.get_by_guild_id(guild_id).await {
+        Ok(Some(registered_guild)) => registered_guild,
+        _ => return,
+    };
+    let token = env::var("DISCORD_TOKEN").expect("DISCORD_TOKEN not set!");
+    let discord_api_client = DiscordApiClient::new(token);
+    if let Err(e) =
+        sync_linked_member_rank_roles(&registered_guild, db, &discord_api_client, discord_user_id)
+            .await
+    {
+        error!("Failed to sync rank roles after unlinking: {:?}", e);
+    }
+}
@@ -1,20 +1,36 @@
+pub mod add_broadcast_rule_command;
+pub mod broadcast_rules_command;
+pub mod drop_stats_command;
+pub mod embed_template_command;
 pub mod expel_clanmate_command;
 pub mod get_custom_drop_broadcast_filter;
 pub mod get_verification_code;
 pub mod info;
+pub mod link_command;
 pub(crate) mod manually_run_wom_sync_command;
 pub mod name_change_command;
+pub mod pets_command;
+pub mod rank_role_command;
+pub mod rank_role_sync_command;
+pub mod remove_broadcast_rule_command;
 pub mod reset_broadcasts_thresholds;
 pub mod reset_verification_code;
+pub mod role_ping_command;
+pub mod route_command;
+pub mod set_account_type_filter_command;
 pub mod set_broadcast_channel;
 pub mod set_clan_chat_channel;
 pub mod set_clog_max_percentage;
+pub mod set_combat_achievement_min_command;
 pub mod set_custom_drop_broadcast_filter;
 pub mod set_diary_min_command;
+pub mod set_drop_price_source_command;
+pub mod set_drop_rarity_command;
 pub mod set_leagues_broadcast_channel;
 pub mod set_quest_min_command;
 pub mod set_threshold_command;
 pub mod set_wom_id_command;
+pub mod skills_command;
 pub mod stop_leagues_notifications;
 pub mod toggle_broadcasts_command;
 pub mod trackscape_command_trait;
@@ -0,0 +1,152 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use trackscape_discord_shared::database::clan_mate_pets::ClanPetCollection;
+use trackscape_discord_shared::database::clan_mates::{name_compare, ClanMates};
+
+//Discord messages can only be 2000 characters
+const MAX_RESPONSE_LENGTH: usize = 1900;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("pets")
+        .description("Shows the clan's pet collection and who is still missing each pet.")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "player",
+                "Show the pets this clan mate has and the clan's pets they are missing.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::User,
+                "user",
+                "Show the pets of the RuneScape accounts this discord user has linked.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let pet_collection = match db.clan_mate_pets.get_guild_pet_collection(guild_id).await {
+        Ok(pet_collection) => pet_collection,
+        Err(_) => {
+            return Some("There was a technical error. Please try again later.".to_string());
+        }
+    };
+    if pet_collection.is_empty() {
+        return Some("No pets have been recorded for this clan yet.".to_string());
+    }
+
+    let mut players: Vec<String> = Vec::new();
+    for option in command {
+        match option.value.clone() {
+            CommandDataOptionValue::String(player) => players.push(player),
+            CommandDataOptionValue::User(user_id) => {
+                match db
+                    .clan_mates
+                    .find_by_discord_user_id(guild_id, user_id.get())
+                    .await
+                {
+                    Ok(clan_mates) if !clan_mates.is_empty() => players.extend(
+                        clan_mates
+                            .into_iter()
+                            .map(|clan_mate| clan_mate.player_name),
+                    ),
+                    Ok(_) => {
+                        return Some(format!(
+                            "<@{}> has not linked a RuneScape account with /link.",
+                            user_id.get()
+                        ))
+                    }
+                    Err(_) => {
+                        return Some(
+                            "There was a technical error. Please try again later.".to_string(),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let lines: Vec<String> = match players.is_empty() {
+        true => pet_collection
+            .iter()
+            .map(|pet| {
+                let mut owners: Vec<String> = pet
+                    .owners
+                    .iter()
+                    .filter_map(|owner| owner.clan_mate.clone())
+                    .map(|clan_mate| clan_mate.player_name)
+                    .collect();
+                owners.sort();
+                owners.dedup();
+                format!(
+                    "**{}** - Owned by: {} | Missing: {}",
+                    pet.pet_name,
+                    list_or_none(owners),
+                    list_or_none(pet.missing.clone())
+                )
+            })
+            .collect(),
+        false => players
+            .iter()
+            .flat_map(|player| player_pet_lines(&pet_collection, player))
+            .collect(),
+    };
+
+    let mut response = String::new();
+    for line in lines {
+        if response.len() + line.len() > MAX_RESPONSE_LENGTH {
+            response.push_str("...");
+            break;
+        }
+        response.push_str(&line);
+        response.push('\n');
+    }
+    Some(response)
+}
+
+fn player_pet_lines(pet_collection: &[ClanPetCollection], player: &str) -> Vec<String> {
+    let mut owned: Vec<String> = Vec::new();
+    let mut missing: Vec<String> = Vec::new();
+    for pet in pet_collection.iter() {
+        let owner = pet.owners.iter().find(|owner| match &owner.clan_mate {
+            Some(clan_mate) => name_compare(&clan_mate.player_name, player),
+            None => false,
+        });
+        match owner {
+            Some(owner) => owned.push(match owner.count {
+                Some(count) => format!(
+                    "{} at {} {}",
+                    pet.pet_name,
+                    count,
+                    owner.count_type.to_string()
+                ),
+                None => pet.pet_name.clone(),
+            }),
+            None => missing.push(pet.pet_name.clone()),
+        }
+    }
+    vec![
+        format!("**{}'s pets**: {}", player, list_or_none(owned)),
+        format!("**Still missing**: {}", list_or_none(missing)),
+    ]
+}
+
+fn list_or_none(list: Vec<String>) -> String {
+    match list.is_empty() {
+        true => "None".to_string(),
+        false => list.join(", "),
+    }
+}
@@ -0,0 +1,93 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("rank_role")
+        .description("Gives linked clan mates a discord role for their clan rank. Leave out the role to stop.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "rank",
+                "The clan rank title as it shows in game, like Captain.",
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Role,
+                "role",
+                "The discord role clan mates with this rank get.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    let mut saved_guild = match saved_guild_query {
+        Ok(saved_guild) => saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id)),
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let mut rank: Option<String> = None;
+    let mut role: Option<u64> = None;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("rank", CommandDataOptionValue::String(rank_title)) => {
+                rank = Some(rank_title.trim().to_string())
+            }
+            ("role", CommandDataOptionValue::Role(role_id)) => role = Some(role_id.get()),
+            _ => {}
+        }
+    }
+    let rank = match rank {
+        Some(rank) if !rank.is_empty() => rank,
+        _ => return Some("Please enter a clan rank.".to_string()),
+    };
+
+    let rank_roles = saved_guild
+        .rank_roles
+        .get_or_insert_with(std::collections::HashMap::new);
+    //Rank titles are matched without case, so replace any existing spelling
+    rank_roles.retain(|rank_title, _| rank_title.to_lowercase() != rank.to_lowercase());
+    let mut response = match role {
+        Some(role_id) => {
+            rank_roles.insert(rank.clone(), role_id);
+            format!("{} will get <@&{}>.", rank, role_id)
+        }
+        None => format!("{} no longer gets a role.", rank),
+    };
+
+    let mut mapping: Vec<String> = rank_roles
+        .iter()
+        .map(|(rank_title, role_id)| format!("{}: <@&{}>", rank_title, role_id))
+        .collect();
+    mapping.sort();
+    if !mapping.is_empty() {
+        response.push_str(&format!("\nRank roles:\n{}", mapping.join("\n")));
+    }
+    if !saved_guild.rank_role_sync_enabled.unwrap_or(false) {
+        response.push_str(
+            "\nRoles are not synced yet. Use /rank_role_sync to preview the changes and enable it.",
+        );
+    }
+
+    db.guilds.update_guild(saved_guild).await;
+    Some(response)
+}
@@ -0,0 +1,107 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use std::env;
+use tracing::error;
+use trackscape_discord_shared::discord_api::DiscordApiClient;
+use trackscape_discord_shared::rank_roles::reconcile_guild_rank_roles;
+
+//Discord messages can only be 2000 characters
+const MAX_RESPONSE_LENGTH: usize = 1900;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("rank_role_sync")
+        .description(
+            "Previews the role changes the rank roles would make, or turns syncing on or off.",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "action",
+                "Preview lists the changes without making them.",
+            )
+            .add_string_choice("Preview", "preview")
+            .add_string_choice("Enable", "enable")
+            .add_string_choice("Disable", "disable")
+            .required(true),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let mut saved_guild = match db.guilds.get_by_guild_id(guild_id).await {
+        Ok(Some(saved_guild)) => saved_guild,
+        _ => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let action = match command.get(0) {
+        Some(option) => match option.value.clone() {
+            CommandDataOptionValue::String(action) => action,
+            _ => return Some("Invalid action.".to_string()),
+        },
+        None => return Some("Invalid action.".to_string()),
+    };
+
+    match action.as_str() {
+        "enable" => {
+            if !saved_guild
+                .rank_roles
+                .as_ref()
+                .is_some_and(|rank_roles| !rank_roles.is_empty())
+            {
+                return Some("Add a rank role with /rank_role first.".to_string());
+            }
+            saved_guild.rank_role_sync_enabled = Some(true);
+            db.guilds.update_guild(saved_guild).await;
+            Some("Rank roles will now be synced. Linked clan mates get their roles when their rank changes and every hour.".to_string())
+        }
+        "disable" => {
+            saved_guild.rank_role_sync_enabled = Some(false);
+            db.guilds.update_guild(saved_guild).await;
+            Some("Rank roles will no longer be synced.".to_string())
+        }
+        _ => {
+            let token = env::var("DISCORD_TOKEN").expect("DISCORD_TOKEN not set!");
+            let discord_api_client = DiscordApiClient::new(token);
+            let role_changes =
+                match reconcile_guild_rank_roles(&saved_guild, db, &discord_api_client, true).await
+                {
+                    Ok(role_changes) => role_changes,
+                    Err(e) => {
+                        error!("Error previewing rank roles: {:?}", e);
+                        return Some(
+                            "There was a technical error. Please try again later.".to_string(),
+                        );
+                    }
+                };
+            if role_changes.is_empty() {
+                return Some("No role changes are needed.".to_string());
+            }
+
+            let mut response = format!("{} members would change:\n", role_changes.len());
+            for role_change in role_changes {
+                let line = role_change.to_string();
+                if response.len() + line.len() > MAX_RESPONSE_LENGTH {
+                    response.push_str("...");
+                    break;
+                }
+                response.push_str(&line);
+                response.push('\n');
+            }
+            Some(response)
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("remove_broadcast_rule")
+        .description("Removes one of your broadcast rules.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "rule",
+                "Number of the rule from /broadcast_rules.",
+            )
+            .min_int_value(1)
+            .required(true),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let mut saved_guild = match db.guilds.get_by_guild_id(guild_id).await {
+        Ok(Some(saved_guild)) => saved_guild,
+        _ => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+    let rule_number = match command.get(0).map(|option| option.value.clone()) {
+        Some(CommandDataOptionValue::Integer(rule_number)) => rule_number,
+        _ => return Some("Invalid rule number.".to_string()),
+    };
+
+    let rules = saved_guild.broadcast_rules.get_or_insert_with(Vec::new);
+    if rule_number < 1 || rule_number as usize > rules.len() {
+        return Some(format!("There is no rule {}.", rule_number));
+    }
+    let removed_rule = rules.remove(rule_number as usize - 1);
+    db.guilds.update_guild(saved_guild).await;
+    Some(format!("Removed rule: {}", removed_rule.to_string()))
+}
@@ -5,6 +5,7 @@ use serenity::all::{
 use serenity::builder::CreateCommand;
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::RuleSetting;
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType::ItemDrop;
@@ -25,6 +26,10 @@ pub fn register() -> CreateCommand {
             .add_string_choice(
                 BroadcastType::Pk.to_string(),
                  BroadcastType::Pk.to_slug())
+            .add_string_choice(
+                BroadcastType::LootKey.to_string(),
+                BroadcastType::LootKey.to_slug(),
+            )
             .add_string_choice(
                 BroadcastType::Quest.to_string(),
                 BroadcastType::Quest.to_slug(),
@@ -33,6 +38,10 @@ pub fn register() -> CreateCommand {
                 BroadcastType::Diary.to_string(),
                 BroadcastType::Diary.to_slug(),
             )
+            .add_string_choice(
+                BroadcastType::CombatTask.to_string(),
+                BroadcastType::CombatTask.to_slug(),
+            )
             .add_string_choice(
                 BroadcastType::CollectionLog.to_string(),
                 BroadcastType::CollectionLog.to_slug(),
@@ -57,25 +66,33 @@ pub async fn run(
                 broadcast_type.clone().value
             {
                 let broadcast_type = BroadcastType::from_string(broadcast_type.replace("_", " "));
-                match broadcast_type {
-                    BroadcastType::ItemDrop => {
-                        saved_guild.drop_price_threshold = None;
-                    }
-                    BroadcastType::Pk => {
-                        saved_guild.pk_value_threshold = None;
-                    }
-                    BroadcastType::Quest => {
-                        saved_guild.min_quest_difficulty = None;
+                let reset: fn(&RuleSetting) -> bool = match broadcast_type {
+                    BroadcastType::ItemDrop => |setting| {
+                        matches!(
+                            setting,
+                            RuleSetting::DropPriceThreshold
+                                | RuleSetting::AccountTypeDropPriceThreshold(_)
+                                | RuleSetting::DropRarityThreshold
+                        )
+                    },
+                    BroadcastType::Pk => |setting| setting == &RuleSetting::PkValueThreshold,
+                    BroadcastType::LootKey => {
+                        |setting| setting == &RuleSetting::LootKeyValueThreshold
                     }
-                    BroadcastType::Diary => {
-                        saved_guild.min_diary_tier = None;
+                    BroadcastType::Quest => |setting| setting == &RuleSetting::MinQuestDifficulty,
+                    BroadcastType::Diary => |setting| setting == &RuleSetting::MinDiaryTier,
+                    BroadcastType::CombatTask | BroadcastType::CombatAchievementTier => {
+                        |setting| setting == &RuleSetting::MinCombatAchievementTier
                     }
                     BroadcastType::CollectionLog => {
-                        saved_guild.collection_log_max_percentage = None;
+                        |setting| setting == &RuleSetting::CollectionLogMaxPercentage
                     }
                     _ => {
                         return Some("Invalid broadcast type.".to_string());
                     }
+                };
+                if let Some(ref mut rules) = saved_guild.broadcast_rules {
+                    rules.retain(|rule| !rule.setting.as_ref().is_some_and(reset));
                 }
                 db.guilds.update_guild(saved_guild).await;
                 Some(format!(
@@ -0,0 +1,143 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::RuleCondition;
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+use trackscape_discord_shared::role_pings::RolePing;
+
+pub fn register() -> CreateCommand {
+    let mut broadcast_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "broadcast",
+        "The broadcast type to mention the role on.",
+    );
+    for broadcast_type in BroadcastType::iter() {
+        if let BroadcastType::Unknown = broadcast_type {
+            continue;
+        }
+        broadcast_option = broadcast_option
+            .add_string_choice(broadcast_type.to_string(), broadcast_type.to_slug());
+    }
+
+    CreateCommand::new("role_ping")
+        .description("Mentions a role on a broadcast type. Leave out the role to stop mentioning it.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(broadcast_option.required(true))
+        .add_option(
+            CreateCommandOption::new(CommandOptionType::Role, "role", "The role to mention.")
+                .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "min_value",
+                "Only mention the role for broadcasts worth at least this much gp.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Number,
+                "rarer_than_percentage",
+                "Only mention the role for collection log items less than this % of players have.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "cooldown_minutes",
+                "Minutes before the role can be mentioned again for this broadcast type. Default 5.",
+            )
+            .min_int_value(0)
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    let mut saved_guild = match saved_guild_query {
+        Ok(saved_guild) => saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id)),
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let mut broadcast_type: Option<BroadcastType> = None;
+    let mut role_id: Option<u64> = None;
+    let mut conditions: Vec<RuleCondition> = Vec::new();
+    let mut cooldown_seconds: Option<u64> = None;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("broadcast", CommandDataOptionValue::String(slug)) => {
+                broadcast_type = BroadcastType::iter()
+                    .into_iter()
+                    .find(|broadcast_type| broadcast_type.to_slug() == slug)
+            }
+            ("role", CommandDataOptionValue::Role(role)) => role_id = Some(role.get()),
+            ("min_value", CommandDataOptionValue::Integer(value)) => {
+                conditions.push(RuleCondition::ValueBetween {
+                    min: Some(value),
+                    max: None,
+                })
+            }
+            ("rarer_than_percentage", CommandDataOptionValue::Number(percentage)) => {
+                conditions.push(RuleCondition::ClogPercentageBelow(percentage))
+            }
+            ("cooldown_minutes", CommandDataOptionValue::Integer(minutes)) => {
+                cooldown_seconds = Some(minutes.max(0) as u64 * 60)
+            }
+            _ => {}
+        }
+    }
+    let broadcast_type = match broadcast_type {
+        Some(broadcast_type) => broadcast_type,
+        None => return Some("Invalid broadcast type.".to_string()),
+    };
+
+    let role_pings = saved_guild
+        .role_pings
+        .get_or_insert_with(std::collections::HashMap::new);
+    let response = match role_id {
+        //The @everyone role has the same id as the server
+        Some(role_id) if role_id == guild_id => {
+            return Some("Broadcasts can not mention @everyone.".to_string());
+        }
+        Some(role_id) => {
+            let role_ping = RolePing {
+                role_id,
+                conditions,
+                cooldown_seconds,
+            };
+            let response = format!(
+                "{} broadcasts will mention {}.",
+                broadcast_type.to_string(),
+                role_ping.to_string()
+            );
+            role_pings.insert(broadcast_type, role_ping);
+            response
+        }
+        None => {
+            role_pings.remove(&broadcast_type);
+            format!(
+                "{} broadcasts will no longer mention a role.",
+                broadcast_type.to_string()
+            )
+        }
+    };
+
+    db.guilds.update_guild(saved_guild).await;
+    Some(response)
+}
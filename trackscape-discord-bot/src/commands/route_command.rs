@@ -0,0 +1,111 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::channel::ChannelType;
+use serenity::model::prelude::Permissions;
+use tracing::error;
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+
+pub fn register() -> CreateCommand {
+    let mut broadcast_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "broadcast",
+        "The broadcast type to send to its own channel.",
+    );
+    for broadcast_type in BroadcastType::iter() {
+        if let BroadcastType::Unknown = broadcast_type {
+            continue;
+        }
+        broadcast_option = broadcast_option
+            .add_string_choice(broadcast_type.to_string(), broadcast_type.to_slug());
+    }
+
+    CreateCommand::new("route")
+        .description(
+            "Sends a broadcast type to its own channel. Leave out the channel to use the broadcast channel again.",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(broadcast_option.required(true))
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Channel,
+                "channel",
+                "The discord channel to send this broadcast type to.",
+            )
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    let mut saved_guild = match saved_guild_query {
+        Ok(saved_guild) => saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id)),
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    };
+
+    let mut broadcast_type: Option<BroadcastType> = None;
+    let mut channel = None;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("broadcast", CommandDataOptionValue::String(slug)) => {
+                broadcast_type = BroadcastType::iter()
+                    .into_iter()
+                    .find(|broadcast_type| broadcast_type.to_slug() == slug)
+            }
+            ("channel", CommandDataOptionValue::Channel(channel_id)) => channel = Some(channel_id),
+            _ => {}
+        }
+    }
+    let broadcast_type = match broadcast_type {
+        Some(broadcast_type) => broadcast_type,
+        None => return Some("Invalid broadcast type.".to_string()),
+    };
+
+    let broadcast_type_channels = saved_guild
+        .broadcast_type_channels
+        .get_or_insert_with(std::collections::HashMap::new);
+    let response = match channel {
+        Some(channel) => {
+            let guild_channel = match channel.to_channel(&ctx).await {
+                Ok(actual_channel) => actual_channel.guild(),
+                Err(e) => {
+                    error!("Error getting channel: {:?}", e);
+                    return Some("Error getting channel".to_string());
+                }
+            };
+            match guild_channel {
+                Some(guild_channel) if guild_channel.kind == ChannelType::Text => {}
+                _ => return Some("Please select a text channel.".to_string()),
+            }
+            broadcast_type_channels.insert(broadcast_type.clone(), channel.get());
+            format!(
+                "{} broadcasts will be sent to <#{}>.",
+                broadcast_type.to_string(),
+                channel.get()
+            )
+        }
+        None => {
+            broadcast_type_channels.remove(&broadcast_type);
+            format!(
+                "{} broadcasts will be sent to the broadcast channel.",
+                broadcast_type.to_string()
+            )
+        }
+    };
+
+    db.guilds.update_guild(saved_guild).await;
+    Some(response)
+}
@@ -0,0 +1,157 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{
+    account_type_filter_rules, allowed_account_types, RuleSetting,
+};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    AccountType, BroadcastType,
+};
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("account_type_filter")
+        .description(
+            "Toggles an account type for a broadcast. Once set only those account types are sent.",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "broadcast",
+                "Broadcast type to filter by account type.",
+            )
+            .add_string_choice(
+                BroadcastType::ItemDrop.to_string(),
+                BroadcastType::ItemDrop.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::RaidDrop.to_string(),
+                BroadcastType::RaidDrop.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::ClueItem.to_string(),
+                BroadcastType::ClueItem.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::PetDrop.to_string(),
+                BroadcastType::PetDrop.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::CollectionLog.to_string(),
+                BroadcastType::CollectionLog.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::PersonalBest.to_string(),
+                BroadcastType::PersonalBest.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::LevelMilestone.to_string(),
+                BroadcastType::LevelMilestone.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::Quest.to_string(),
+                BroadcastType::Quest.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::Diary.to_string(),
+                BroadcastType::Diary.to_slug(),
+            )
+            .required(true),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "account_type",
+                "Account type to add or remove for the broadcast.",
+            )
+            .add_string_choice(
+                AccountType::Normal.to_string(),
+                AccountType::Normal.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::Ironman.to_string(),
+                AccountType::Ironman.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::UltimateIronman.to_string(),
+                AccountType::UltimateIronman.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::HardcoreIronman.to_string(),
+                AccountType::HardcoreIronman.to_slug(),
+            )
+            .required(true),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    match saved_guild_query {
+        Ok(saved_guild) => {
+            let mut saved_guild = saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id));
+            let broadcast_type = command.get(0).expect("Expected a broadcast type option");
+            let account_type = command.get(1).expect("Expected an account type option");
+
+            return if let CommandDataOptionValue::String(broadcast_type) =
+                broadcast_type.clone().value
+            {
+                if let CommandDataOptionValue::String(account_type) = account_type.clone().value {
+                    let broadcast_type =
+                        BroadcastType::from_string(broadcast_type.replace("_", " "));
+                    let account_type = AccountType::from_string(account_type);
+
+                    let mut allowed_account_types = allowed_account_types(
+                        &saved_guild.broadcast_rules.clone().unwrap_or_default(),
+                        &broadcast_type,
+                    );
+                    if allowed_account_types.contains(&account_type) {
+                        allowed_account_types.retain(|x| x != &account_type);
+                    } else {
+                        allowed_account_types.push(account_type);
+                    }
+
+                    let response = match allowed_account_types.is_empty() {
+                        true => format!(
+                            "{} broadcasts are now sent for every account type.",
+                            broadcast_type.to_string()
+                        ),
+                        false => format!(
+                            "{} broadcasts are now only sent for: {}.",
+                            broadcast_type.to_string(),
+                            allowed_account_types
+                                .iter()
+                                .map(|x| x.to_string())
+                                .collect::<Vec<String>>()
+                                .join(", ")
+                        ),
+                    };
+                    saved_guild.set_setting_rules(
+                        RuleSetting::AccountTypeFilter(broadcast_type.clone()),
+                        account_type_filter_rules(broadcast_type, allowed_account_types),
+                    );
+                    db.guilds.update_guild(saved_guild).await;
+                    Some(response)
+                } else {
+                    Some("Invalid account type.".to_string())
+                }
+            } else {
+                Some("Invalid broadcast type.".to_string())
+            };
+        }
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    }
+}
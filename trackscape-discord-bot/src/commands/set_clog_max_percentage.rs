@@ -5,6 +5,9 @@ use serenity::all::{
 use serenity::builder::CreateCommand;
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{
+    collection_log_max_percentage_rules, RuleSetting,
+};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 
 pub fn register() -> CreateCommand {
@@ -38,11 +41,15 @@ pub async fn run(
             return if let CommandDataOptionValue::String(percentage) =
                 possible_percentage.clone().value
             {
-                saved_guild.collection_log_max_percentage = match percentage.parse::<f64>() {
-                    Ok(value) if value <= 100.0 && value >= 0.0 => Some(value),
+                let max_percentage = match percentage.parse::<f64>() {
+                    Ok(value) if value <= 100.0 && value >= 0.0 => value,
                     Ok(_) => return Some("Percentage must be between 0 and 100.".to_string()),
                     Err(_) => return Some("Invalid percentage value. Please provide a valid number.".to_string()),
                 };
+                saved_guild.set_setting_rules(
+                    RuleSetting::CollectionLogMaxPercentage,
+                    collection_log_max_percentage_rules(max_percentage),
+                );
                 db.guilds.update_guild(saved_guild).await;
                 Some("Successfully updated the Collection Log max percentage.".to_string())
             } else {
@@ -0,0 +1,82 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommandOption,
+};
+use serenity::builder::CreateCommand;
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{min_tier_rules, RuleSetting};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::CombatAchievementTier;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("combat_achievements")
+        .description(
+            "Sets min combat achievement tier to trigger a broadcast. Anything below will not send",
+        )
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "tier",
+                "Min combat achievement tier to send a broadcast.",
+            )
+            .add_string_choice(
+                CombatAchievementTier::Easy.to_string(),
+                CombatAchievementTier::Easy.to_string(),
+            )
+            .add_string_choice(
+                CombatAchievementTier::Medium.to_string(),
+                CombatAchievementTier::Medium.to_string(),
+            )
+            .add_string_choice(
+                CombatAchievementTier::Hard.to_string(),
+                CombatAchievementTier::Hard.to_string(),
+            )
+            .add_string_choice(
+                CombatAchievementTier::Elite.to_string(),
+                CombatAchievementTier::Elite.to_string(),
+            )
+            .add_string_choice(
+                CombatAchievementTier::Master.to_string(),
+                CombatAchievementTier::Master.to_string(),
+            )
+            .add_string_choice(
+                CombatAchievementTier::Grandmaster.to_string(),
+                CombatAchievementTier::Grandmaster.to_string(),
+            )
+            .required(true),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    match saved_guild_query {
+        Ok(saved_guild) => {
+            let mut saved_guild = saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id));
+            let possible_tier = command.get(0).expect("Expected combat achievement tier option");
+
+            return if let CommandDataOptionValue::String(tier) = possible_tier.clone().value {
+                let min_tier = CombatAchievementTier::from_string(tier.clone());
+                saved_guild.set_setting_rules(
+                    RuleSetting::MinCombatAchievementTier,
+                    min_tier_rules(RuleSetting::MinCombatAchievementTier, min_tier.ranking()),
+                );
+                db.guilds.update_guild(saved_guild).await;
+                Some("Successfully updated min combat achievement tier to broadcast.".to_string())
+            } else {
+                Some("Invalid option.".to_string())
+            };
+        }
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    }
+}
@@ -5,6 +5,7 @@ use serenity::all::{
 };
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{custom_drop_filter_rules, RuleSetting};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
 
@@ -22,6 +23,18 @@ pub fn register() -> CreateCommand {
                 BroadcastType::ItemDrop.to_string(),
                 BroadcastType::ItemDrop.to_slug(),
             )
+            .add_string_choice(
+                BroadcastType::RaidDrop.to_string(),
+                BroadcastType::RaidDrop.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::ClueItem.to_string(),
+                BroadcastType::ClueItem.to_slug(),
+            )
+            .add_string_choice(
+                BroadcastType::CollectionLog.to_string(),
+                BroadcastType::CollectionLog.to_slug(),
+            )
             .add_string_choice(
                 BroadcastType::PetDrop.to_string(),
                 BroadcastType::PetDrop.to_slug(),
@@ -75,13 +88,10 @@ pub async fn run(
 
                     let filter_list: Vec<String> =
                         filter.split(',').map(|s| s.trim().to_string()).collect();
-                    if let Some(ref mut filter_map) = saved_guild.custom_drop_broadcast_filter {
-                        filter_map.insert(broadcast_type, filter_list.clone());
-                    } else {
-                        let mut new_filter_map = std::collections::HashMap::new();
-                        new_filter_map.insert(broadcast_type, filter_list.clone());
-                        saved_guild.custom_drop_broadcast_filter = Some(new_filter_map);
-                    }
+                    saved_guild.set_setting_rules(
+                        RuleSetting::CustomDropFilter(broadcast_type.clone()),
+                        custom_drop_filter_rules(broadcast_type, &filter_list),
+                    );
                     db.guilds.update_guild(saved_guild).await;
                     None
                 } else {
@@ -5,6 +5,7 @@ use serenity::all::{
 use serenity::builder::CreateCommand;
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{min_tier_rules, RuleSetting};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::DiaryTier;
 
@@ -41,8 +42,11 @@ pub async fn run(
             return if let CommandDataOptionValue::String(diary_tier) =
                 possible_diary_tier.clone().value
             {
-                saved_guild.min_diary_tier =
-                    Some(DiaryTier::from_string(diary_tier.clone().to_string()));
+                let min_diary_tier = DiaryTier::from_string(diary_tier.clone().to_string());
+                saved_guild.set_setting_rules(
+                    RuleSetting::MinDiaryTier,
+                    min_tier_rules(RuleSetting::MinDiaryTier, min_diary_tier.ranking()),
+                );
                 db.guilds.update_guild(saved_guild).await;
                 Some("Successfully updated min diary to broadcast.".to_string())
             } else {
@@ -0,0 +1,61 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommandOption,
+};
+use serenity::builder::CreateCommand;
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::ge_api::ge_api::DropPriceSource;
+
+pub fn register() -> CreateCommand {
+    let mut source_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "source",
+        "Price the drop threshold is checked against.",
+    );
+    for source in DropPriceSource::iter() {
+        source_option = source_option.add_string_choice(source.to_string(), source.to_slug());
+    }
+
+    CreateCommand::new("set_drop_price_source")
+        .description("Choose which price drop thresholds use. Drops without that price use the broadcast value.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(source_option.required(true))
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    match saved_guild_query {
+        Ok(saved_guild) => {
+            let mut saved_guild = saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id));
+            let possible_source = command.get(0).expect("Expected source option");
+
+            return if let CommandDataOptionValue::String(slug) = possible_source.clone().value {
+                let source = match DropPriceSource::from_slug(&slug) {
+                    Some(source) => source,
+                    None => return Some("Invalid price source.".to_string()),
+                };
+                let response = format!(
+                    "Drop thresholds will now be checked against the {} price.",
+                    source.to_string()
+                );
+                saved_guild.drop_price_source = Some(source);
+                db.guilds.update_guild(saved_guild).await;
+                Some(response)
+            } else {
+                Some("Invalid price source.".to_string())
+            };
+        }
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    }
+}
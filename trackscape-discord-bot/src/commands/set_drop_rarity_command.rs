@@ -0,0 +1,62 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommandOption,
+};
+use serenity::builder::CreateCommand;
+use serenity::client::Context;
+use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{drop_rarity_rules, RuleSetting};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::wiki_api::wiki_api::format_drop_rate;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("set_drop_rarity")
+        .description("Always send item and clue drops rarer than 1/N on the wiki, and block more common ones.")
+        .default_member_permissions(Permissions::MANAGE_GUILD)
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "one_in",
+                "The N in the 1/N drop rate, like 512. Drops without a known rate use the threshold.",
+            )
+            .min_int_value(1)
+            .required(true),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let saved_guild_query = db.guilds.get_by_guild_id(guild_id).await;
+    match saved_guild_query {
+        Ok(saved_guild) => {
+            let mut saved_guild = saved_guild.unwrap_or(RegisteredGuildModel::new(guild_id));
+            let possible_one_in = command.get(0).expect("Expected one_in option");
+
+            return if let CommandDataOptionValue::Integer(one_in) = possible_one_in.clone().value {
+                if one_in < 1 {
+                    return Some("The drop rate must be at least 1/1.".to_string());
+                }
+                saved_guild.set_setting_rules(
+                    RuleSetting::DropRarityThreshold,
+                    drop_rarity_rules(one_in as f64),
+                );
+                db.guilds.update_guild(saved_guild).await;
+                Some(format!(
+                    "Item and clue drops rarer than {} will always be sent, more common drops will not.",
+                    format_drop_rate(one_in as f64)
+                ))
+            } else {
+                Some("Invalid drop rate.".to_string())
+            };
+        }
+        Err(_) => {
+            return Some(
+                "No saved guild was found. Please try adding and removing the bot".to_string(),
+            );
+        }
+    }
+}
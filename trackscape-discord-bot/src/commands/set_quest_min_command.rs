@@ -5,6 +5,7 @@ use serenity::all::{
 use serenity::builder::CreateCommand;
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{min_tier_rules, RuleSetting};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::QuestDifficulty;
 
@@ -59,9 +60,15 @@ pub async fn run(
             return if let CommandDataOptionValue::String(quest_difficulty) =
                 possible_broadcast_type.clone().value
             {
-                saved_guild.min_quest_difficulty = Some(QuestDifficulty::from_string(
-                    quest_difficulty.clone().to_string(),
-                ));
+                let min_quest_difficulty =
+                    QuestDifficulty::from_string(quest_difficulty.clone().to_string());
+                saved_guild.set_setting_rules(
+                    RuleSetting::MinQuestDifficulty,
+                    min_tier_rules(
+                        RuleSetting::MinQuestDifficulty,
+                        min_quest_difficulty.ranking(),
+                    ),
+                );
                 db.guilds.update_guild(saved_guild).await;
                 Some("Successfully updated min quest difficulty to broadcast.".to_string())
             } else {
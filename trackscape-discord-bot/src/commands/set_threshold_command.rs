@@ -5,7 +5,9 @@ use serenity::all::{
 };
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{value_threshold_rules, RuleSetting};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::AccountType;
 
 pub fn register() -> CreateCommand {
     CreateCommand::new("threshold")
@@ -19,6 +21,7 @@ pub fn register() -> CreateCommand {
             )
             .add_string_choice("Item Drops", "item_drop")
             .add_string_choice("PK Loot", "pk_loot")
+            .add_string_choice("Loot Keys", "loot_key")
             .required(true),
         )
         .add_option(
@@ -29,6 +32,30 @@ pub fn register() -> CreateCommand {
             )
             .required(true),
         )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "account_type",
+                "Only use this item drop threshold for clan mates of this account type.",
+            )
+            .add_string_choice(
+                AccountType::Normal.to_string(),
+                AccountType::Normal.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::Ironman.to_string(),
+                AccountType::Ironman.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::UltimateIronman.to_string(),
+                AccountType::UltimateIronman.to_slug(),
+            )
+            .add_string_choice(
+                AccountType::HardcoreIronman.to_string(),
+                AccountType::HardcoreIronman.to_slug(),
+            )
+            .required(false),
+        )
 }
 
 pub async fn run(
@@ -45,24 +72,43 @@ pub async fn run(
 
             let threshold = command.get(1).expect("Expected threshold option");
 
+            let possible_account_type = match command.get(2) {
+                Some(account_type) => match account_type.clone().value {
+                    CommandDataOptionValue::String(account_type) => {
+                        Some(AccountType::from_string(account_type))
+                    }
+                    _ => None,
+                },
+                None => None,
+            };
+
             return if let CommandDataOptionValue::String(broadcast_type) =
                 broadcast_type.clone().value
             {
                 if let CommandDataOptionValue::Integer(threshold) = threshold.value {
-                    match broadcast_type.as_str() {
-                        "item_drop" => {
-                            saved_guild.drop_price_threshold = Some(threshold);
-                            db.guilds.update_guild(saved_guild).await;
-                            None
-                        }
-                        "pk_loot" => {
-                            //TODO: Implement
-                            saved_guild.pk_value_threshold = Some(threshold);
-                            db.guilds.update_guild(saved_guild).await;
-                            None
+                    let setting = match broadcast_type.as_str() {
+                        "item_drop" => match possible_account_type {
+                            Some(account_type) => {
+                                RuleSetting::AccountTypeDropPriceThreshold(account_type)
+                            }
+                            None => RuleSetting::DropPriceThreshold,
+                        },
+                        _ if possible_account_type.is_some() => {
+                            return Some(
+                                "Account type thresholds can only be set for item drops."
+                                    .to_string(),
+                            )
                         }
-                        _ => Some("Invalid broadcast type.".to_string()),
-                    }
+                        "pk_loot" => RuleSetting::PkValueThreshold,
+                        "loot_key" => RuleSetting::LootKeyValueThreshold,
+                        _ => return Some("Invalid broadcast type.".to_string()),
+                    };
+                    saved_guild.set_setting_rules(
+                        setting.clone(),
+                        value_threshold_rules(setting, threshold),
+                    );
+                    db.guilds.update_guild(saved_guild).await;
+                    None
                 } else {
                     Some("Invalid threshold.".to_string())
                 }
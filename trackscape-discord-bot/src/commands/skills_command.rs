@@ -0,0 +1,124 @@
+use crate::database::BotMongoDb;
+use serenity::all::{
+    CommandDataOption, CommandDataOptionValue, CommandOptionType, CreateCommand,
+    CreateCommandOption,
+};
+use serenity::client::Context;
+use trackscape_discord_shared::database::clan_mate_skills::{skill_name, ClanMateSkillModel};
+
+//Discord messages can only be 2000 characters
+const MAX_RESPONSE_LENGTH: usize = 1900;
+
+pub fn register() -> CreateCommand {
+    CreateCommand::new("skills")
+        .description("Shows who has a level in a skill, or the clan mates closest to max.")
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::String,
+                "skill",
+                "The skill to check like Fishing. Leave empty for the clan mates closest to max.",
+            )
+            .required(false),
+        )
+        .add_option(
+            CreateCommandOption::new(
+                CommandOptionType::Integer,
+                "level",
+                "Only show clan mates with at least this level in the skill. Defaults to 99.",
+            )
+            .min_int_value(1)
+            .required(false),
+        )
+}
+
+pub async fn run(
+    command: &Vec<CommandDataOption>,
+    _ctx: &Context,
+    db: &BotMongoDb,
+    guild_id: u64,
+) -> Option<String> {
+    let mut skill: Option<String> = None;
+    let mut level: i64 = 99;
+    for option in command {
+        match (option.name.as_str(), option.value.clone()) {
+            ("skill", CommandDataOptionValue::String(value)) => skill = Some(skill_name(&value)),
+            ("level", CommandDataOptionValue::Integer(value)) => level = value,
+            _ => {}
+        }
+    }
+
+    let lines: Vec<String> = match skill {
+        Some(skill) => {
+            let skills = match db
+                .clan_mate_skills
+                .get_skill_leaderboard(guild_id, &skill, Some(level))
+                .await
+            {
+                Ok(skills) => skills,
+                Err(_) => {
+                    return Some(
+                        "There was a technical error. Please try again later.".to_string(),
+                    );
+                }
+            };
+            if skills.is_empty() {
+                return Some(format!(
+                    "No clan mates are known to have {} {} yet.",
+                    level, skill
+                ));
+            }
+            let mut lines = vec![format!("**Clan mates with {} {}**", level, skill)];
+            lines.extend(skills.iter().map(skill_line));
+            lines
+        }
+        None => {
+            let total_levels = match db.clan_mate_skills.get_closest_to_max(guild_id).await {
+                Ok(total_levels) => total_levels,
+                Err(_) => {
+                    return Some(
+                        "There was a technical error. Please try again later.".to_string(),
+                    );
+                }
+            };
+            if total_levels.is_empty() {
+                return Some("No total levels have been recorded for this clan yet.".to_string());
+            }
+            let mut lines = vec!["**Clan mates closest to max**".to_string()];
+            lines.extend(total_levels.iter().map(|total_level| {
+                let mut line = skill_line(total_level);
+                if total_level.maxed {
+                    line.push_str(" - Maxed");
+                }
+                line
+            }));
+            lines
+        }
+    };
+
+    let mut response = String::new();
+    for line in lines {
+        if response.len() + line.len() > MAX_RESPONSE_LENGTH {
+            response.push_str("...");
+            break;
+        }
+        response.push_str(&line);
+        response.push('\n');
+    }
+    Some(response)
+}
+
+fn skill_line(skill: &ClanMateSkillModel) -> String {
+    let player_name = skill
+        .clan_mate
+        .as_ref()
+        .map(|clan_mate| clan_mate.player_name.clone())
+        .unwrap_or_default();
+    let level = skill
+        .level
+        .map(|level| level.to_string())
+        .unwrap_or("?".to_string());
+    match skill.xp {
+        Some(xp) => format!("{} - Level {} ({} XP)", player_name, level, xp),
+        None => format!("{} - Level {}", player_name, level),
+    }
+}
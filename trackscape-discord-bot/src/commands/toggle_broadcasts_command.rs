@@ -5,108 +5,30 @@ use serenity::all::{
 use serenity::builder::CreateCommand;
 use serenity::client::Context;
 use serenity::model::prelude::Permissions;
+use trackscape_discord_shared::broadcast_rules::{
+    disallowed_broadcast_types, toggle_rules, RuleSetting,
+};
 use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
 use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
-use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType::ItemDrop;
 
 pub fn register() -> CreateCommand {
+    let mut broadcast_option = CreateCommandOption::new(
+        CommandOptionType::String,
+        "broadcast",
+        "Broadcast type to toggle on or off.",
+    );
+    for broadcast_type in BroadcastType::iter()
+        .into_iter()
+        .filter(|broadcast_type| broadcast_type.is_toggleable())
+    {
+        broadcast_option = broadcast_option
+            .add_string_choice(broadcast_type.to_string(), broadcast_type.to_slug());
+    }
+
     CreateCommand::new("toggle")
         .description("Turns on or off a broadcast type to be sent.")
         .default_member_permissions(Permissions::MANAGE_GUILD)
-        .add_option(
-            CreateCommandOption::new(
-                CommandOptionType::String,
-                "broadcast",
-                "Broadcast type to toggle on or off.",
-            )
-            .add_string_choice(BroadcastType::ItemDrop.to_string(), ItemDrop.to_slug())
-            .add_string_choice(BroadcastType::Pk.to_string(), BroadcastType::Pk.to_slug())
-            .add_string_choice(
-                BroadcastType::Quest.to_string(),
-                BroadcastType::Quest.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::Diary.to_string(),
-                BroadcastType::Diary.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::RaidDrop.to_string(),
-                BroadcastType::RaidDrop.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::PetDrop.to_string(),
-                BroadcastType::PetDrop.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::ClueItem.to_string(),
-                BroadcastType::ClueItem.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::Invite.to_string(),
-                BroadcastType::Invite.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::LeftTheClan.to_string(),
-                BroadcastType::LeftTheClan.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::ExpelledFromClan.to_string(),
-                BroadcastType::ExpelledFromClan.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::LevelMilestone.to_string(),
-                BroadcastType::LevelMilestone.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::XPMilestone.to_string(),
-                BroadcastType::XPMilestone.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::CollectionLog.to_string(),
-                BroadcastType::CollectionLog.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::CofferWithdrawal.to_string(),
-                BroadcastType::CofferWithdrawal.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::CofferDonation.to_string(),
-                BroadcastType::CofferDonation.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::ExpelledFromClan.to_string(),
-                BroadcastType::ExpelledFromClan.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::LeftTheClan.to_string(),
-                BroadcastType::LeftTheClan.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::Invite.to_string(),
-                BroadcastType::Invite.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::PersonalBest.to_string(),
-                BroadcastType::PersonalBest.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::AreaUnlock.to_string(),
-                BroadcastType::AreaUnlock.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::LeaguesRank.to_string(),
-                BroadcastType::LeaguesRank.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::CombatMasteries.to_string(),
-                BroadcastType::CombatMasteries.to_slug(),
-            )
-            .add_string_choice(
-                BroadcastType::RelicTier.to_string(),
-                BroadcastType::RelicTier.to_slug(),
-            )
-            .required(true),
-        )
+        .add_option(broadcast_option.required(true))
         .add_option(
             CreateCommandOption::new(
                 CommandOptionType::Boolean,
@@ -137,18 +59,17 @@ pub async fn run(
                 return if let CommandDataOptionValue::Boolean(toggle) = toggle.clone().value {
                     let broadcast_type =
                         BroadcastType::from_string(broadcast_type.replace("_", " "));
-                    if toggle.clone() {
-                        saved_guild.disallowed_broadcast_types = saved_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .filter(|&b| b != &broadcast_type)
-                            .cloned()
-                            .collect();
-                    } else {
-                        saved_guild
-                            .disallowed_broadcast_types
-                            .push(broadcast_type.clone());
+                    let mut disallowed_broadcast_types = disallowed_broadcast_types(
+                        &saved_guild.broadcast_rules.clone().unwrap_or_default(),
+                    );
+                    disallowed_broadcast_types.retain(|b| b != &broadcast_type);
+                    if !toggle.clone() {
+                        disallowed_broadcast_types.push(broadcast_type.clone());
                     }
+                    saved_guild.set_setting_rules(
+                        RuleSetting::Toggle,
+                        toggle_rules(disallowed_broadcast_types),
+                    );
 
                     db.guilds.update_guild(saved_guild).await;
                     if toggle.clone() {
@@ -237,6 +237,24 @@ impl EventHandler for Bot {
                     )
                     .await
                 }
+                "set_drop_price_source" => {
+                    commands::set_drop_price_source_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "set_drop_rarity" => {
+                    commands::set_drop_rarity_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
                 "diaries" => {
                     commands::set_diary_min_command::run(
                         &command.data.options,
@@ -246,6 +264,134 @@ impl EventHandler for Bot {
                     )
                     .await
                 }
+                "combat_achievements" => {
+                    commands::set_combat_achievement_min_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "account_type_filter" => {
+                    commands::set_account_type_filter_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "pets" => {
+                    commands::pets_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "skills" => {
+                    commands::skills_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "drop_stats" => {
+                    commands::drop_stats_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "add_broadcast_rule" => {
+                    commands::add_broadcast_rule_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "broadcast_rules" => {
+                    commands::broadcast_rules_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "remove_broadcast_rule" => {
+                    commands::remove_broadcast_rule_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "route" => {
+                    commands::route_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "embed_template" => {
+                    commands::embed_template_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                        command.channel_id,
+                    )
+                    .await
+                }
+                "link" => {
+                    commands::link_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                        command.user.id.get(),
+                    )
+                    .await
+                }
+                "rank_role" => {
+                    commands::rank_role_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "rank_role_sync" => {
+                    commands::rank_role_sync_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
+                "role_ping" => {
+                    commands::role_ping_command::run(
+                        &command.data.options,
+                        &ctx,
+                        &self.mongo_db,
+                        command.guild_id.unwrap().get(),
+                    )
+                    .await
+                }
                 "reset" => {
                     commands::reset_broadcasts_thresholds::run(
                         &command.data.options,
@@ -378,7 +524,10 @@ fn get_commands() -> Vec<CreateCommand> {
     commands.push(commands::set_threshold_command::register());
     commands.push(commands::set_quest_min_command::register());
     commands.push(commands::set_clog_max_percentage::register());
+    commands.push(commands::set_drop_rarity_command::register());
+    commands.push(commands::set_drop_price_source_command::register());
     commands.push(commands::set_diary_min_command::register());
+    commands.push(commands::set_combat_achievement_min_command::register());
     commands.push(commands::reset_broadcasts_thresholds::register());
     commands.push(commands::toggle_broadcasts_command::register());
     commands.push(commands::set_wom_id_command::register());
@@ -390,6 +539,19 @@ fn get_commands() -> Vec<CreateCommand> {
     commands.push(commands::reset_verification_code::register());
     commands.push(commands::set_custom_drop_broadcast_filter::register());
     commands.push(commands::get_custom_drop_broadcast_filter::register());
+    commands.push(commands::set_account_type_filter_command::register());
+    commands.push(commands::pets_command::register());
+    commands.push(commands::skills_command::register());
+    commands.push(commands::drop_stats_command::register());
+    commands.push(commands::add_broadcast_rule_command::register());
+    commands.push(commands::broadcast_rules_command::register());
+    commands.push(commands::remove_broadcast_rule_command::register());
+    commands.push(commands::route_command::register());
+    commands.push(commands::embed_template_command::register());
+    commands.push(commands::link_command::register());
+    commands.push(commands::rank_role_command::register());
+    commands.push(commands::rank_role_sync_command::register());
+    commands.push(commands::role_ping_command::register());
     commands
 }
 pub async fn create_commands_for_guild(guild_id: &GuildId, ctx: Context) {
@@ -417,6 +579,9 @@ async fn serenity() -> shuttle_serenity::ShuttleSerenity {
     };
 
     let db = BotMongoDb::new_db_instance(mongodb_url).await;
+    db.prepare_on_startup()
+        .await
+        .expect("Could not prepare the database");
     // Set gateway intents, which decides what events the bot will be notified about
     let intents =
         GatewayIntents::GUILD_MESSAGES | GatewayIntents::MESSAGE_CONTENT | GatewayIntents::GUILDS;
@@ -0,0 +1,120 @@
+use anyhow::Result;
+use replay::{
+    guild_with_overrides, read_clan_message, replay_clan_message, ReplayOutcome, ReplayResult,
+};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+
+mod replay;
+
+const USAGE: &str = "Usage: trackscape-discord-broadcast-replay <broadcasts file> [--guild <guild config json>] [--json]";
+
+/// Replays a file of raw clan broadcasts, one per line as plain text or NDJSON `ClanMessage`s,
+/// through the extractor and handler and reports what would have been sent to Discord.
+/// `--json` prints one result per line which is the format of the golden files
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut broadcasts_path: Option<String> = None;
+    let mut guild_path: Option<String> = None;
+    let mut json_output = false;
+
+    let mut args_iter = args.into_iter();
+    while let Some(arg) = args_iter.next() {
+        match arg.as_str() {
+            "--json" => json_output = true,
+            "--guild" => guild_path = args_iter.next(),
+            _ => broadcasts_path = Some(arg),
+        }
+    }
+
+    let Some(broadcasts_path) = broadcasts_path else {
+        eprintln!("{}", USAGE);
+        std::process::exit(1);
+    };
+
+    let registered_guild = load_guild_config(guild_path)?;
+    let broadcasts = std::fs::read_to_string(broadcasts_path)?;
+
+    let mut results: Vec<ReplayResult> = Vec::new();
+    for (line_number, line) in broadcasts.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        match read_clan_message(line) {
+            Ok(clan_message) => {
+                results.push(replay_clan_message(clan_message, registered_guild.clone()).await)
+            }
+            Err(e) => eprintln!("Skipping line {}: {}", line_number + 1, e),
+        }
+    }
+
+    if json_output {
+        for result in results {
+            println!("{}", serde_json::to_string(&result)?);
+        }
+    } else {
+        print_report(results);
+    }
+    Ok(())
+}
+
+fn load_guild_config(guild_path: Option<String>) -> Result<RegisteredGuildModel> {
+    match guild_path {
+        None => Ok(RegisteredGuildModel::new(0)),
+        Some(guild_path) => guild_with_overrides(&std::fs::read_to_string(guild_path)?),
+    }
+}
+
+fn print_report(results: Vec<ReplayResult>) {
+    let total = results.len();
+    let mut unmatched = Vec::new();
+    let mut unknown = Vec::new();
+    let mut extraction_failures = Vec::new();
+    let mut suppressed = Vec::new();
+    let mut rendered = Vec::new();
+    for result in results {
+        match result.outcome {
+            ReplayOutcome::Unmatched => unmatched.push(result.message),
+            ReplayOutcome::Unknown => unknown.push(result.message),
+            ReplayOutcome::ExtractionFailed => extraction_failures.push(format!(
+                "[{}] {}",
+                result.broadcast_type.to_string(),
+                result.message
+            )),
+            ReplayOutcome::Suppressed => suppressed.push(format!(
+                "[{}] {}",
+                result.broadcast_type.to_string(),
+                result.message
+            )),
+            ReplayOutcome::Rendered(broadcast) => rendered.push(broadcast),
+        }
+    }
+
+    println!("Replayed {} broadcasts", total);
+    print_section("Unmatched lines", &unmatched);
+    print_section("Unknown classifications", &unknown);
+    print_section("Extraction failures", &extraction_failures);
+    print_section("Classified but not sent", &suppressed);
+
+    println!("\nRendered broadcasts ({})", rendered.len());
+    for broadcast in rendered {
+        println!(
+            "  [{}] {} | {} | player: {} | icon: {} | quantity: {}",
+            broadcast.type_of_broadcast.to_string(),
+            broadcast.title,
+            broadcast.message,
+            broadcast.player_it_happened_to,
+            broadcast.icon_url.unwrap_or_else(|| "none".to_string()),
+            broadcast
+                .item_quantity
+                .map_or("none".to_string(), |quantity| quantity.to_string()),
+        );
+    }
+}
+
+fn print_section(title: &str, lines: &[String]) {
+    println!("\n{} ({})", title, lines.len());
+    for line in lines {
+        println!("  {}", line);
+    }
+}
@@ -0,0 +1,423 @@
+use async_trait::async_trait;
+use celery::error::CeleryError;
+use celery::prelude::Task;
+use celery::task::{AsyncResult, Signature};
+use mongodb::bson::{oid::ObjectId, DateTime};
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use trackscape_discord_shared::database::clan_mate_collection_log_totals::{
+    ClanMateCollectionLogTotalModel, ClanMateCollectionLogTotals,
+};
+use trackscape_discord_shared::database::clan_mates::{ClanMateModel, ClanMates};
+use trackscape_discord_shared::database::drop_logs_db::{DropLogModel, DropLogs, DropSourceStats};
+use trackscape_discord_shared::database::guilds_db::RegisteredGuildModel;
+use trackscape_discord_shared::database::pb_records_db::PersonalBests;
+use trackscape_discord_shared::jobs::JobQueue;
+use trackscape_discord_shared::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    area_unlock_broadcast_extractor, combat_masteries_broadcast_extractor,
+    leagues_catch_all_broadcast_extractor, leagues_rank_broadcast_extractor, parse_broadcast,
+    relic_tier_broadcast_extractor, BroadcastType, ClanMessage, DropItemBroadcast,
+    LeaguesBroadCastType,
+};
+use trackscape_discord_shared::osrs_broadcast_handler::{
+    BroadcastMessageToDiscord, OSRSBroadcastHandler,
+};
+
+//Same tag the chat controller strips to spot league world broadcasts
+const LEAGUES_ICON_TAG: &str = "<img=22> ";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", content = "broadcast")]
+pub enum ReplayOutcome {
+    /// No broadcast pattern matched the line at all
+    Unmatched,
+    /// Only matched a leagues format outside a league world, so the live pipeline sees Unknown
+    Unknown,
+    /// Classified but the typed extractor could not pull the fields out
+    ExtractionFailed,
+    /// Classified and extracted but the handler did not send anything with this guild config
+    Suppressed,
+    Rendered(BroadcastMessageToDiscord),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReplayResult {
+    pub message: String,
+    pub broadcast_type: BroadcastType,
+    #[serde(flatten)]
+    pub outcome: ReplayOutcome,
+}
+
+/// Reads a line of NDJSON as a `ClanMessage`, anything else is treated as the raw broadcast text
+pub fn read_clan_message(line: &str) -> Result<ClanMessage, anyhow::Error> {
+    if line.starts_with('{') {
+        return Ok(serde_json::from_str::<ClanMessage>(line)?);
+    }
+    Ok(ClanMessage {
+        sender: "Replay".to_string(),
+        message: line.to_string(),
+        clan_name: "Replay".to_string(),
+        rank: "".to_string(),
+        icon_id: None,
+        is_league_world: None,
+    })
+}
+
+/// Starts from a fresh guild that allows everything and overlays any fields set in the json config
+pub fn guild_with_overrides(overrides_json: &str) -> Result<RegisteredGuildModel, anyhow::Error> {
+    let mut guild_json = serde_json::to_value(RegisteredGuildModel::new(0))?;
+    let overrides: serde_json::Value = serde_json::from_str(overrides_json)?;
+    if let (Some(guild_fields), Some(override_fields)) =
+        (guild_json.as_object_mut(), overrides.as_object())
+    {
+        for (key, value) in override_fields {
+            guild_fields.insert(key.clone(), value.clone());
+        }
+    }
+    Ok(serde_json::from_value(guild_json)?)
+}
+
+pub async fn replay_clan_message(
+    mut clan_message: ClanMessage,
+    registered_guild: RegisteredGuildModel,
+) -> ReplayResult {
+    let mut league_world = clan_message.is_league_world.unwrap_or(false);
+    if clan_message.message.starts_with(LEAGUES_ICON_TAG) {
+        clan_message.message = clan_message.message.replace(LEAGUES_ICON_TAG, "");
+        clan_message.is_league_world = Some(true);
+        league_world = true;
+    }
+    let message = clan_message.message.clone();
+
+    let parsed_type = parse_broadcast(message.as_str()).broadcast_type();
+    let leagues_type = match parsed_type {
+        BroadcastType::Unknown => leagues_catch_all_broadcast_extractor(message.clone()),
+        _ => None,
+    };
+
+    let handler = OSRSBroadcastHandler::new(
+        clan_message,
+        Err(anyhow::anyhow!("Item mapping is not loaded when replaying")),
+        Err(anyhow::anyhow!("GE prices are not loaded when replaying")),
+        Err(anyhow::anyhow!("Quests are not loaded when replaying")),
+        Err(anyhow::anyhow!(
+            "Collection logs are not loaded when replaying"
+        )),
+        Err(anyhow::anyhow!("Drop rates are not loaded when replaying")),
+        registered_guild,
+        league_world,
+        ReplayDb,
+        ReplayDb,
+        ReplayDb,
+        ReplayDb,
+        Arc::new(ReplayJobQueue),
+    );
+
+    let (broadcast_type, possible_broadcast) = match leagues_type {
+        None => {
+            if let BroadcastType::Unknown = parsed_type {
+                return ReplayResult {
+                    message,
+                    broadcast_type: parsed_type,
+                    outcome: ReplayOutcome::Unmatched,
+                };
+            }
+            (parsed_type, handler.extract_message().await)
+        }
+        Some(leagues_type) => {
+            let broadcast_type = leagues_type.to_broadcast_type();
+            if !league_world {
+                return ReplayResult {
+                    message,
+                    broadcast_type: BroadcastType::Unknown,
+                    outcome: ReplayOutcome::Unknown,
+                };
+            }
+            if !leagues_extraction_succeeds(&leagues_type, message.clone()) {
+                return ReplayResult {
+                    message,
+                    broadcast_type,
+                    outcome: ReplayOutcome::ExtractionFailed,
+                };
+            }
+            (broadcast_type, handler.extract_leagues_message().await)
+        }
+    };
+
+    ReplayResult {
+        message,
+        broadcast_type,
+        outcome: match possible_broadcast {
+            None => ReplayOutcome::Suppressed,
+            Some(broadcast) => ReplayOutcome::Rendered(broadcast),
+        },
+    }
+}
+
+fn leagues_extraction_succeeds(leagues_type: &LeaguesBroadCastType, message: String) -> bool {
+    match leagues_type {
+        LeaguesBroadCastType::AreaUnlock => area_unlock_broadcast_extractor(message).is_some(),
+        LeaguesBroadCastType::LeaguesRank => leagues_rank_broadcast_extractor(message).is_some(),
+        LeaguesBroadCastType::CombatMasteries => {
+            combat_masteries_broadcast_extractor(message).is_some()
+        }
+        LeaguesBroadCastType::RelicTier => relic_tier_broadcast_extractor(message).is_some(),
+        LeaguesBroadCastType::NormalBroadCast => false,
+    }
+}
+
+/// Stands in for every database the handler writes to so replays never touch mongo
+#[derive(Clone)]
+pub struct ReplayDb;
+
+#[async_trait]
+impl DropLogs for ReplayDb {
+    fn new_instance(_mongodb: Database) -> Self {
+        Self
+    }
+
+    async fn new_drop_log(
+        &self,
+        _drop_log: DropItemBroadcast,
+        _broadcast_type: BroadcastType,
+        _ge_price: Option<i64>,
+        _guild_id: u64,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    async fn get_drops_between_dates(
+        &self,
+        _guild_id: u64,
+        _start_date: DateTime,
+        _end_date: DateTime,
+    ) -> anyhow::Result<Vec<DropLogModel>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_drop_source_stats(
+        &self,
+        _guild_id: u64,
+        _start_date: DateTime,
+        _end_date: DateTime,
+    ) -> anyhow::Result<Vec<DropSourceStats>> {
+        Ok(Vec::new())
+    }
+}
+
+#[async_trait]
+impl ClanMateCollectionLogTotals for ReplayDb {
+    fn new_instance(_mongodb: Database) -> Self {
+        Self
+    }
+
+    async fn update_or_create(
+        &self,
+        _guild_id: u64,
+        _player_id: ObjectId,
+        _total: i64,
+        _item_name: Option<String>,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn get_guild_totals(
+        &self,
+        _guild_id: u64,
+    ) -> Result<Vec<ClanMateCollectionLogTotalModel>, anyhow::Error> {
+        Ok(Vec::new())
+    }
+}
+
+//Every time replays as the clan mates first, so the broadcast has no previous best
+#[async_trait]
+impl PersonalBests for ReplayDb {
+    fn new_instance(_mongodb: Database) -> Self {
+        Self
+    }
+
+    async fn get_personal_best(
+        &self,
+        _clan_mate_id: ObjectId,
+        _activity_name: String,
+    ) -> Result<Option<f64>, anyhow::Error> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl ClanMates for ReplayDb {
+    fn new_instance(_mongodb: Database) -> Self {
+        Self
+    }
+
+    async fn find_or_create_clan_mate(
+        &self,
+        guild_id: u64,
+        player_name: String,
+    ) -> Result<ClanMateModel, anyhow::Error> {
+        Ok(ClanMateModel::new(guild_id, player_name, None))
+    }
+
+    async fn create_new_clan_mate(
+        &self,
+        guild_id: u64,
+        player_name: String,
+        wom_player_id: Option<u64>,
+    ) -> Result<ClanMateModel, anyhow::Error> {
+        Ok(ClanMateModel::new(guild_id, player_name, wom_player_id))
+    }
+
+    async fn find_by_current_name(
+        &self,
+        _guild_id: u64,
+        _player_name: String,
+    ) -> Result<Option<ClanMateModel>, anyhow::Error> {
+        Ok(None)
+    }
+
+    async fn find_by_previous_name(
+        &self,
+        _guild_id: u64,
+        _player_name: String,
+    ) -> Result<Option<ClanMateModel>, anyhow::Error> {
+        Ok(None)
+    }
+
+    async fn update_clan_mate(&self, model: ClanMateModel) -> Result<ClanMateModel, anyhow::Error> {
+        Ok(model)
+    }
+
+    async fn find_by_discord_user_id(
+        &self,
+        _guild_id: u64,
+        _discord_user_id: u64,
+    ) -> Result<Vec<ClanMateModel>, anyhow::Error> {
+        Ok(Vec::new())
+    }
+
+    async fn get_clan_member_count(&self, _guild_id: u64) -> Result<u64, anyhow::Error> {
+        Ok(0)
+    }
+
+    async fn get_clan_mates_by_guild_id(
+        &self,
+        _guild_id: u64,
+    ) -> Result<Vec<ClanMateModel>, anyhow::Error> {
+        Ok(Vec::new())
+    }
+
+    async fn remove_clan_mate(
+        &self,
+        _guild_id: u64,
+        _player_name: String,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+
+    async fn change_name(
+        &self,
+        _guild_id: u64,
+        _old_name: String,
+        _new_name: String,
+    ) -> Result<(), anyhow::Error> {
+        Ok(())
+    }
+}
+
+/// Drops every job, replays should not have side effects
+pub struct ReplayJobQueue;
+
+#[async_trait]
+impl JobQueue for ReplayJobQueue {
+    async fn send_task<T: Task>(
+        &self,
+        _task_sig: Signature<T>,
+    ) -> Result<AsyncResult, CeleryError> {
+        Ok(AsyncResult {
+            task_id: "".to_string(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use trackscape_discord_shared::broadcast_processors::{
+        broadcast_processors, BroadcastProcessor,
+    };
+
+    // When a change to the output is intended regenerate the expected file with
+    // cargo run -p trackscape-discord-broadcast-replay -- golden/broadcasts.ndjson --guild golden/guild.json --json > golden/broadcasts.golden.ndjson
+    const GOLDEN_INPUT: &str = include_str!("../golden/broadcasts.ndjson");
+    const GOLDEN_EXPECTED: &str = include_str!("../golden/broadcasts.golden.ndjson");
+    const GOLDEN_GUILD: &str = include_str!("../golden/guild.json");
+
+    async fn replay_golden_input() -> Vec<ReplayResult> {
+        let registered_guild = guild_with_overrides(GOLDEN_GUILD).unwrap();
+        let mut results = Vec::new();
+        for line in GOLDEN_INPUT.lines().filter(|line| !line.trim().is_empty()) {
+            let clan_message = read_clan_message(line).unwrap();
+            results.push(replay_clan_message(clan_message, registered_guild.clone()).await);
+        }
+        results
+    }
+
+    #[tokio::test]
+    async fn test_golden_broadcasts() {
+        let results = replay_golden_input().await;
+        let expected: Vec<serde_json::Value> = GOLDEN_EXPECTED
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+
+        assert_eq!(results.len(), expected.len());
+        for (result, expected) in results.iter().zip(expected.iter()) {
+            let actual = serde_json::to_value(result).unwrap();
+            assert_eq!(&actual, expected, "Broadcast changed: {}", result.message);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_golden_broadcasts_cover_every_type() {
+        let rendered_types: HashSet<BroadcastType> = replay_golden_input()
+            .await
+            .into_iter()
+            .filter(|result| matches!(result.outcome, ReplayOutcome::Rendered(_)))
+            .map(|result| result.broadcast_type)
+            .collect();
+
+        //Types only sent from jobs, like clan records, have no processor and are never parsed
+        let processors: Vec<
+            &dyn BroadcastProcessor<ReplayDb, ReplayDb, ReplayDb, ReplayDb, ReplayJobQueue>,
+        > = broadcast_processors();
+        for processor in processors {
+            let broadcast_type = processor.broadcast_type();
+            if broadcast_type == BroadcastType::Unknown {
+                continue;
+            }
+            assert!(
+                rendered_types.contains(&broadcast_type),
+                "No golden broadcast renders a {}",
+                broadcast_type.to_string()
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_golden_broadcasts_keep_their_type() {
+        //The toggles, routes, templates and pings all go by the type the broadcast is sent as
+        for result in replay_golden_input().await {
+            if let ReplayOutcome::Rendered(broadcast) = result.outcome {
+                assert_eq!(
+                    broadcast.type_of_broadcast, result.broadcast_type,
+                    "Sent as another type: {}",
+                    result.message
+                );
+            }
+        }
+    }
+}
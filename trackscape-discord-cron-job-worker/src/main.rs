@@ -2,7 +2,10 @@ use anyhow::Result;
 use celery::beat::CronSchedule;
 use dotenv::dotenv;
 use env_logger::Env;
+use trackscape_discord_shared::jobs::drop_rates_job::refresh_wiki_drop_rates;
+use trackscape_discord_shared::jobs::job_helpers::get_mongodb;
 // use trackscape_discord_shared::jobs::name_change_job::name_change;
+use trackscape_discord_shared::jobs::rank_roles_job::reconcile_rank_roles;
 use trackscape_discord_shared::jobs::wom_guild_sync_job::wom_guild_sync;
 
 /// This is not really a worker but more of something to send jobs to the worker at certain times.
@@ -11,6 +14,8 @@ async fn main() -> Result<()> {
     dotenv().ok();
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
+    get_mongodb().await.prepare_on_startup().await?;
+
     let mut cron_job_worker = celery::beat!(
         broker = RedisBroker { std::env::var("REDIS_ADDR").unwrap_or_else(|_| "redis://127.0.0.1:6379/".into()) },
         tasks = [
@@ -24,6 +29,17 @@ async fn main() -> Result<()> {
                 //Off set by at least 4 or 5 hours from name_change
                 schedule = CronSchedule::from_string("0 0,12 * * *")?,
                 args = (),
+            },
+            "reconcile_rank_roles" => {
+                reconcile_rank_roles,
+                schedule = CronSchedule::from_string("15 * * * *")?,
+                args = (),
+            },
+            "refresh_wiki_drop_rates" => {
+                refresh_wiki_drop_rates,
+                //Daily so the week long cache never runs out
+                schedule = CronSchedule::from_string("45 3 * * *")?,
+                args = (),
             }
         ],
         task_routes = [
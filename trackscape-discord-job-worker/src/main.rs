@@ -1,10 +1,15 @@
 use anyhow::Result;
 use dotenv::dotenv;
 use env_logger::Env;
+use trackscape_discord_shared::jobs::job_helpers::get_mongodb;
 use trackscape_discord_shared::jobs::{
-    add_job, name_change_job::name_change, new_pb_job::record_new_pb,
-    parse_rl_chat_command::parse_command, remove_clanmate_job::remove_clanmate,
-    update_create_clanmate_job::update_create_clanmate, wom_guild_sync_job::wom_guild_sync,
+    add_job, combat_achievement_job::record_combat_achievement_tier,
+    drop_rates_job::refresh_wiki_drop_rates, leagues_progress_job::record_leagues_progress,
+    name_change_job::name_change, new_pb_job::record_new_pb, parse_rl_chat_command::parse_command,
+    pet_drop_job::record_pet_drop, rank_roles_job::reconcile_rank_roles,
+    remove_clanmate_job::remove_clanmate, skill_milestone_job::record_level_milestone,
+    skill_milestone_job::record_xp_milestone, update_create_clanmate_job::update_create_clanmate,
+    wom_guild_sync_job::wom_guild_sync,
 };
 
 #[tokio::main]
@@ -12,6 +17,8 @@ async fn main() -> Result<()> {
     dotenv().ok();
     env_logger::Builder::from_env(Env::default().default_filter_or("info")).init();
 
+    get_mongodb().await.prepare_on_startup().await?;
+
     let instant_job_app = celery::app!(
         broker = RedisBroker { std::env::var("REDIS_ADDR").unwrap_or_else(|_| "redis://127.0.0.1:6379/".into()) },
         tasks = [
@@ -20,14 +27,22 @@ async fn main() -> Result<()> {
             remove_clanmate,
             name_change,
             wom_guild_sync,
+            record_combat_achievement_tier,
+            record_leagues_progress,
+            record_pet_drop,
+            record_level_milestone,
+            record_xp_milestone,
             record_new_pb,
             parse_command,
+            reconcile_rank_roles,
+            refresh_wiki_drop_rates,
         ],
         // This just shows how we can route certain tasks to certain queues based
         // on glob matching.
         task_routes = [
             "name_change" => "cron_job_queue",
             "wom_guild_sync" => "cron_job_queue",
+            "reconcile_rank_roles" => "cron_job_queue",
             "*" => "celery",
         ],
         prefetch_count = 2,
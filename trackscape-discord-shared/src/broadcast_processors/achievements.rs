@@ -0,0 +1,758 @@
+use super::{BroadcastProcessor, BroadcastTypeInfo};
+use crate::broadcast_rules::BroadcastRuleContext;
+use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mates::ClanMates;
+use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::{ClanRecord, PersonalBests};
+use crate::embed_templates::{format_time, format_time_change, TemplateValues};
+use crate::jobs::combat_achievement_job::record_combat_achievement_tier;
+use crate::jobs::new_pb_job::record_new_pb;
+use crate::jobs::skill_milestone_job::{record_level_milestone, record_xp_milestone};
+use crate::jobs::JobQueue;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    get_combat_achievement_tier_icon, AccountType, BroadcastPattern, BroadcastType,
+    LevelMilestoneKind, ParsedBroadcast, PersonalBestBroadcast, COMBAT_ACHIEVEMENT_TIER_PATTERNS,
+    COMBAT_TASK_PATTERNS, DIARY_PATTERNS, HARDCORE_DEATH_PATTERNS, HARDCORE_IRONMAN_ICON_ID,
+    LEVELMILESTONE_PATTERNS, PERSONAL_BEST_PATTERNS, QUEST_PATTERNS, XPMILESTONE_PATTERNS,
+};
+use crate::osrs_broadcast_handler::{BroadcastMessageToDiscord, OSRSBroadcastHandler};
+use async_trait::async_trait;
+use log::error;
+use mongodb::bson::DateTime;
+
+pub struct CombatTaskProcessor;
+
+impl BroadcastTypeInfo for CombatTaskProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CombatTask
+    }
+
+    fn name(&self) -> &'static str {
+        "Combat Task"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        COMBAT_TASK_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CombatTaskProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::CombatTask(combat_task) = parsed_broadcast else {
+            return None;
+        };
+        let mut context =
+            BroadcastRuleContext::new(BroadcastType::CombatTask, combat_task.player.clone());
+        context.item_name = Some(combat_task.task_name.clone());
+        context.tier = Some(combat_task.tier.ranking());
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues combat task completed!".to_string(),
+            false => ":crossed_swords: New combat task completed!".to_string(),
+        };
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::CombatTask,
+                    player_it_happened_to: combat_task.player,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(get_combat_achievement_tier_icon(&combat_task.tier)),
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct CombatAchievementTierProcessor;
+
+impl BroadcastTypeInfo for CombatAchievementTierProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CombatAchievementTier
+    }
+
+    fn name(&self) -> &'static str {
+        "Combat Achievement Tier"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        COMBAT_ACHIEVEMENT_TIER_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CombatAchievementTierProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::CombatAchievementTier(combat_achievement_tier) = parsed_broadcast
+        else {
+            return None;
+        };
+        if !handler.leagues_message {
+            let job = record_combat_achievement_tier::new(
+                combat_achievement_tier.clone(),
+                handler.registered_guild.guild_id,
+            );
+            let _ = handler.job_queue.send_task(job).await;
+        }
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::CombatAchievementTier,
+            combat_achievement_tier.player.clone(),
+        );
+        context.tier = Some(combat_achievement_tier.tier.ranking());
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues combat achievement tier unlocked!".to_string(),
+            false => ":trophy: New combat achievement tier unlocked!".to_string(),
+        };
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::CombatAchievementTier,
+                    player_it_happened_to: combat_achievement_tier.player,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(get_combat_achievement_tier_icon(
+                        &combat_achievement_tier.tier,
+                    )),
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct QuestProcessor;
+
+impl BroadcastTypeInfo for QuestProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::Quest
+    }
+
+    fn name(&self) -> &'static str {
+        "Quest"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        QUEST_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for QuestProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::Quest(exported_data) = parsed_broadcast else {
+            return None;
+        };
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::Quest,
+            exported_data.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(exported_data.quest_name.clone());
+        context.tier = handler.quests.as_ref().and_then(|quests| {
+            quests
+                .iter()
+                .find(|quest| quest.name == exported_data.quest_name)
+                .map(|quest| quest.difficulty.ranking())
+        });
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues quest completed!".to_string(),
+            false => ":tada: New quest completed!".to_string(),
+        };
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::Quest,
+                    player_it_happened_to: exported_data.player_it_happened_to,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: exported_data.quest_reward_scroll_icon,
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct DiaryProcessor;
+
+impl BroadcastTypeInfo for DiaryProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::Diary
+    }
+
+    fn name(&self) -> &'static str {
+        "Diary"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        DIARY_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for DiaryProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::Diary(exported_data) = parsed_broadcast else {
+            return None;
+        };
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::Diary,
+            exported_data.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(
+            exported_data.diary_name.clone() + " " + exported_data.diary_tier.to_string().as_str(),
+        );
+        context.tier = Some(exported_data.diary_tier.ranking());
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues diary completed!".to_string(),
+            false => ":tada: New diary completed!".to_string(),
+        };
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::Diary,
+                    player_it_happened_to: exported_data.player_it_happened_to,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(
+                        "https://oldschool.runescape.wiki/images/Achievement_Diaries.png"
+                            .to_string(),
+                    ),
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct XPMilestoneProcessor;
+
+impl BroadcastTypeInfo for XPMilestoneProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::XPMilestone
+    }
+
+    fn name(&self) -> &'static str {
+        "XP Milestone"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        XPMILESTONE_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for XPMilestoneProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::XPMilestone(xpmilestone_broadcast) = parsed_broadcast else {
+            return None;
+        };
+        if !handler.leagues_message {
+            let job = record_xp_milestone::new(
+                xpmilestone_broadcast.clone(),
+                handler.registered_guild.guild_id,
+            );
+            let _ = handler.job_queue.send_task(job).await;
+        }
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues XP Milestone reached!".to_string(),
+            false => ":tada: New XP Milestone reached!".to_string(),
+        };
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::XPMilestone,
+                player_it_happened_to: xpmilestone_broadcast.clan_mate,
+                message: handler.clan_message.message.clone(),
+                icon_url: xpmilestone_broadcast.skill_icon,
+                title,
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct LevelMilestoneProcessor;
+
+impl BroadcastTypeInfo for LevelMilestoneProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::LevelMilestone
+    }
+
+    fn name(&self) -> &'static str {
+        "Level Milestone"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        LEVELMILESTONE_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for LevelMilestoneProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::LevelMilestone(levelmilestone_broadcast) = parsed_broadcast else {
+            return None;
+        };
+        if !handler.leagues_message {
+            let job = record_level_milestone::new(
+                levelmilestone_broadcast.clone(),
+                handler.registered_guild.guild_id,
+            );
+            let _ = handler.job_queue.send_task(job).await;
+        }
+        let title = match (
+            handler.leagues_message,
+            &levelmilestone_broadcast.kind,
+            levelmilestone_broadcast.maxed,
+        ) {
+            (true, _, _) => ":bar_chart: New Leagues Level Milestone reached!".to_string(),
+            (false, LevelMilestoneKind::Total, true) => ":trophy: Maxed total level!".to_string(),
+            (false, LevelMilestoneKind::Total, false) => {
+                ":tada: New total level reached!".to_string()
+            }
+            (false, LevelMilestoneKind::Combat, true) => {
+                ":trophy: Max combat level reached!".to_string()
+            }
+            (false, LevelMilestoneKind::Combat, false) => {
+                ":crossed_swords: New combat level reached!".to_string()
+            }
+            (false, LevelMilestoneKind::Skill, _) => {
+                ":tada: New Level Milestone reached!".to_string()
+            }
+        };
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::LevelMilestone,
+                player_it_happened_to: levelmilestone_broadcast.clan_mate,
+                message: handler.clan_message.message.clone(),
+                icon_url: levelmilestone_broadcast.skill_icon,
+                title,
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct PersonalBestProcessor;
+
+impl BroadcastTypeInfo for PersonalBestProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::PersonalBest
+    }
+
+    fn name(&self) -> &'static str {
+        "Personal Best"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        PERSONAL_BEST_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for PersonalBestProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::PersonalBest(exported_data) = parsed_broadcast else {
+            return None;
+        };
+        //Read before the job saves the time, for the change from their old best
+        let previous_best = get_previous_best(handler, &exported_data).await;
+        let job = record_new_pb::new(
+            exported_data.clone(),
+            handler.clan_message.clone(),
+            handler.registered_guild.guild_id,
+        );
+        let _ = handler.job_queue.send_task(job).await;
+
+        let time = format_time(exported_data.time_in_seconds);
+        let mut message = handler.clan_message.message.clone();
+        let mut template_values = TemplateValues {
+            time: Some(time.clone()),
+            ..TemplateValues::default()
+        };
+        if let Some(previous_best) =
+            previous_best.filter(|previous_best| exported_data.time_in_seconds < *previous_best)
+        {
+            let previous_time = format_time(previous_best);
+            let time_change = format_time_change(previous_best - exported_data.time_in_seconds);
+            message = format!(
+                "{}\n{} → {} ({})",
+                message, previous_time, time, time_change
+            );
+            template_values.previous_time = Some(previous_time);
+            template_values.time_change = Some(time_change);
+        }
+
+        let mut context =
+            BroadcastRuleContext::new(BroadcastType::PersonalBest, exported_data.player.clone());
+        context.item_name = Some(exported_data.activity.clone());
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::PersonalBest,
+                    player_it_happened_to: exported_data.player.clone(),
+                    message,
+                    icon_url: Some(best_guest_pb_icon(exported_data.activity).to_string()),
+                    title: ":stopwatch: New Personal Best!".to_string(),
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values,
+                },
+            )
+            .await
+    }
+}
+
+/// The clan mates best before this broadcast, only used to show the change in the message
+async fn get_previous_best<
+    T: DropLogs,
+    CL: ClanMateCollectionLogTotals,
+    CM: ClanMates,
+    PB: PersonalBests,
+    J: JobQueue,
+>(
+    handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+    personal_best: &PersonalBestBroadcast,
+) -> Option<f64> {
+    let activity_name = personal_best
+        .variant
+        .clone()
+        .unwrap_or(personal_best.activity.clone());
+    let clan_mate = handler
+        .clan_mates_db
+        .find_by_current_name(
+            handler.registered_guild.guild_id,
+            personal_best.player.clone(),
+        )
+        .await
+        .ok()??;
+    match handler
+        .pb_records_db
+        .get_personal_best(clan_mate.id, activity_name)
+        .await
+    {
+        Ok(previous_best) => previous_best,
+        Err(error) => {
+            error!("Failed to get the previous personal best: {:?}", error);
+            None
+        }
+    }
+}
+
+/// The announcement for a personal best that beat the guilds record, sent by the record_new_pb
+/// job once saving the time shows the record was beaten
+pub(crate) async fn clan_record_broadcast<
+    T: DropLogs,
+    CL: ClanMateCollectionLogTotals,
+    CM: ClanMates,
+    PB: PersonalBests,
+    J: JobQueue,
+>(
+    handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+    personal_best: &PersonalBestBroadcast,
+    clan_record: &ClanRecord,
+) -> Option<BroadcastMessageToDiscord> {
+    let mut context =
+        BroadcastRuleContext::new(BroadcastType::ClanRecord, personal_best.player.clone());
+    context.item_name = Some(personal_best.activity.clone());
+    let time = format_time(personal_best.time_in_seconds);
+    let previous_time = format_time(clan_record.time_in_seconds);
+    let time_change =
+        format_time_change(clan_record.time_in_seconds - personal_best.time_in_seconds);
+    handler
+        .apply_broadcast_rules(
+            context,
+            BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::ClanRecord,
+                player_it_happened_to: personal_best.player.clone(),
+                message: format!(
+                    "{}\nClan record: {} {} → {} {} ({})",
+                    handler.clan_message.message,
+                    clan_record.player_name,
+                    previous_time,
+                    personal_best.player,
+                    time,
+                    time_change
+                ),
+                icon_url: Some(best_guest_pb_icon(personal_best.activity.clone()).to_string()),
+                title: ":trophy: New Clan Record!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues {
+                    time: Some(time),
+                    previous_time: Some(previous_time),
+                    time_change: Some(time_change),
+                    ..TemplateValues::default()
+                },
+            },
+        )
+        .await
+}
+
+/// Sent by the record_new_pb job when a personal best beats the guilds fastest time, it is
+/// never parsed from a broadcast so it has no processor
+pub struct ClanRecordType;
+
+impl BroadcastTypeInfo for ClanRecordType {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::ClanRecord
+    }
+
+    fn name(&self) -> &'static str {
+        "Clan Record"
+    }
+}
+
+pub struct HardcoreDeathProcessor;
+
+impl BroadcastTypeInfo for HardcoreDeathProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::HardcoreDeath
+    }
+
+    fn name(&self) -> &'static str {
+        "Hardcore Death"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        HARDCORE_DEATH_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for HardcoreDeathProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::HardcoreDeath(hardcore_death) = parsed_broadcast else {
+            return None;
+        };
+        //Only trust the death if the broadcast icon agrees it was a hardcore account
+        let confirmed_hardcore = match handler.clan_message.icon_id {
+            Some(icon_id) => icon_id == HARDCORE_IRONMAN_ICON_ID,
+            None => true,
+        };
+        if !confirmed_hardcore {
+            error!(
+                "Hardcore death broadcast for {} did not have the hardcore icon, icon id: {:?}",
+                hardcore_death.player, handler.clan_message.icon_id
+            );
+        }
+
+        if !handler.leagues_message && confirmed_hardcore {
+            let possible_clan_mate = handler
+                .clan_mates_db
+                .find_or_create_clan_mate(
+                    handler.registered_guild.guild_id,
+                    hardcore_death.player.clone(),
+                )
+                .await;
+            match possible_clan_mate {
+                Ok(mut clan_mate) => {
+                    if clan_mate.lost_hardcore_status_at.is_none() {
+                        clan_mate.lost_hardcore_status_at = Some(DateTime::now());
+                        //Hardcores are downgraded to a regular ironman when they die
+                        clan_mate.account_type = Some(AccountType::Ironman);
+                        if let Err(error) = handler.clan_mates_db.update_clan_mate(clan_mate).await
+                        {
+                            error!("{:?}", error);
+                        }
+                    }
+                }
+                Err(error) => {
+                    error!("{:?}", error);
+                }
+            }
+        }
+
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::HardcoreDeath,
+                player_it_happened_to: hardcore_death.player,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/Hardcore_ironman_chat_badge.png"
+                        .to_string(),
+                ),
+                title: ":skull: A Hardcore Ironman has fallen!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+fn best_guest_pb_icon(activity: String) -> String {
+    match activity.as_str().to_lowercase() {
+        x if x.contains("theatre of blood") => {
+            "https://oldschool.runescape.wiki/images/Theatre_of_Blood_logo.png".to_string()
+        }
+        x if x.contains("chambers of xeric") => {
+            "https://oldschool.runescape.wiki/images/Chambers_of_Xeric_logo.png".to_string()
+        }
+        x if x.contains("tombs of amascut") => {
+            "https://oldschool.runescape.wiki/images/Tombs_of_Amascut.png".to_string()
+        }
+        x if x.contains("colosseum") => {
+            "https://oldschool.runescape.wiki/images/Sol_Heredit.png".to_string()
+        }
+        x if x.contains("hueycoatl") => {
+            "https://oldschool.runescape.wiki/images/The_Hueycoatl.png".to_string()
+        }
+        x if x.contains("whisperer") => {
+            "https://oldschool.runescape.wiki/images/The_Whisperer.png".to_string()
+        }
+        x if x.contains("leviathan") => {
+            "https://oldschool.runescape.wiki/images/The_Leviathan.png".to_string()
+        }
+        x if x.contains("mimic") => {
+            "https://oldschool.runescape.wiki/images/The_Mimic.png".to_string()
+        }
+        x if x.contains("zulrah") => {
+            "https://oldschool.runescape.wiki/images/Zulrah_(serpentine).png".to_string()
+        }
+        x if x.contains("corrupted gauntlet") => {
+            "https://oldschool.runescape.wiki/images/Corrupted_Hunllef.png".to_string()
+        }
+        x if x.contains("gauntlet") => {
+            "https://oldschool.runescape.wiki/images/Crystalline_Hunllef.png".to_string()
+        }
+        x if x.contains("inferno") => {
+            "https://oldschool.runescape.wiki/images/TzKal-Zuk.png".to_string()
+        }
+        x if x.contains("fight caves") => {
+            "https://oldschool.runescape.wiki/images/TzTok-Jad.png".to_string()
+        }
+        x if x.contains("duke sucellus") => {
+            "https://oldschool.runescape.wiki/images/Duke_Sucellus.png".to_string()
+        }
+        x if x.contains("phantom muspah") => {
+            "https://oldschool.runescape.wiki/images/Phantom_Muspah_(ranged).png".to_string()
+        }
+        x if x.contains("royal titans") => {
+            "https://oldschool.runescape.wiki/images/Eldric_the_Ice_King.png".to_string()
+        }
+        x if x.contains("grotesque guardians") => {
+            "https://oldschool.runescape.wiki/images/Dusk_(2nd_form).png".to_string()
+        }
+        _ => format!("https://oldschool.runescape.wiki/images/{}.png", activity).to_string(),
+    }
+}
@@ -0,0 +1,283 @@
+use super::{BroadcastProcessor, BroadcastTypeInfo};
+use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mates::ClanMates;
+use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::PersonalBests;
+use crate::embed_templates::TemplateValues;
+use crate::jobs::{remove_clanmate_job, JobQueue};
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    BroadcastPattern, BroadcastType, ParsedBroadcast, COFFER_DONATION_PATTERNS,
+    COFFER_WITHDRAWAL_PATTERNS, EXPELLED_FROM_CLAN_PATTERNS, INVITE_PATTERNS,
+    LEFT_THE_CLAN_PATTERNS,
+};
+use crate::osrs_broadcast_handler::{BroadcastMessageToDiscord, OSRSBroadcastHandler};
+use async_trait::async_trait;
+
+pub struct InviteProcessor;
+
+impl BroadcastTypeInfo for InviteProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::Invite
+    }
+
+    fn name(&self) -> &'static str {
+        "Invite"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        INVITE_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for InviteProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::Invite(invite_broadcast) = parsed_broadcast else {
+            return None;
+        };
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::Invite,
+                player_it_happened_to: invite_broadcast.clan_mate,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/Your_Clan_icon.png".to_string(),
+                ),
+                title: ":wave: New Invite!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct LeftTheClanProcessor;
+
+impl BroadcastTypeInfo for LeftTheClanProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::LeftTheClan
+    }
+
+    fn name(&self) -> &'static str {
+        "Left The Clan"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        LEFT_THE_CLAN_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for LeftTheClanProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::LeftTheClan(clan_mate_who_left) = parsed_broadcast else {
+            return None;
+        };
+        let job = remove_clanmate_job::remove_clanmate::new(
+            clan_mate_who_left.clone(),
+            handler.registered_guild.guild_id,
+        );
+        let _ = handler.job_queue.send_task(job).await;
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::LeftTheClan,
+                player_it_happened_to: clan_mate_who_left,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/Your_Clan_icon.png".to_string(),
+                ),
+                title: ":people_hugging: Someone has left the clan!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct ExpelledFromClanProcessor;
+
+impl BroadcastTypeInfo for ExpelledFromClanProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::ExpelledFromClan
+    }
+
+    fn name(&self) -> &'static str {
+        "Expelled From Clan"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        EXPELLED_FROM_CLAN_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for ExpelledFromClanProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::ExpelledFromClan(clan_mate_who_got_kicked) = parsed_broadcast else {
+            return None;
+        };
+        let job = remove_clanmate_job::remove_clanmate::new(
+            clan_mate_who_got_kicked.clone(),
+            handler.registered_guild.guild_id,
+        );
+        let _ = handler.job_queue.send_task(job).await;
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::ExpelledFromClan,
+                player_it_happened_to: clan_mate_who_got_kicked,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/Your_Clan_icon.png".to_string(),
+                ),
+                title: ":boot: Someone has been expelled!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct CofferDonationProcessor;
+
+impl BroadcastTypeInfo for CofferDonationProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CofferDonation
+    }
+
+    fn name(&self) -> &'static str {
+        "Coffer Donation"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        COFFER_DONATION_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CofferDonationProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::CofferDonation(coffer_donation) = parsed_broadcast else {
+            return None;
+        };
+
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::CofferDonation,
+                player_it_happened_to: coffer_donation.player,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/thumb/Clan_Coffer.png/943px-Clan_Coffer.png"
+                        .to_string(),
+                ),
+                title: ":coin: New Donation!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
+
+pub struct CofferWithdrawalProcessor;
+
+impl BroadcastTypeInfo for CofferWithdrawalProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CofferWithdrawal
+    }
+
+    fn name(&self) -> &'static str {
+        "Coffer Withdrawal"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        COFFER_WITHDRAWAL_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CofferWithdrawalProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::CofferWithdrawal(coffer_withdrawal) = parsed_broadcast else {
+            return None;
+        };
+
+        handler
+            .apply_basic_broadcast_rules(BroadcastMessageToDiscord {
+                type_of_broadcast: BroadcastType::CofferWithdrawal,
+                player_it_happened_to: coffer_withdrawal.player,
+                message: handler.clan_message.message.clone(),
+                icon_url: Some(
+                    "https://oldschool.runescape.wiki/images/thumb/Clan_Coffer.png/943px-Clan_Coffer.png"
+                        .to_string(),
+                ),
+                title: ":person_running: New Clan Coffer Withdrawal!".to_string(),
+                item_quantity: None,
+                route_to_channel: None,
+                ping_role_id: None,
+                template_values: TemplateValues::default(),
+            })
+            .await
+    }
+}
@@ -0,0 +1,724 @@
+use super::{BroadcastProcessor, BroadcastTypeInfo};
+use crate::broadcast_rules::BroadcastRuleContext;
+use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mates::ClanMates;
+use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::PersonalBests;
+use crate::embed_templates::TemplateValues;
+use crate::ge_api::ge_api::GeItemPrice;
+use crate::jobs::pet_drop_job::record_pet_drop;
+use crate::jobs::JobQueue;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    get_loot_key_icon, BroadcastPattern, BroadcastType, CollectionLogBroadcast, DropItemBroadcast,
+    ParsedBroadcast, CLUE_ITEM_PATTERNS, COLLECTION_LOG_PATTERNS, DROP_PATTERNS, LOOT_KEY_PATTERNS,
+    PET_PATTERNS, PK_PATTERNS, RAID_PATTERNS,
+};
+use crate::osrs_broadcast_handler::{BroadcastMessageToDiscord, OSRSBroadcastHandler};
+use async_trait::async_trait;
+use log::error;
+use num_format::{Locale, ToFormattedString};
+
+pub struct ItemDropProcessor;
+
+impl BroadcastTypeInfo for ItemDropProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::ItemDrop
+    }
+
+    fn name(&self) -> &'static str {
+        "Item Drop"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        DROP_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for ItemDropProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::ItemDrop(mut drop_item) = parsed_broadcast else {
+            return None;
+        };
+        drop_item.item_id = handler.get_item_id(drop_item.item_name.as_str());
+        let ge_price = handler.get_item_price(drop_item.item_id);
+        if !handler.leagues_message {
+            log_drop(
+                handler,
+                drop_item.clone(),
+                BroadcastType::ItemDrop,
+                ge_price,
+            )
+            .await;
+        }
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::ItemDrop,
+            drop_item.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(drop_item.item_name.clone());
+        context.value = handler.drop_value(&drop_item, ge_price);
+        context.drop_rate = handler.get_drop_rate(drop_item.item_name.as_str());
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues High Value drop!".to_string(),
+            false => ":tada: New High Value drop!".to_string(),
+        };
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    player_it_happened_to: drop_item.player_it_happened_to.clone(),
+                    type_of_broadcast: BroadcastType::ItemDrop,
+                    title,
+                    message: match drop_item.item_quantity {
+                        //If there is only one of the items dropped
+                        1 => match drop_item.item_value {
+                            //If the item has a value with it
+                            None => format!(
+                                "{} received a drop: {}.",
+                                drop_item.player_it_happened_to, drop_item.item_name
+                            ),
+                            _ => format!(
+                                "{} received a drop: {} ({} coins).",
+                                drop_item.player_it_happened_to,
+                                drop_item.item_name,
+                                drop_item
+                                    .item_value
+                                    .unwrap()
+                                    .to_formatted_string(&Locale::en)
+                            ),
+                        },
+                        _ => match drop_item.item_value {
+                            //If the item has a value with it
+                            None => format!(
+                                "{} received a drop: {} x {}",
+                                drop_item.player_it_happened_to,
+                                drop_item.item_name,
+                                drop_item.item_quantity
+                            ),
+                            _ => format!(
+                                "{} received a drop: {} x {} ({} coins).",
+                                drop_item.player_it_happened_to,
+                                drop_item.item_quantity,
+                                drop_item.item_name,
+                                drop_item
+                                    .item_value
+                                    .unwrap()
+                                    .to_formatted_string(&Locale::en)
+                            ),
+                        },
+                    },
+                    icon_url: drop_item.item_icon,
+                    item_quantity: drop_item.item_value,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: ge_template_values(ge_price, drop_item.item_quantity),
+                },
+            )
+            .await
+    }
+}
+
+pub struct RaidDropProcessor;
+
+impl BroadcastTypeInfo for RaidDropProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::RaidDrop
+    }
+
+    fn name(&self) -> &'static str {
+        "Raid Drop"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        RAID_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for RaidDropProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::RaidDrop(mut drop_item) = parsed_broadcast else {
+            return None;
+        };
+        drop_item.item_id = handler.get_item_id(drop_item.item_name.as_str());
+        let ge_price = handler.get_item_price(drop_item.item_id);
+        //Raid broadcasts do not say what the item is worth, guilds going by the broadcast value
+        //get the GE high price instead
+        let ge_high = ge_price
+            .map(|price| price.high)
+            .filter(|high_price| *high_price > 0);
+        drop_item.item_value = handler.drop_value(&drop_item, ge_price).or(ge_high);
+
+        if !handler.leagues_message {
+            log_drop(
+                handler,
+                drop_item.clone(),
+                BroadcastType::RaidDrop,
+                ge_price,
+            )
+            .await;
+        }
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues raid drop!".to_string(),
+            false => ":tada: New raid drop!".to_string(),
+        };
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::RaidDrop,
+            drop_item.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(drop_item.item_name.clone());
+        context.value = drop_item.item_value;
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    player_it_happened_to: drop_item.player_it_happened_to.clone(),
+                    type_of_broadcast: BroadcastType::RaidDrop,
+                    message: match drop_item.item_value {
+                        None => {
+                            format!(
+                                "{} received special loot from a raid: {}.",
+                                drop_item.player_it_happened_to, drop_item.item_name
+                            )
+                        }
+                        Some(item_value) => {
+                            format!(
+                                "{} received special loot from a raid: {} ({} coins).",
+                                drop_item.player_it_happened_to,
+                                drop_item.item_name,
+                                item_value.to_formatted_string(&Locale::en)
+                            )
+                        }
+                    },
+                    icon_url: drop_item.item_icon,
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: ge_template_values(ge_price, drop_item.item_quantity),
+                },
+            )
+            .await
+    }
+}
+
+pub struct PetDropProcessor;
+
+impl BroadcastTypeInfo for PetDropProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::PetDrop
+    }
+
+    fn name(&self) -> &'static str {
+        "Pet Drop"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        PET_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for PetDropProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::PetDrop(pet_drop) = parsed_broadcast else {
+            return None;
+        };
+        if !handler.leagues_message {
+            let job = record_pet_drop::new(pet_drop.clone(), handler.registered_guild.guild_id);
+            let _ = handler.job_queue.send_task(job).await;
+        }
+
+        let title = match (handler.leagues_message, pet_drop.duplicate) {
+            (true, true) => ":bar_chart: New Leagues duplicate Pet drop!".to_string(),
+            (true, false) => ":bar_chart: New Leagues Pet drop!".to_string(),
+            (false, true) => ":dog: New duplicate Pet drop!".to_string(),
+            (false, false) => ":tada: New Pet drop!".to_string(),
+        };
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::PetDrop,
+            pet_drop.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(pet_drop.pet_name.clone());
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::PetDrop,
+                    player_it_happened_to: pet_drop.player_it_happened_to,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: pet_drop.pet_icon,
+                    title,
+                    item_quantity: pet_drop.actions_optioned_at,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues {
+                        kc: pet_drop.actions_optioned_at,
+                        ..TemplateValues::default()
+                    },
+                },
+            )
+            .await
+    }
+}
+
+pub struct ClueItemProcessor;
+
+impl BroadcastTypeInfo for ClueItemProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::ClueItem
+    }
+
+    fn name(&self) -> &'static str {
+        "Clue Item"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        CLUE_ITEM_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for ClueItemProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::ClueItem(mut clue_item) = parsed_broadcast else {
+            return None;
+        };
+        clue_item.item_id = handler.get_item_id(clue_item.item_name.as_str());
+        let ge_price = handler.get_item_price(clue_item.item_id);
+        if !handler.leagues_message {
+            log_drop(
+                handler,
+                clue_item.clone(),
+                BroadcastType::ClueItem,
+                ge_price,
+            )
+            .await;
+        }
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::ClueItem,
+            clue_item.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(clue_item.item_name.clone());
+        context.value = handler.drop_value(&clue_item, ge_price);
+        context.drop_rate = handler.get_drop_rate(clue_item.item_name.as_str());
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues High Value drop!".to_string(),
+            false => ":tada: New High Value drop!".to_string(),
+        };
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    player_it_happened_to: clue_item.player_it_happened_to.clone(),
+                    type_of_broadcast: BroadcastType::ClueItem,
+                    title,
+                    message: match clue_item.item_quantity {
+                        //If there is only one of the items dropped
+                        1 => match clue_item.item_value {
+                            //If the item has a value with it
+                            None => format!(
+                                "{} received a clue item: {}.",
+                                clue_item.player_it_happened_to, clue_item.item_name
+                            ),
+                            _ => format!(
+                                "{} received a clue item: {} ({} coins).",
+                                clue_item.player_it_happened_to,
+                                clue_item.item_name,
+                                clue_item
+                                    .item_value
+                                    .unwrap()
+                                    .to_formatted_string(&Locale::en)
+                            ),
+                        },
+                        _ => match clue_item.item_value {
+                            //If the item has a value with it
+                            None => format!(
+                                "{} received a clue item: {}",
+                                clue_item.player_it_happened_to, clue_item.item_name,
+                            ),
+                            _ => format!(
+                                "{} received a clue item: {} ({} coins).",
+                                clue_item.player_it_happened_to,
+                                clue_item.item_name,
+                                clue_item
+                                    .item_value
+                                    .unwrap()
+                                    .to_formatted_string(&Locale::en)
+                            ),
+                        },
+                    },
+                    icon_url: clue_item.item_icon,
+                    item_quantity: clue_item.item_value,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: ge_template_values(ge_price, clue_item.item_quantity),
+                },
+            )
+            .await
+    }
+}
+
+pub struct PkProcessor;
+
+impl BroadcastTypeInfo for PkProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::Pk
+    }
+
+    fn name(&self) -> &'static str {
+        "Pk"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        PK_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for PkProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::Pk(pk_broadcast) = parsed_broadcast else {
+            return None;
+        };
+        let mut context = BroadcastRuleContext::new(BroadcastType::Pk, pk_broadcast.winner.clone());
+        context.value = pk_broadcast.gp_exchanged;
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues PK!".to_string(),
+            false => ":crossed_swords: New PK!".to_string(),
+        };
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::Pk,
+                    player_it_happened_to: pk_broadcast.winner,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some("https://oldschool.runescape.wiki/images/Skull.png".to_string()),
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct CollectionLogProcessor;
+
+impl BroadcastTypeInfo for CollectionLogProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CollectionLog
+    }
+
+    fn name(&self) -> &'static str {
+        "Collection Log"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        COLLECTION_LOG_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CollectionLogProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::CollectionLog(collection_log_broadcast) = parsed_broadcast else {
+            return None;
+        };
+        if !handler.leagues_message {
+            let possible_clan_mate = handler
+                .clan_mates_db
+                .find_or_create_clan_mate(
+                    handler.registered_guild.guild_id,
+                    collection_log_broadcast.player_it_happened_to.clone(),
+                )
+                .await;
+            let _ = match possible_clan_mate {
+                Ok(clan_mate) => {
+                    handler
+                        .collection_log_db
+                        .update_or_create(
+                            clan_mate.guild_id,
+                            clan_mate.id,
+                            collection_log_broadcast.log_slots,
+                            Some(collection_log_broadcast.item_name.clone()),
+                        )
+                        .await
+                }
+                Err(error) => {
+                    error!("{:?}", error);
+                    Err(error)
+                }
+            };
+            log_collection_log_item(handler, &collection_log_broadcast).await;
+        }
+        let mut context = BroadcastRuleContext::new(
+            BroadcastType::CollectionLog,
+            collection_log_broadcast.player_it_happened_to.clone(),
+        );
+        context.item_name = Some(collection_log_broadcast.item_name.clone());
+        context.clog_percentage = handler.clogs.as_ref().and_then(|clogs| {
+            clogs
+                .iter()
+                .find(|clog| clog.name == collection_log_broadcast.item_name)
+                .map(|clog| clog.percentage)
+        });
+        if context.clog_percentage.is_none() {
+            println!(
+                "No cached percentage found for {}",
+                collection_log_broadcast.item_name
+            );
+        }
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues collection log item!".to_string(),
+            false => ":tada: New collection log item!".to_string(),
+        };
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::CollectionLog,
+                    player_it_happened_to: collection_log_broadcast.player_it_happened_to,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: collection_log_broadcast.item_icon,
+                    title,
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                },
+            )
+            .await
+    }
+}
+
+pub struct LootKeyProcessor;
+
+impl BroadcastTypeInfo for LootKeyProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::LootKey
+    }
+
+    fn name(&self) -> &'static str {
+        "Loot Key"
+    }
+
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        LOOT_KEY_PATTERNS
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for LootKeyProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let ParsedBroadcast::LootKey(loot_key) = parsed_broadcast else {
+            return None;
+        };
+        //Logged as a drop so loot keys show up in the drop history
+        if !handler.leagues_message {
+            log_drop(
+                handler,
+                DropItemBroadcast {
+                    player_it_happened_to: loot_key.player.clone(),
+                    item_name: "Loot key".to_string(),
+                    item_quantity: 1,
+                    item_value: Some(loot_key.value),
+                    item_icon: Some(get_loot_key_icon()),
+                    drop_source: None,
+                    item_id: None,
+                },
+                BroadcastType::LootKey,
+                None,
+            )
+            .await;
+        }
+        let mut context =
+            BroadcastRuleContext::new(BroadcastType::LootKey, loot_key.player.clone());
+        context.value = Some(loot_key.value);
+
+        let title = match handler.leagues_message {
+            true => ":bar_chart: New Leagues Loot Key opened!".to_string(),
+            false => ":key: New Loot Key opened!".to_string(),
+        };
+
+        handler
+            .apply_broadcast_rules(
+                context,
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::LootKey,
+                    player_it_happened_to: loot_key.player,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(get_loot_key_icon()),
+                    title,
+                    item_quantity: Some(1),
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues {
+                        item: Some("Loot key".to_string()),
+                        value: Some(loot_key.value),
+                        ..TemplateValues::default()
+                    },
+                },
+            )
+            .await
+    }
+}
+
+/// The whole drops GE prices for the embed, only when the item has traded both ways
+fn ge_template_values(ge_price: Option<&GeItemPrice>, quantity: i64) -> TemplateValues {
+    match ge_price {
+        Some(price) if price.high > 0 && price.low > 0 => TemplateValues {
+            ge_high: Some(price.high * quantity.max(1)),
+            ge_low: Some(price.low * quantity.max(1)),
+            ..TemplateValues::default()
+        },
+        _ => TemplateValues::default(),
+    }
+}
+
+/// Adds the new collection log item to the loot ledger without a value,
+/// the drop broadcast for the same item already counts its GP
+async fn log_collection_log_item<
+    T: DropLogs,
+    CL: ClanMateCollectionLogTotals,
+    CM: ClanMates,
+    PB: PersonalBests,
+    J: JobQueue,
+>(
+    handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+    collection_log_broadcast: &CollectionLogBroadcast,
+) {
+    let item_id = handler.get_item_id(collection_log_broadcast.item_name.as_str());
+    log_drop(
+        handler,
+        DropItemBroadcast {
+            player_it_happened_to: collection_log_broadcast.player_it_happened_to.clone(),
+            item_name: collection_log_broadcast.item_name.clone(),
+            item_quantity: 1,
+            item_value: None,
+            item_icon: collection_log_broadcast.item_icon.clone(),
+            drop_source: None,
+            item_id,
+        },
+        BroadcastType::CollectionLog,
+        handler.get_item_price(item_id),
+    )
+    .await;
+}
+
+/// Adds the drop to the loot ledger with the items GE price from when it dropped
+async fn log_drop<
+    T: DropLogs,
+    CL: ClanMateCollectionLogTotals,
+    CM: ClanMates,
+    PB: PersonalBests,
+    J: JobQueue,
+>(
+    handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+    drop_item: DropItemBroadcast,
+    broadcast_type: BroadcastType,
+    ge_price: Option<&GeItemPrice>,
+) {
+    let ge_price = ge_price
+        .map(|price| price.high)
+        .filter(|high_price| *high_price > 0);
+    if let Err(e) = handler
+        .drop_log_db
+        .new_drop_log(
+            drop_item,
+            broadcast_type,
+            ge_price,
+            handler.registered_guild.guild_id,
+        )
+        .await
+    {
+        error!("Failed to log the drop: {:?}", e);
+    }
+}
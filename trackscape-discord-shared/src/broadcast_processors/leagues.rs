@@ -0,0 +1,290 @@
+use super::{BroadcastProcessor, BroadcastTypeInfo};
+use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mate_leagues_progress::LeaguesProgressUpdate;
+use crate::database::clan_mates::ClanMates;
+use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::PersonalBests;
+use crate::embed_templates::TemplateValues;
+use crate::jobs::JobQueue;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    area_unlock_broadcast_extractor, combat_masteries_broadcast_extractor, get_leagues_area_icon,
+    get_leagues_relic_icon, leagues_rank_broadcast_extractor, relic_tier_broadcast_extractor,
+    BroadcastType, ParsedBroadcast,
+};
+use crate::osrs_broadcast_handler::{BroadcastMessageToDiscord, OSRSBroadcastHandler};
+use async_trait::async_trait;
+use log::error;
+
+pub struct AreaUnlockProcessor;
+
+impl BroadcastTypeInfo for AreaUnlockProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::AreaUnlock
+    }
+
+    fn name(&self) -> &'static str {
+        "Area Unlock"
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for AreaUnlockProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        _parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let possible_area_unlock =
+            area_unlock_broadcast_extractor(handler.clan_message.message.clone());
+        let leagues_broadcast = match possible_area_unlock {
+            None => {
+                error!(
+                    "Failed to extract area unlock info from message: {}",
+                    handler.clan_message.message.clone()
+                );
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::AreaUnlock,
+                    player_it_happened_to: "".to_string(),
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some("https://oldschool.runescape.wiki/images/Leagues_V-_Raging_Echoes_-_Summer_Summit_2024_%282%29.png?3877a".to_string()),
+                    title: ":new: New Leagues Area Unlock!".to_string(),
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                }
+            }
+            Some(area_unlock) => {
+                handler
+                    .record_leagues_progress(LeaguesProgressUpdate {
+                        player: area_unlock.player.clone(),
+                        area: area_unlock.area.clone(),
+                        areas_unlocked: area_unlock.areas_unlocked,
+                        ..Default::default()
+                    })
+                    .await;
+                let icon_url = match area_unlock.area {
+                    Some(area) => get_leagues_area_icon(area),
+                    None => "https://oldschool.runescape.wiki/images/Leagues_V-_Raging_Echoes_-_Summer_Summit_2024_%282%29.png?3877a".to_string(),
+                };
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::AreaUnlock,
+                    player_it_happened_to: area_unlock.player,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(icon_url),
+                    title: ":new: New Leagues Area Unlock!".to_string(),
+                    item_quantity: area_unlock.areas_unlocked,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                }
+            }
+        };
+        handler.apply_basic_broadcast_rules(leagues_broadcast).await
+    }
+}
+
+pub struct LeaguesRankProcessor;
+
+impl BroadcastTypeInfo for LeaguesRankProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::LeaguesRank
+    }
+
+    fn name(&self) -> &'static str {
+        "Leagues Rank"
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for LeaguesRankProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        _parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let possible_leagues_rank =
+            leagues_rank_broadcast_extractor(handler.clan_message.message.clone());
+        let player_it_happened_to = match possible_leagues_rank {
+            None => {
+                error!(
+                    "Failed to extract leagues rank info from message: {}",
+                    handler.clan_message.message.clone()
+                );
+                "".to_string()
+            }
+            Some(leagues_rank) => {
+                handler
+                    .record_leagues_progress(LeaguesProgressUpdate {
+                        player: leagues_rank.player.clone(),
+                        leagues_rank: Some(leagues_rank.rank),
+                        ..Default::default()
+                    })
+                    .await;
+                leagues_rank.player
+            }
+        };
+        let leagues_broadcast = BroadcastMessageToDiscord {
+            type_of_broadcast: BroadcastType::LeaguesRank,
+            player_it_happened_to,
+            message: handler.clan_message.message.clone(),
+            icon_url: Some("https://oldschool.runescape.wiki/images/thumb/Leagues_icon.png/260px-Leagues_icon.png?0570b".to_string()),
+            title: ":new: New Leagues Rank Unlock!".to_string(),
+            item_quantity: None,
+            route_to_channel: None,
+            ping_role_id: None,
+            template_values: TemplateValues::default(),
+        };
+        handler.apply_basic_broadcast_rules(leagues_broadcast).await
+    }
+}
+
+pub struct CombatMasteriesProcessor;
+
+impl BroadcastTypeInfo for CombatMasteriesProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::CombatMasteries
+    }
+
+    fn name(&self) -> &'static str {
+        "Combat Masteries"
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for CombatMasteriesProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        _parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let possible_combat_mastery =
+            combat_masteries_broadcast_extractor(handler.clan_message.message.clone());
+        let (player_it_happened_to, item_quantity) = match possible_combat_mastery {
+            None => {
+                error!(
+                    "Failed to extract combat mastery info from message: {}",
+                    handler.clan_message.message.clone()
+                );
+                ("".to_string(), None)
+            }
+            Some(combat_mastery) => {
+                //Per style points aren't comparable to the overall count so only the total is kept
+                if combat_mastery.combat_mastery.is_none() {
+                    handler
+                        .record_leagues_progress(LeaguesProgressUpdate {
+                            player: combat_mastery.player.clone(),
+                            combat_mastery_points: Some(combat_mastery.rank),
+                            ..Default::default()
+                        })
+                        .await;
+                }
+                (combat_mastery.player, Some(combat_mastery.rank))
+            }
+        };
+        let leagues_broadcast = BroadcastMessageToDiscord {
+            type_of_broadcast: BroadcastType::CombatMasteries,
+            player_it_happened_to,
+            message: handler.clan_message.message.clone(),
+            icon_url: Some("https://oldschool.runescape.wiki/images/Raging_Echoes_League_combat_masteries_icon.png?4e2c2".to_string()),
+            title: ":new: New Leagues Combat Mastery earned!".to_string(),
+            item_quantity,
+            route_to_channel: None,
+            ping_role_id: None,
+            template_values: TemplateValues::default(),
+        };
+        handler.apply_basic_broadcast_rules(leagues_broadcast).await
+    }
+}
+
+pub struct RelicTierProcessor;
+
+impl BroadcastTypeInfo for RelicTierProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::RelicTier
+    }
+
+    fn name(&self) -> &'static str {
+        "Relic Tier"
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for RelicTierProcessor
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        _parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let possible_relic_tier =
+            relic_tier_broadcast_extractor(handler.clan_message.message.clone());
+        let leagues_broadcast = match possible_relic_tier {
+            None => {
+                error!(
+                    "Failed to extract relic tier info from message: {}",
+                    handler.clan_message.message.clone()
+                );
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::RelicTier,
+                    player_it_happened_to: "".to_string(),
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some("https://oldschool.runescape.wiki/images/thumb/Leagues_icon.png/260px-Leagues_icon.png?0570b".to_string()),
+                    title: ":new: New Leagues Relic unlocked!".to_string(),
+                    item_quantity: None,
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                }
+            }
+            Some(relic_tier) => {
+                handler
+                    .record_leagues_progress(LeaguesProgressUpdate {
+                        player: relic_tier.player.clone(),
+                        relic_tier: Some(relic_tier.tier),
+                        ..Default::default()
+                    })
+                    .await;
+                BroadcastMessageToDiscord {
+                    type_of_broadcast: BroadcastType::RelicTier,
+                    player_it_happened_to: relic_tier.player,
+                    message: handler.clan_message.message.clone(),
+                    icon_url: Some(get_leagues_relic_icon(relic_tier.relic, relic_tier.tier)),
+                    title: ":new: New Leagues Relic unlocked!".to_string(),
+                    item_quantity: Some(relic_tier.tier),
+                    route_to_channel: None,
+                    ping_role_id: None,
+                    template_values: TemplateValues::default(),
+                }
+            }
+        };
+        handler.apply_basic_broadcast_rules(leagues_broadcast).await
+    }
+}
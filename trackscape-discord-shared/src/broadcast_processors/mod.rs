@@ -0,0 +1,174 @@
+pub mod achievements;
+pub mod clan;
+pub mod drops;
+pub mod leagues;
+
+use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mates::ClanMates;
+use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::PersonalBests;
+use crate::jobs::JobQueue;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    BroadcastPattern, BroadcastType, ParsedBroadcast,
+};
+use crate::osrs_broadcast_handler::{BroadcastMessageToDiscord, OSRSBroadcastHandler};
+use achievements::{
+    ClanRecordType, CombatAchievementTierProcessor, CombatTaskProcessor, DiaryProcessor,
+    HardcoreDeathProcessor, LevelMilestoneProcessor, PersonalBestProcessor, QuestProcessor,
+    XPMilestoneProcessor,
+};
+use async_trait::async_trait;
+use clan::{
+    CofferDonationProcessor, CofferWithdrawalProcessor, ExpelledFromClanProcessor, InviteProcessor,
+    LeftTheClanProcessor,
+};
+use drops::{
+    ClueItemProcessor, CollectionLogProcessor, ItemDropProcessor, LootKeyProcessor,
+    PetDropProcessor, PkProcessor, RaidDropProcessor,
+};
+use leagues::{
+    AreaUnlockProcessor, CombatMasteriesProcessor, LeaguesRankProcessor, RelicTierProcessor,
+};
+
+/// What the rest of the app needs to know about a broadcast type without processing one
+pub trait BroadcastTypeInfo: Sync {
+    fn broadcast_type(&self) -> BroadcastType;
+
+    /// Shown to users and saved on the guild, so it should not change once released
+    fn name(&self) -> &'static str;
+
+    /// Whether guilds can turn the broadcast type on and off
+    fn toggleable(&self) -> bool {
+        true
+    }
+
+    /// The messages that are this type. A message matching the patterns of more than one
+    /// type is left as Unknown rather than guessing
+    fn patterns(&self) -> &'static [BroadcastPattern] {
+        &[]
+    }
+}
+
+/// Turns a parsed broadcast of its type into the message for Discord.
+/// It saves anything the broadcast tells us and runs the guilds rules, None when nothing should be sent
+#[async_trait]
+pub trait BroadcastProcessor<
+    T: DropLogs,
+    CL: ClanMateCollectionLogTotals,
+    CM: ClanMates,
+    PB: PersonalBests,
+    J: JobQueue,
+>: BroadcastTypeInfo
+{
+    async fn process(
+        &self,
+        handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord>;
+}
+
+/// Messages that did not match any broadcast, they are never sent
+pub struct UnknownProcessor;
+
+impl BroadcastTypeInfo for UnknownProcessor {
+    fn broadcast_type(&self) -> BroadcastType {
+        BroadcastType::Unknown
+    }
+
+    fn name(&self) -> &'static str {
+        "Unknown"
+    }
+
+    fn toggleable(&self) -> bool {
+        false
+    }
+}
+
+#[async_trait]
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > BroadcastProcessor<T, CL, CM, PB, J> for UnknownProcessor
+{
+    async fn process(
+        &self,
+        _handler: &OSRSBroadcastHandler<T, CL, CM, PB, J>,
+        _parsed_broadcast: ParsedBroadcast,
+    ) -> Option<BroadcastMessageToDiscord> {
+        None
+    }
+}
+
+macro_rules! broadcast_registry {
+    (
+        processors: [$($processor:expr),+ $(,)?],
+        types_only: [$($type_info:expr),* $(,)?] $(,)?
+    ) => {
+        /// Every broadcast type, the order is the order they are listed to users in
+        pub static BROADCAST_TYPES: &[&dyn BroadcastTypeInfo] =
+            &[$(&$processor,)+ $(&$type_info,)*];
+
+        /// The processors in the same order as [BROADCAST_TYPES], types sent from jobs have none
+        pub fn broadcast_processors<
+            'a,
+            T: DropLogs + 'a,
+            CL: ClanMateCollectionLogTotals + 'a,
+            CM: ClanMates + 'a,
+            PB: PersonalBests + 'a,
+            J: JobQueue + 'a,
+        >() -> Vec<&'a dyn BroadcastProcessor<T, CL, CM, PB, J>> {
+            vec![$(&$processor),+]
+        }
+    };
+}
+
+//New broadcast types only need a processor added here, types only sent from jobs go in types_only
+broadcast_registry!(
+    processors: [
+        ItemDropProcessor,
+        RaidDropProcessor,
+        PetDropProcessor,
+        ClueItemProcessor,
+        CombatTaskProcessor,
+        CombatAchievementTierProcessor,
+        QuestProcessor,
+        DiaryProcessor,
+        PkProcessor,
+        InviteProcessor,
+        XPMilestoneProcessor,
+        LevelMilestoneProcessor,
+        CollectionLogProcessor,
+        LeftTheClanProcessor,
+        ExpelledFromClanProcessor,
+        CofferDonationProcessor,
+        CofferWithdrawalProcessor,
+        PersonalBestProcessor,
+        LootKeyProcessor,
+        HardcoreDeathProcessor,
+        AreaUnlockProcessor,
+        LeaguesRankProcessor,
+        CombatMasteriesProcessor,
+        RelicTierProcessor,
+        UnknownProcessor,
+    ],
+    types_only: [ClanRecordType],
+);
+
+/// The processor registered for the broadcast type
+pub fn processor_for<
+    'a,
+    T: DropLogs + 'a,
+    CL: ClanMateCollectionLogTotals + 'a,
+    CM: ClanMates + 'a,
+    PB: PersonalBests + 'a,
+    J: JobQueue + 'a,
+>(
+    broadcast_type: &BroadcastType,
+) -> Option<&'a dyn BroadcastProcessor<T, CL, CM, PB, J>> {
+    broadcast_processors()
+        .into_iter()
+        .find(|processor| processor.broadcast_type() == *broadcast_type)
+}
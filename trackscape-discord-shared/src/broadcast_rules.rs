@@ -0,0 +1,550 @@
+use crate::database::clan_mates::name_compare;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{AccountType, BroadcastType};
+use crate::wiki_api::wiki_api::format_drop_rate;
+use log::error;
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+//The rules are checked for every broadcast, so each pattern is only compiled the first time it
+//is seen. Patterns that do not compile are kept as None so the error is only logged once
+static ITEM_PATTERNS: Lazy<Mutex<HashMap<String, Option<Regex>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn item_matches(pattern: &str, item_name: &str) -> bool {
+    let mut item_patterns = ITEM_PATTERNS
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    let regex =
+        item_patterns.entry(pattern.to_string()).or_insert_with(|| {
+            match RegexBuilder::new(pattern).case_insensitive(true).build() {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    error!("Invalid broadcast rule pattern {}: {:?}", pattern, e);
+                    None
+                }
+            }
+        });
+    regex
+        .as_ref()
+        .is_some_and(|regex| regex.is_match(item_name))
+}
+
+/// A single check against a broadcast. Broadcasts where the value, tier, percentage or
+/// account type is unknown never match a condition on it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RuleCondition {
+    BroadcastType(Vec<BroadcastType>),
+    Player(Vec<String>),
+    /// Case insensitive regex checked against the item, pet, quest, diary, task or activity name
+    ItemMatches(String),
+    /// Inclusive range of the gp value of the broadcast
+    ValueBetween {
+        min: Option<i64>,
+        max: Option<i64>,
+    },
+    /// Percentage of players with the collection log item is higher than this, so more common
+    ClogPercentageAbove(f64),
+    /// Percentage of players with the collection log item is lower than this, so rarer
+    ClogPercentageBelow(f64),
+    /// The wiki drop rate of the item is rarer than 1 in this many
+    DropRateRarerThan(f64),
+    /// The wiki drop rate of the item is more common than 1 in this many
+    DropRateCommonerThan(f64),
+    /// Ranking of the quest difficulty, diary tier or combat achievement tier is lower than this
+    TierBelow(usize),
+    AccountType(Vec<AccountType>),
+    Not(Box<RuleCondition>),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RuleAction {
+    Allow,
+    Deny,
+    /// Sends the broadcast to this channel instead of the broadcast channel
+    Route(u64),
+}
+
+/// The setting command that saved a rule, running the command again replaces the rules it saved.
+/// The order of the variants is the order the settings are checked in
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum RuleSetting {
+    Toggle,
+    CustomDropFilter(BroadcastType),
+    AccountTypeFilter(BroadcastType),
+    CollectionLogMaxPercentage,
+    MinQuestDifficulty,
+    MinDiaryTier,
+    MinCombatAchievementTier,
+    PkValueThreshold,
+    LootKeyValueThreshold,
+    DropRarityThreshold,
+    AccountTypeDropPriceThreshold(AccountType),
+    DropPriceThreshold,
+}
+
+impl RuleSetting {
+    fn order(&self) -> usize {
+        match self {
+            RuleSetting::Toggle => 0,
+            RuleSetting::CustomDropFilter(_) => 1,
+            RuleSetting::AccountTypeFilter(_) => 2,
+            RuleSetting::CollectionLogMaxPercentage => 3,
+            RuleSetting::MinQuestDifficulty => 4,
+            RuleSetting::MinDiaryTier => 5,
+            RuleSetting::MinCombatAchievementTier => 6,
+            RuleSetting::PkValueThreshold => 7,
+            RuleSetting::LootKeyValueThreshold => 8,
+            RuleSetting::DropRarityThreshold => 9,
+            RuleSetting::AccountTypeDropPriceThreshold(_) => 10,
+            RuleSetting::DropPriceThreshold => 11,
+        }
+    }
+
+    /// The broadcast types the setting is for
+    fn broadcast_types(&self) -> Vec<BroadcastType> {
+        match self {
+            RuleSetting::Toggle => Vec::new(),
+            RuleSetting::CustomDropFilter(broadcast_type)
+            | RuleSetting::AccountTypeFilter(broadcast_type) => vec![broadcast_type.clone()],
+            RuleSetting::CollectionLogMaxPercentage => vec![BroadcastType::CollectionLog],
+            RuleSetting::MinQuestDifficulty => vec![BroadcastType::Quest],
+            RuleSetting::MinDiaryTier => vec![BroadcastType::Diary],
+            RuleSetting::MinCombatAchievementTier => vec![
+                BroadcastType::CombatTask,
+                BroadcastType::CombatAchievementTier,
+            ],
+            RuleSetting::PkValueThreshold => vec![BroadcastType::Pk],
+            RuleSetting::LootKeyValueThreshold => vec![BroadcastType::LootKey],
+            RuleSetting::DropRarityThreshold
+            | RuleSetting::AccountTypeDropPriceThreshold(_)
+            | RuleSetting::DropPriceThreshold => vec![
+                BroadcastType::ItemDrop,
+                BroadcastType::RaidDrop,
+                BroadcastType::ClueItem,
+            ],
+        }
+    }
+
+    /// The command that changes the setting
+    pub fn command(&self) -> &'static str {
+        match self {
+            RuleSetting::Toggle => "/toggle",
+            RuleSetting::CustomDropFilter(_) => "/set_custom_drop_broadcast_filter",
+            RuleSetting::AccountTypeFilter(_) => "/account_type_filter",
+            RuleSetting::CollectionLogMaxPercentage => "/set_clog_max_percentage",
+            RuleSetting::MinQuestDifficulty => "/quests",
+            RuleSetting::MinDiaryTier => "/diaries",
+            RuleSetting::MinCombatAchievementTier => "/combat_achievements",
+            RuleSetting::PkValueThreshold
+            | RuleSetting::LootKeyValueThreshold
+            | RuleSetting::AccountTypeDropPriceThreshold(_)
+            | RuleSetting::DropPriceThreshold => "/threshold",
+            RuleSetting::DropRarityThreshold => "/set_drop_rarity",
+        }
+    }
+}
+
+/// Applies its action when every condition matches. A rule without conditions matches everything
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BroadcastRule {
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    //None for the rules added with /add_broadcast_rule
+    #[serde(default)]
+    pub setting: Option<RuleSetting>,
+}
+
+/// What the rules know about the broadcast being checked
+#[derive(Debug, Clone)]
+pub struct BroadcastRuleContext {
+    pub broadcast_type: BroadcastType,
+    pub player: String,
+    pub item_name: Option<String>,
+    pub value: Option<i64>,
+    pub clog_percentage: Option<f64>,
+    //The N in the items 1/N wiki drop rate
+    pub drop_rate: Option<f64>,
+    pub tier: Option<usize>,
+    pub account_type: Option<AccountType>,
+}
+
+impl BroadcastRuleContext {
+    pub fn new(broadcast_type: BroadcastType, player: String) -> Self {
+        Self {
+            broadcast_type,
+            player,
+            item_name: None,
+            value: None,
+            clog_percentage: None,
+            drop_rate: None,
+            tier: None,
+            account_type: None,
+        }
+    }
+}
+
+impl RuleCondition {
+    pub fn matches(&self, context: &BroadcastRuleContext) -> bool {
+        match self {
+            RuleCondition::BroadcastType(broadcast_types) => {
+                broadcast_types.contains(&context.broadcast_type)
+            }
+            RuleCondition::Player(players) => players
+                .iter()
+                .any(|player| name_compare(player, &context.player)),
+            RuleCondition::ItemMatches(pattern) => match &context.item_name {
+                Some(item_name) => item_matches(pattern, item_name),
+                None => false,
+            },
+            RuleCondition::ValueBetween { min, max } => match context.value {
+                Some(value) => {
+                    !min.is_some_and(|min| value < min) && !max.is_some_and(|max| value > max)
+                }
+                None => false,
+            },
+            RuleCondition::ClogPercentageAbove(percentage) => match context.clog_percentage {
+                Some(clog_percentage) => clog_percentage > *percentage,
+                None => false,
+            },
+            RuleCondition::ClogPercentageBelow(percentage) => match context.clog_percentage {
+                Some(clog_percentage) => clog_percentage < *percentage,
+                None => false,
+            },
+            RuleCondition::DropRateRarerThan(one_in) => match context.drop_rate {
+                Some(drop_rate) => drop_rate > *one_in,
+                None => false,
+            },
+            RuleCondition::DropRateCommonerThan(one_in) => match context.drop_rate {
+                Some(drop_rate) => drop_rate < *one_in,
+                None => false,
+            },
+            RuleCondition::TierBelow(ranking) => match context.tier {
+                Some(tier) => tier < *ranking,
+                None => false,
+            },
+            RuleCondition::AccountType(account_types) => match &context.account_type {
+                Some(account_type) => account_types.contains(account_type),
+                None => false,
+            },
+            RuleCondition::Not(condition) => !condition.matches(context),
+        }
+    }
+
+    fn uses_account_type(&self) -> bool {
+        match self {
+            RuleCondition::AccountType(_) => true,
+            RuleCondition::Not(condition) => condition.uses_account_type(),
+            _ => false,
+        }
+    }
+
+    pub fn to_string(&self) -> String {
+        match self {
+            RuleCondition::BroadcastType(broadcast_types) => format!(
+                "type is {}",
+                broadcast_types
+                    .iter()
+                    .map(|broadcast_type| broadcast_type.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" or ")
+            ),
+            RuleCondition::Player(players) => format!("player is {}", players.join(" or ")),
+            RuleCondition::ItemMatches(pattern) => format!("name matches `{}`", pattern),
+            RuleCondition::ValueBetween { min, max } => match (min, max) {
+                (Some(min), Some(max)) => format!("value is {} to {} gp", min, max),
+                (Some(min), None) => format!("value is at least {} gp", min),
+                (None, Some(max)) => format!("value is at most {} gp", max),
+                (None, None) => "has a value".to_string(),
+            },
+            RuleCondition::ClogPercentageAbove(percentage) => {
+                format!("more than {}% of players have the log item", percentage)
+            }
+            RuleCondition::ClogPercentageBelow(percentage) => {
+                format!("less than {}% of players have the log item", percentage)
+            }
+            RuleCondition::DropRateRarerThan(one_in) => {
+                format!("drop rate is rarer than {}", format_drop_rate(*one_in))
+            }
+            RuleCondition::DropRateCommonerThan(one_in) => {
+                format!(
+                    "drop rate is more common than {}",
+                    format_drop_rate(*one_in)
+                )
+            }
+            RuleCondition::TierBelow(ranking) => format!("tier ranking is below {}", ranking),
+            RuleCondition::AccountType(account_types) => format!(
+                "account type is {}",
+                account_types
+                    .iter()
+                    .map(|account_type| account_type.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" or ")
+            ),
+            RuleCondition::Not(condition) => format!("not ({})", condition.to_string()),
+        }
+    }
+}
+
+impl BroadcastRule {
+    pub fn matches(&self, context: &BroadcastRuleContext) -> bool {
+        self.conditions
+            .iter()
+            .all(|condition| condition.matches(context))
+    }
+
+    /// The account type needs a database lookup so it is only loaded when a rule checks it
+    pub fn uses_account_type(&self) -> bool {
+        self.conditions
+            .iter()
+            .any(|condition| condition.uses_account_type())
+    }
+
+    pub fn to_string(&self) -> String {
+        let rule = self.describe();
+        match &self.setting {
+            Some(setting) => format!("{} (set with {})", rule, setting.command()),
+            None => rule,
+        }
+    }
+
+    fn describe(&self) -> String {
+        let action = match &self.action {
+            RuleAction::Allow => "Allow".to_string(),
+            RuleAction::Deny => "Deny".to_string(),
+            RuleAction::Route(channel_id) => format!("Send to <#{}>", channel_id),
+        };
+        match self.conditions.is_empty() {
+            true => format!("{} every broadcast", action),
+            false => format!(
+                "{} when {}",
+                action,
+                self.conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" and ")
+            ),
+        }
+    }
+}
+
+/// The first rule that matches decides what happens, broadcasts no rule matches are allowed
+pub fn evaluate_broadcast_rules(
+    rules: &[BroadcastRule],
+    context: &BroadcastRuleContext,
+) -> RuleAction {
+    rules
+        .iter()
+        .find(|rule| rule.matches(context))
+        .map_or(RuleAction::Allow, |rule| rule.action.clone())
+}
+
+/// Adds a rule made with /add_broadcast_rule. It goes after the guilds other rules but before the
+/// ones saved by the setting commands, so the guilds own rules can override the settings
+pub fn add_rule(rules: &mut Vec<BroadcastRule>, rule: BroadcastRule) {
+    let position = rules
+        .iter()
+        .position(|rule| rule.setting.is_some())
+        .unwrap_or(rules.len());
+    rules.insert(position, rule);
+}
+
+/// Replaces the rules the setting saved before with the new ones, no new rules turns the setting
+/// off. They go before the settings that are checked after them so the settings keep working
+/// together, like rare drops being allowed before the value threshold denies them
+pub fn set_setting_rules(
+    rules: &mut Vec<BroadcastRule>,
+    setting: RuleSetting,
+    new_rules: Vec<BroadcastRule>,
+) {
+    rules.retain(|rule| rule.setting.as_ref() != Some(&setting));
+    let position = rules
+        .iter()
+        .position(|rule| {
+            rule.setting
+                .as_ref()
+                .is_some_and(|rule_setting| rule_setting.order() > setting.order())
+        })
+        .unwrap_or(rules.len());
+    rules.splice(position..position, new_rules);
+}
+
+/// The conditions of the rules the setting saved
+fn setting_conditions<'a>(
+    rules: &'a [BroadcastRule],
+    setting: &'a RuleSetting,
+) -> impl Iterator<Item = &'a RuleCondition> {
+    rules
+        .iter()
+        .filter(move |rule| rule.setting.as_ref() == Some(setting))
+        .flat_map(|rule| rule.conditions.iter())
+}
+
+/// The broadcast types turned off with /toggle
+pub fn disallowed_broadcast_types(rules: &[BroadcastRule]) -> Vec<BroadcastType> {
+    setting_conditions(rules, &RuleSetting::Toggle)
+        .find_map(|condition| match condition {
+            RuleCondition::BroadcastType(broadcast_types) => Some(broadcast_types.clone()),
+            _ => None,
+        })
+        .unwrap_or_default()
+}
+
+/// The account types the broadcast type is only sent for, empty when it is sent for all of them
+pub fn allowed_account_types(
+    rules: &[BroadcastRule],
+    broadcast_type: &BroadcastType,
+) -> Vec<AccountType> {
+    let setting = RuleSetting::AccountTypeFilter(broadcast_type.clone());
+    let allowed_account_types = setting_conditions(rules, &setting)
+        .find_map(|condition| match condition {
+            RuleCondition::Not(condition) => match condition.as_ref() {
+                RuleCondition::AccountType(account_types) => Some(account_types.clone()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .unwrap_or_default();
+    allowed_account_types
+}
+
+/// The pattern /set_custom_drop_broadcast_filter saved for the broadcast type
+pub fn custom_drop_filter(
+    rules: &[BroadcastRule],
+    broadcast_type: &BroadcastType,
+) -> Option<String> {
+    let setting = RuleSetting::CustomDropFilter(broadcast_type.clone());
+    let pattern = setting_conditions(rules, &setting).find_map(|condition| match condition {
+        RuleCondition::ItemMatches(pattern) => Some(pattern.clone()),
+        _ => None,
+    });
+    pattern
+}
+
+fn setting_rule(
+    setting: &RuleSetting,
+    mut conditions: Vec<RuleCondition>,
+    action: RuleAction,
+) -> BroadcastRule {
+    conditions.insert(0, RuleCondition::BroadcastType(setting.broadcast_types()));
+    BroadcastRule {
+        conditions,
+        action,
+        setting: Some(setting.clone()),
+    }
+}
+
+/// Denies the broadcast types turned off with /toggle
+pub fn toggle_rules(disallowed_broadcast_types: Vec<BroadcastType>) -> Vec<BroadcastRule> {
+    if disallowed_broadcast_types.is_empty() {
+        return Vec::new();
+    }
+    vec![BroadcastRule {
+        conditions: vec![RuleCondition::BroadcastType(disallowed_broadcast_types)],
+        action: RuleAction::Deny,
+        setting: Some(RuleSetting::Toggle),
+    }]
+}
+
+/// Denies the broadcasts of the type with a name containing one of the filters
+pub fn custom_drop_filter_rules(
+    broadcast_type: BroadcastType,
+    filters: &[String],
+) -> Vec<BroadcastRule> {
+    let filters: Vec<String> = filters
+        .iter()
+        .filter(|filter| !filter.is_empty())
+        .map(|filter| regex::escape(filter))
+        .collect();
+    if filters.is_empty() {
+        return Vec::new();
+    }
+    vec![setting_rule(
+        &RuleSetting::CustomDropFilter(broadcast_type),
+        vec![RuleCondition::ItemMatches(filters.join("|"))],
+        RuleAction::Deny,
+    )]
+}
+
+/// Only sends the broadcast type for the account types, every account type when there are none
+pub fn account_type_filter_rules(
+    broadcast_type: BroadcastType,
+    allowed_account_types: Vec<AccountType>,
+) -> Vec<BroadcastRule> {
+    if allowed_account_types.is_empty() {
+        return Vec::new();
+    }
+    vec![setting_rule(
+        &RuleSetting::AccountTypeFilter(broadcast_type),
+        vec![RuleCondition::Not(Box::new(RuleCondition::AccountType(
+            allowed_account_types,
+        )))],
+        RuleAction::Deny,
+    )]
+}
+
+/// Denies the collection log items more than this percentage of players have
+pub fn collection_log_max_percentage_rules(max_percentage: f64) -> Vec<BroadcastRule> {
+    vec![setting_rule(
+        &RuleSetting::CollectionLogMaxPercentage,
+        vec![RuleCondition::ClogPercentageAbove(max_percentage)],
+        RuleAction::Deny,
+    )]
+}
+
+/// Denies the quests, diaries or combat achievements ranked below the minimum tier
+pub fn min_tier_rules(setting: RuleSetting, min_ranking: usize) -> Vec<BroadcastRule> {
+    vec![setting_rule(
+        &setting,
+        vec![RuleCondition::TierBelow(min_ranking)],
+        RuleAction::Deny,
+    )]
+}
+
+/// Denies the broadcasts worth less than the threshold. An account type threshold replaces the
+/// guild wide one for that account type, so once it passes the drop is allowed
+pub fn value_threshold_rules(setting: RuleSetting, threshold: i64) -> Vec<BroadcastRule> {
+    let below_threshold = RuleCondition::ValueBetween {
+        min: None,
+        max: Some(threshold - 1),
+    };
+    match setting {
+        RuleSetting::AccountTypeDropPriceThreshold(ref account_type) => {
+            let account_type = RuleCondition::AccountType(vec![account_type.clone()]);
+            vec![
+                setting_rule(
+                    &setting,
+                    vec![below_threshold, account_type.clone()],
+                    RuleAction::Deny,
+                ),
+                setting_rule(&setting, vec![account_type], RuleAction::Allow),
+            ]
+        }
+        _ => vec![setting_rule(
+            &setting,
+            vec![below_threshold],
+            RuleAction::Deny,
+        )],
+    }
+}
+
+/// Rare drops skip the value thresholds and common ones are denied, drops without a known rate
+/// are left to the thresholds
+pub fn drop_rarity_rules(one_in: f64) -> Vec<BroadcastRule> {
+    let setting = RuleSetting::DropRarityThreshold;
+    vec![
+        setting_rule(
+            &setting,
+            vec![RuleCondition::DropRateRarerThan(one_in)],
+            RuleAction::Allow,
+        ),
+        setting_rule(
+            &setting,
+            vec![RuleCondition::DropRateCommonerThan(one_in)],
+            RuleAction::Deny,
+        ),
+    ]
+}
@@ -1,10 +1,11 @@
+use crate::database::indexes::index;
 use crate::database::BroadcastsDb;
 use crate::osrs_broadcast_handler::BroadcastMessageToDiscord;
 use bson::DateTime;
 use futures::TryStreamExt;
 use mockall::predicate::*;
 use mongodb::bson::doc;
-use mongodb::{bson, Database};
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -22,6 +23,15 @@ pub struct BroadcastModel {
 
 impl BroadcastsDb {
     pub const COLLECTION_NAME: &'static str = "broadcasts";
+
+    /// The latest broadcasts are read for a guild
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![index(
+            "guild_id_created_at",
+            doc! { "guild_id": 1, "created_at": -1 },
+        )]
+    }
+
     pub fn new_instance(mongodb: Database) -> Self {
         Self { db: mongodb }
     }
@@ -1,11 +1,13 @@
+use crate::database::indexes::{index, unique_index};
 use crate::database::ClanMateCollectionLogTotalsDb;
 use anyhow::Error;
 use async_trait::async_trait;
 use futures::TryStreamExt;
-use log::info;
 use mockall::automock;
-use mongodb::bson::{doc, DateTime};
-use mongodb::{bson, Database};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::FindOptions;
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
 
 use super::clan_mates::ClanMateModel;
@@ -21,6 +23,15 @@ pub struct ClanMateCollectionLogTotalModel {
 
 impl ClanMateCollectionLogTotalModel {
     pub const COLLECTION_NAME: &'static str = "clan_mate_collection_log_totals";
+
+    /// One total per clan mate in each guild
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "guild_id_player_id_unique",
+            doc! { "guild_id": 1, "player_id": 1 },
+        )]
+    }
+
     pub fn new(guild_id: u64, player_id: bson::oid::ObjectId, total: i64) -> Self {
         Self {
             guild_id,
@@ -38,16 +49,91 @@ pub struct ClanMateCollectionLogTotalsView {
     pub total: i64,
 }
 
+/// A change to a clan mates collection log total. Every change is kept so progress can be
+/// graphed and gains added up over any period
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMateCollectionLogEntryModel {
+    #[serde(rename = "_id")]
+    pub id: ObjectId,
+    pub guild_id: u64,
+    pub player_id: ObjectId,
+    //The item from the broadcast that changed the total
+    pub item_name: Option<String>,
+    pub total: i64,
+    //The slots the change added, a broadcast is one new slot when the total before is not known
+    pub gained: i64,
+    //Set on the entries backfilled from a saved broadcast
+    pub broadcast_id: Option<ObjectId>,
+    pub created_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl ClanMateCollectionLogEntryModel {
+    pub const COLLECTION_NAME: &'static str = "clan_mate_collection_log_entries";
+
+    /// Gains are read for a guild over a period, progress and items for one clan mate
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(
+                "guild_id_created_at",
+                doc! { "guild_id": 1, "created_at": 1 },
+            ),
+            index(
+                "guild_id_player_id_created_at",
+                doc! { "guild_id": 1, "player_id": 1, "created_at": 1 },
+            ),
+            index("broadcast_id", doc! { "broadcast_id": 1 }),
+        ]
+    }
+
+    pub fn new(
+        guild_id: u64,
+        player_id: ObjectId,
+        item_name: Option<String>,
+        total: i64,
+        previous_total: Option<i64>,
+    ) -> Self {
+        Self {
+            id: ObjectId::new(),
+            guild_id,
+            player_id,
+            item_name,
+            total,
+            gained: previous_total.map_or(1, |previous_total| (total - previous_total).max(0)),
+            broadcast_id: None,
+            created_at: DateTime::now(),
+            clan_mate: None,
+        }
+    }
+}
+
+/// How many slots a clan mate gained over a period
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CollectionLogGainsView {
+    pub player_name: String,
+    pub gained: i64,
+    pub total: i64,
+}
+
+/// The collection log items a clan mate has had broadcast
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMateCollectionLogItemsView {
+    pub player_name: String,
+    pub items: Vec<String>,
+}
+
 #[automock]
 #[async_trait]
-pub trait ClanMateCollectionLogTotals {
+pub trait ClanMateCollectionLogTotals: Send + Sync {
     fn new_instance(mongodb: Database) -> Self;
 
+    /// Sets the clan mates total and records the change with the item that caused it
     async fn update_or_create(
         &self,
         guild_id: u64,
         player_id: bson::oid::ObjectId,
         total: i64,
+        item_name: Option<String>,
     ) -> Result<(), anyhow::Error>;
 
     async fn get_guild_totals(
@@ -67,6 +153,7 @@ impl ClanMateCollectionLogTotals for ClanMateCollectionLogTotalsDb {
         guild_id: u64,
         player_id: bson::oid::ObjectId,
         total: i64,
+        item_name: Option<String>,
     ) -> Result<(), Error> {
         let collection = self.db.collection::<ClanMateCollectionLogTotalModel>(
             ClanMateCollectionLogTotalModel::COLLECTION_NAME,
@@ -74,29 +161,47 @@ impl ClanMateCollectionLogTotals for ClanMateCollectionLogTotalsDb {
 
         let filter = doc! {
             "guild_id": bson::to_bson(&guild_id).unwrap(),
-            "player_id": player_id.clone(),
+            "player_id": player_id,
         };
 
-        let update_result = collection
-            .update_one(
-                filter,
-                doc! {
-                    "$set": {
-                        "total": bson::to_bson(&total).unwrap()
-                    }
-                },
-                None,
+        let previous_total = collection
+            .find_one(filter.clone(), None)
+            .await?
+            .map(|saved_total| saved_total.total);
+        match previous_total {
+            Some(previous_total) if previous_total == total => return Ok(()),
+            Some(_) => {
+                collection
+                    .update_one(
+                        filter,
+                        doc! {
+                            "$set": {
+                                "total": bson::to_bson(&total).unwrap()
+                            }
+                        },
+                        None,
+                    )
+                    .await?;
+            }
+            None => {
+                let new_total = ClanMateCollectionLogTotalModel::new(guild_id, player_id, total);
+                collection.insert_one(new_total, None).await?;
+            }
+        }
+
+        let entry = ClanMateCollectionLogEntryModel::new(
+            guild_id,
+            player_id,
+            item_name,
+            total,
+            previous_total,
+        );
+        self.db
+            .collection::<ClanMateCollectionLogEntryModel>(
+                ClanMateCollectionLogEntryModel::COLLECTION_NAME,
             )
+            .insert_one(entry, None)
             .await?;
-        info!("Update result: {:?}", update_result);
-        if update_result.matched_count > 0 {
-            return Ok(());
-        }
-        let new_total = ClanMateCollectionLogTotalModel::new(guild_id, player_id, total);
-        let _ = collection
-            .insert_one(new_total, None)
-            .await
-            .expect("Error inserting new collection log total.");
         Ok(())
     }
 
@@ -153,3 +258,127 @@ impl ClanMateCollectionLogTotals for ClanMateCollectionLogTotalsDb {
         return Ok(results);
     }
 }
+
+impl ClanMateCollectionLogTotalsDb {
+    /// Slots each clan mate gained between the dates, most first
+    pub async fn get_gains_between_dates(
+        &self,
+        guild_id: u64,
+        start_date: DateTime,
+        end_date: DateTime,
+    ) -> Result<Vec<CollectionLogGainsView>, anyhow::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "guild_id": bson::to_bson(&guild_id).unwrap(),
+                    "created_at": {
+                        "$gte": start_date,
+                        "$lte": end_date
+                    }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$player_id",
+                    "gained": { "$sum": "$gained" },
+                    "total": { "$max": "$total" }
+                }
+            },
+            doc! {
+                "$match": { "gained": { "$gt": 0 } }
+            },
+        ];
+        let mut gains: Vec<CollectionLogGainsView> = self
+            .with_clan_mate_names(pipeline, doc! { "gained": 1, "total": 1 })
+            .await?
+            .into_iter()
+            .filter_map(|result| bson::from_document(result).ok())
+            .collect();
+        gains.sort_by_key(|gain| std::cmp::Reverse(gain.gained));
+        Ok(gains)
+    }
+
+    /// Every change to the clan mates total, oldest first
+    pub async fn get_clan_mate_progress(
+        &self,
+        guild_id: u64,
+        player_id: ObjectId,
+    ) -> Result<Vec<ClanMateCollectionLogEntryModel>, anyhow::Error> {
+        let collection = self.db.collection::<ClanMateCollectionLogEntryModel>(
+            ClanMateCollectionLogEntryModel::COLLECTION_NAME,
+        );
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "player_id": player_id,
+        };
+        let options = FindOptions::builder()
+            .sort(doc! { "created_at": 1 })
+            .build();
+        Ok(collection
+            .find(filter, options)
+            .await?
+            .try_collect()
+            .await?)
+    }
+
+    /// The items each clan mate has had a collection log broadcast for
+    pub async fn get_clan_mate_items(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanMateCollectionLogItemsView>, anyhow::Error> {
+        let pipeline = vec![
+            doc! {
+                "$match": {
+                    "guild_id": bson::to_bson(&guild_id).unwrap(),
+                    "item_name": { "$ne": null }
+                }
+            },
+            doc! {
+                "$group": {
+                    "_id": "$player_id",
+                    "items": { "$addToSet": "$item_name" }
+                }
+            },
+        ];
+        let mut clan_mate_items: Vec<ClanMateCollectionLogItemsView> = self
+            .with_clan_mate_names(pipeline, doc! { "items": 1 })
+            .await?
+            .into_iter()
+            .filter_map(|result| bson::from_document(result).ok())
+            .collect();
+        for clan_mate in clan_mate_items.iter_mut() {
+            clan_mate.items.sort();
+        }
+        clan_mate_items.sort_by(|a, b| a.player_name.cmp(&b.player_name));
+        Ok(clan_mate_items)
+    }
+
+    //Runs the pipeline grouped by player_id on the entries and swaps the id for the clan mates name
+    async fn with_clan_mate_names(
+        &self,
+        mut pipeline: Vec<Document>,
+        mut projection: Document,
+    ) -> Result<Vec<Document>, anyhow::Error> {
+        projection.insert("_id", 0);
+        projection.insert("player_name", "$clan_mate.player_name");
+        pipeline.push(doc! {
+            "$lookup": {
+                "from": "clan_mates",
+                "localField": "_id",
+                "foreignField": "_id",
+                "as": "clan_mate"
+            }
+        });
+        pipeline.push(doc! { "$unwind": "$clan_mate" });
+        pipeline.push(doc! { "$project": projection });
+
+        let collection = self
+            .db
+            .collection::<Document>(ClanMateCollectionLogEntryModel::COLLECTION_NAME);
+        Ok(collection
+            .aggregate(pipeline, None)
+            .await?
+            .try_collect()
+            .await?)
+    }
+}
@@ -0,0 +1,137 @@
+use super::clan_mates::ClanMateModel;
+use super::ClanMateCombatAchievementsDb;
+use crate::database::indexes::unique_index;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::CombatAchievementTier;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, DateTime};
+use mongodb::{bson, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMateCombatAchievementModel {
+    pub guild_id: u64,
+    pub player_id: bson::oid::ObjectId,
+    pub highest_tier: CombatAchievementTier,
+    //Kept alongside the tier so the leaderboard can be sorted in mongo
+    pub highest_tier_ranking: i64,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl ClanMateCombatAchievementModel {
+    pub const COLLECTION_NAME: &'static str = "clan_mate_combat_achievements";
+
+    /// One highest tier per clan mate in each guild
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "guild_id_player_id_unique",
+            doc! { "guild_id": 1, "player_id": 1 },
+        )]
+    }
+
+    pub fn new(guild_id: u64, player_id: bson::oid::ObjectId, tier: CombatAchievementTier) -> Self {
+        Self {
+            guild_id,
+            player_id,
+            highest_tier_ranking: tier.ranking() as i64,
+            highest_tier: tier,
+            created_at: DateTime::now(),
+            updated_at: DateTime::now(),
+            clan_mate: None,
+        }
+    }
+}
+
+impl ClanMateCombatAchievementsDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    /// Saves the tier for the clan mate if it is higher than the one already recorded
+    pub async fn update_or_create_highest_tier(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        tier: CombatAchievementTier,
+    ) -> Result<(), anyhow::Error> {
+        let collection = self.db.collection::<ClanMateCombatAchievementModel>(
+            ClanMateCombatAchievementModel::COLLECTION_NAME,
+        );
+
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "player_id": player_id.clone(),
+        };
+        match collection.find_one(filter.clone(), None).await? {
+            Some(recorded_tier) => {
+                if tier.ranking() as i64 > recorded_tier.highest_tier_ranking {
+                    let update = doc! {
+                        "$set": {
+                            "highest_tier": bson::to_bson(&tier).unwrap(),
+                            "highest_tier_ranking": tier.ranking() as i64,
+                            "updated_at": DateTime::now()
+                        }
+                    };
+                    collection.update_one(filter, update, None).await?;
+                }
+                Ok(())
+            }
+            None => {
+                let new_tier = ClanMateCombatAchievementModel::new(guild_id, player_id, tier);
+                collection.insert_one(new_tier, None).await?;
+                Ok(())
+            }
+        }
+    }
+
+    pub async fn get_guild_highest_tiers(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanMateCombatAchievementModel>, anyhow::Error> {
+        let collection = self.db.collection::<ClanMateCombatAchievementModel>(
+            ClanMateCombatAchievementModel::COLLECTION_NAME,
+        );
+
+        let mut cursor = collection
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "guild_id": bson::to_bson(&guild_id).unwrap(),
+                        }
+                    },
+                    doc! {
+                        "$lookup": {
+                            "from": "clan_mates",
+                            "localField": "player_id",
+                            "foreignField": "_id",
+                            "as": "clan_mate"
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$clan_mate"
+                    },
+                    doc! {
+                        "$sort": {
+                            "highest_tier_ranking": -1,
+                            "updated_at": 1
+                        }
+                    },
+                ],
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ClanMateCombatAchievementModel> = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            if let Ok(view) =
+                bson::from_bson::<ClanMateCombatAchievementModel>(bson::Bson::Document(result))
+            {
+                results.push(view);
+            }
+        }
+
+        Ok(results)
+    }
+}
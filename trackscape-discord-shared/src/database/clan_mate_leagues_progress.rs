@@ -0,0 +1,185 @@
+use super::clan_mates::ClanMateModel;
+use super::ClanMateLeaguesProgressDb;
+use crate::database::indexes::unique_index;
+use futures::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{bson, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMateLeaguesProgressModel {
+    pub guild_id: u64,
+    pub player_id: bson::oid::ObjectId,
+    pub areas: Vec<String>,
+    pub areas_unlocked: i64,
+    pub highest_relic_tier: i64,
+    pub combat_mastery_points: i64,
+    pub leagues_rank: Option<String>,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl ClanMateLeaguesProgressModel {
+    pub const COLLECTION_NAME: &'static str = "clan_mate_leagues_progress";
+
+    /// Progress is merged into one document per clan mate in each guild
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![unique_index(
+            "guild_id_player_id_unique",
+            doc! { "guild_id": 1, "player_id": 1 },
+        )]
+    }
+}
+
+/// A single leagues broadcast worth of progress for a clan mate.
+/// Only the fields that broadcast had are set
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct LeaguesProgressUpdate {
+    pub player: String,
+    pub area: Option<String>,
+    pub areas_unlocked: Option<i64>,
+    pub relic_tier: Option<i64>,
+    pub combat_mastery_points: Option<i64>,
+    pub leagues_rank: Option<String>,
+}
+
+impl ClanMateLeaguesProgressDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    /// Merges the update into the clan mates progress. Counts and tiers only ever go up
+    pub async fn update_progress(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        progress: LeaguesProgressUpdate,
+    ) -> Result<(), anyhow::Error> {
+        let collection = self.db.collection::<ClanMateLeaguesProgressModel>(
+            ClanMateLeaguesProgressModel::COLLECTION_NAME,
+        );
+
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "player_id": player_id,
+        };
+
+        let mut max_fields = Document::new();
+        let mut set_on_insert = doc! {
+            "created_at": DateTime::now(),
+            "clan_mate": bson::Bson::Null,
+        };
+        match progress.areas_unlocked {
+            Some(areas_unlocked) => {
+                max_fields.insert("areas_unlocked", areas_unlocked);
+            }
+            None => {
+                set_on_insert.insert("areas_unlocked", 0_i64);
+            }
+        }
+        match progress.relic_tier {
+            Some(relic_tier) => {
+                max_fields.insert("highest_relic_tier", relic_tier);
+            }
+            None => {
+                set_on_insert.insert("highest_relic_tier", 0_i64);
+            }
+        }
+        match progress.combat_mastery_points {
+            Some(points) => {
+                max_fields.insert("combat_mastery_points", points);
+            }
+            None => {
+                set_on_insert.insert("combat_mastery_points", 0_i64);
+            }
+        }
+
+        let mut set_fields = doc! {
+            "updated_at": DateTime::now(),
+        };
+        match progress.leagues_rank {
+            Some(leagues_rank) => {
+                set_fields.insert("leagues_rank", leagues_rank);
+            }
+            None => {
+                set_on_insert.insert("leagues_rank", bson::Bson::Null);
+            }
+        }
+
+        let mut update = doc! {
+            "$set": set_fields,
+            "$setOnInsert": set_on_insert,
+        };
+        match progress.area {
+            Some(area) => {
+                update.insert("$addToSet", doc! { "areas": area });
+            }
+            None => {
+                update
+                    .get_document_mut("$setOnInsert")
+                    .unwrap()
+                    .insert("areas", bson::Bson::Array(vec![]));
+            }
+        }
+        if !max_fields.is_empty() {
+            update.insert("$max", max_fields);
+        }
+
+        let options = UpdateOptions::builder().upsert(true).build();
+        collection.update_one(filter, update, options).await?;
+        Ok(())
+    }
+
+    pub async fn get_guild_leaderboard(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanMateLeaguesProgressModel>, anyhow::Error> {
+        let collection = self.db.collection::<ClanMateLeaguesProgressModel>(
+            ClanMateLeaguesProgressModel::COLLECTION_NAME,
+        );
+
+        let mut cursor = collection
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "guild_id": bson::to_bson(&guild_id).unwrap(),
+                        }
+                    },
+                    doc! {
+                        "$lookup": {
+                            "from": "clan_mates",
+                            "localField": "player_id",
+                            "foreignField": "_id",
+                            "as": "clan_mate"
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$clan_mate"
+                    },
+                    doc! {
+                        "$sort": {
+                            "highest_relic_tier": -1,
+                            "areas_unlocked": -1,
+                            "combat_mastery_points": -1
+                        }
+                    },
+                ],
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ClanMateLeaguesProgressModel> = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            if let Ok(view) =
+                bson::from_bson::<ClanMateLeaguesProgressModel>(bson::Bson::Document(result))
+            {
+                results.push(view);
+            }
+        }
+
+        Ok(results)
+    }
+}
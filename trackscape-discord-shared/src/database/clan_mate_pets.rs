@@ -0,0 +1,166 @@
+use super::clan_mates::ClanMateModel;
+use super::ClanMatePetsDb;
+use crate::database::indexes::index;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{PetCountType, PetDropBroadcast};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, DateTime};
+use mongodb::{bson, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMatePetModel {
+    pub guild_id: u64,
+    pub player_id: bson::oid::ObjectId,
+    pub pet_name: String,
+    pub count: Option<i64>,
+    pub count_type: PetCountType,
+    pub duplicate: bool,
+    pub created_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl ClanMatePetModel {
+    pub const COLLECTION_NAME: &'static str = "clan_mate_pets";
+
+    /// Every drop is kept, duplicates included, so only the guild lookups are indexed
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![index(
+            "guild_id_player_id",
+            doc! { "guild_id": 1, "player_id": 1 },
+        )]
+    }
+
+    pub fn new(guild_id: u64, player_id: bson::oid::ObjectId, pet_drop: PetDropBroadcast) -> Self {
+        Self {
+            guild_id,
+            player_id,
+            pet_name: pet_drop.pet_name,
+            count: pet_drop.actions_optioned_at,
+            count_type: pet_drop.count_type,
+            duplicate: pet_drop.duplicate,
+            created_at: DateTime::now(),
+            clan_mate: None,
+        }
+    }
+}
+
+/// Every pet the clan has, who has it and the clan mates that are still missing it
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanPetCollection {
+    pub pet_name: String,
+    pub owners: Vec<ClanMatePetModel>,
+    pub missing: Vec<String>,
+}
+
+impl ClanMatePetsDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    pub async fn new_pet(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        pet_drop: PetDropBroadcast,
+    ) -> Result<(), anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<ClanMatePetModel>(ClanMatePetModel::COLLECTION_NAME);
+        let pet = ClanMatePetModel::new(guild_id, player_id, pet_drop);
+        collection.insert_one(pet, None).await?;
+        Ok(())
+    }
+
+    /// Every pet drop for the guild with the clan mate attached, duplicates included
+    pub async fn get_guild_pets(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanMatePetModel>, anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<ClanMatePetModel>(ClanMatePetModel::COLLECTION_NAME);
+
+        let mut cursor = collection
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "guild_id": bson::to_bson(&guild_id).unwrap(),
+                        }
+                    },
+                    doc! {
+                        "$lookup": {
+                            "from": "clan_mates",
+                            "localField": "player_id",
+                            "foreignField": "_id",
+                            "as": "clan_mate"
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$clan_mate"
+                    },
+                    doc! {
+                        "$sort": {
+                            "pet_name": 1,
+                            "created_at": 1
+                        }
+                    },
+                ],
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ClanMatePetModel> = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            if let Ok(view) = bson::from_bson::<ClanMatePetModel>(bson::Bson::Document(result)) {
+                results.push(view);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Groups the guilds pets by pet. Missing is measured against the pets someone in the
+    /// clan has, so a pet nobody has received yet is not listed
+    pub async fn get_guild_pet_collection(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanPetCollection>, anyhow::Error> {
+        let pets = self.get_guild_pets(guild_id).await?;
+        let clan_mates: Vec<ClanMateModel> = self
+            .db
+            .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME)
+            .find(doc! {"guild_id": bson::to_bson(&guild_id).unwrap()}, None)
+            .await?
+            .try_collect()
+            .await?;
+        let mut pet_collection: Vec<ClanPetCollection> = Vec::new();
+        for pet in pets {
+            let position = pet_collection
+                .iter()
+                .position(|collected| collected.pet_name == pet.pet_name);
+            match position {
+                Some(position) => pet_collection[position].owners.push(pet),
+                None => pet_collection.push(ClanPetCollection {
+                    pet_name: pet.pet_name.clone(),
+                    owners: vec![pet],
+                    missing: Vec::new(),
+                }),
+            }
+        }
+
+        for collected in pet_collection.iter_mut() {
+            collected.missing = clan_mates
+                .iter()
+                .filter(|clan_mate| {
+                    !collected
+                        .owners
+                        .iter()
+                        .any(|owner| owner.player_id == clan_mate.id)
+                })
+                .map(|clan_mate| clan_mate.player_name.clone())
+                .collect();
+        }
+        Ok(pet_collection)
+    }
+}
@@ -0,0 +1,222 @@
+use super::clan_mates::ClanMateModel;
+use super::ClanMateSkillsDb;
+use crate::database::indexes::{index, unique_index};
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    LevelMilestoneBroadcast, LevelMilestoneKind,
+};
+use futures::TryStreamExt;
+use mongodb::bson::{doc, DateTime, Document};
+use mongodb::options::UpdateOptions;
+use mongodb::{bson, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+
+//The XP needed for level 99, XP milestones past it mean the skill is 99
+const LEVEL_99_XP: i64 = 13_034_431;
+
+/// The latest known level and XP of a clan mates skill, taken from the level and XP milestone broadcasts.
+/// The total and combat levels are saved as the "Total" and "Combat" skills with their own kind
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ClanMateSkillModel {
+    pub guild_id: u64,
+    pub player_id: bson::oid::ObjectId,
+    pub skill: String,
+    pub kind: LevelMilestoneKind,
+    pub level: Option<i64>,
+    pub xp: Option<i64>,
+    //The highest possible total or combat level was reached
+    pub maxed: bool,
+    pub created_at: DateTime,
+    pub updated_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl ClanMateSkillModel {
+    pub const COLLECTION_NAME: &'static str = "clan_mate_skills";
+
+    /// One entry per skill for each clan mate, leaderboards are read per skill in a guild
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            unique_index(
+                "guild_id_player_id_skill_unique",
+                doc! { "guild_id": 1, "player_id": 1, "skill": 1 },
+            ),
+            index(
+                "guild_id_skill_level",
+                doc! { "guild_id": 1, "skill": 1, "level": -1 },
+            ),
+        ]
+    }
+}
+
+/// Skills are saved the way the game writes them, so "fishing" finds "Fishing"
+pub fn skill_name(skill: &str) -> String {
+    let lowercase = skill.trim().to_lowercase();
+    match lowercase.as_str() {
+        "total" | "overall" | "total level" => return "Total".to_string(),
+        "combat" | "combat level" => return "Combat".to_string(),
+        _ => {}
+    }
+    let mut characters = lowercase.chars();
+    match characters.next() {
+        Some(first) => first.to_uppercase().chain(characters).collect(),
+        None => lowercase,
+    }
+}
+
+impl ClanMateSkillsDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    /// Saves the level from a level milestone, a lower level than the one saved is ignored
+    pub async fn record_level(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        level_milestone: &LevelMilestoneBroadcast,
+    ) -> Result<(), anyhow::Error> {
+        let mut update = doc! {
+            "$max": { "level": level_milestone.new_skill_level },
+            "$set": { "updated_at": DateTime::now() },
+            "$setOnInsert": {
+                "kind": bson::to_bson(&level_milestone.kind)?,
+                "created_at": DateTime::now()
+            }
+        };
+        if level_milestone.maxed {
+            update.get_document_mut("$set")?.insert("maxed", true);
+        } else {
+            update
+                .get_document_mut("$setOnInsert")?
+                .insert("maxed", false);
+        }
+        self.upsert(
+            guild_id,
+            player_id,
+            skill_name(&level_milestone.skill_levelled),
+            update,
+        )
+        .await
+    }
+
+    /// Saves the XP from an XP milestone, XP past 13,034,431 also means the skill is at least 99
+    pub async fn record_xp(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        skill: &str,
+        xp: i64,
+    ) -> Result<(), anyhow::Error> {
+        let mut max = doc! { "xp": xp };
+        if xp >= LEVEL_99_XP {
+            max.insert("level", 99_i64);
+        }
+        let update = doc! {
+            "$max": max,
+            "$set": { "updated_at": DateTime::now() },
+            "$setOnInsert": {
+                "kind": bson::to_bson(&LevelMilestoneKind::Skill)?,
+                "maxed": false,
+                "created_at": DateTime::now()
+            }
+        };
+        self.upsert(guild_id, player_id, skill_name(skill), update)
+            .await
+    }
+
+    async fn upsert(
+        &self,
+        guild_id: u64,
+        player_id: bson::oid::ObjectId,
+        skill: String,
+        update: Document,
+    ) -> Result<(), anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<ClanMateSkillModel>(ClanMateSkillModel::COLLECTION_NAME);
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "player_id": player_id,
+            "skill": skill,
+        };
+        collection
+            .update_one(
+                filter,
+                update,
+                UpdateOptions::builder().upsert(true).build(),
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// The clan mates with the skill at or above the level, highest level and XP first
+    pub async fn get_skill_leaderboard(
+        &self,
+        guild_id: u64,
+        skill: &str,
+        min_level: Option<i64>,
+    ) -> Result<Vec<ClanMateSkillModel>, anyhow::Error> {
+        let mut filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "skill": skill_name(skill),
+        };
+        if let Some(min_level) = min_level {
+            filter.insert("level", doc! { "$gte": min_level });
+        }
+        self.with_clan_mates(filter, doc! { "level": -1, "xp": -1, "updated_at": 1 })
+            .await
+    }
+
+    /// The clan mates by their latest known total level, maxed clan mates first
+    pub async fn get_closest_to_max(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<ClanMateSkillModel>, anyhow::Error> {
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "kind": bson::to_bson(&LevelMilestoneKind::Total)?,
+        };
+        self.with_clan_mates(filter, doc! { "maxed": -1, "level": -1, "updated_at": 1 })
+            .await
+    }
+
+    async fn with_clan_mates(
+        &self,
+        filter: Document,
+        sort: Document,
+    ) -> Result<Vec<ClanMateSkillModel>, anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<ClanMateSkillModel>(ClanMateSkillModel::COLLECTION_NAME);
+
+        let mut cursor = collection
+            .aggregate(
+                vec![
+                    doc! { "$match": filter },
+                    doc! {
+                        "$lookup": {
+                            "from": "clan_mates",
+                            "localField": "player_id",
+                            "foreignField": "_id",
+                            "as": "clan_mate"
+                        }
+                    },
+                    doc! {
+                        "$unwind": "$clan_mate"
+                    },
+                    doc! { "$sort": sort },
+                ],
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<ClanMateSkillModel> = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            if let Ok(view) = bson::from_bson::<ClanMateSkillModel>(bson::Bson::Document(result)) {
+                results.push(view);
+            }
+        }
+
+        Ok(results)
+    }
+}
@@ -1,14 +1,26 @@
-use super::pb_records_db::PersonalBestRecordsModel;
-use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotalModel;
+use super::pb_records_db::{PersonalBestHistoryModel, PersonalBestRecordsModel};
+use crate::database::clan_mate_collection_log_totals::{
+    ClanMateCollectionLogEntryModel, ClanMateCollectionLogTotalModel,
+};
+use crate::database::clan_mate_combat_achievements::ClanMateCombatAchievementModel;
+use crate::database::clan_mate_leagues_progress::ClanMateLeaguesProgressModel;
+use crate::database::clan_mate_pets::ClanMatePetModel;
+use crate::database::clan_mate_skills::ClanMateSkillModel;
+use crate::database::indexes::index;
 use crate::database::ClanMatesDb;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::AccountType;
 use anyhow::Error;
 use async_trait::async_trait;
 use futures::TryStreamExt;
+use log::error;
 use mockall::predicate::*;
 use mockall::*;
-use mongodb::bson::{doc, DateTime};
-use mongodb::{bson, Database};
+use mongodb::bson::oid::ObjectId;
+use mongodb::bson::{doc, Bson, DateTime, Document};
+use mongodb::options::{Collation, CollationStrength, FindOneOptions, IndexOptions};
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ClanMateModel {
@@ -19,12 +31,62 @@ pub struct ClanMateModel {
     pub wom_player_id: Option<u64>,
     pub previous_names: Vec<String>,
     pub rank: Option<String>,
+    //Set when a hardcore death broadcast is seen for the clan mate
+    pub lost_hardcore_status_at: Option<DateTime>,
+    //Taken from the account icon on the clan mates chats
+    pub account_type: Option<AccountType>,
+    //Discord user that proved they own the account with /link
+    pub discord_user_id: Option<u64>,
+    //Set to false with /link so broadcasts stop mentioning the linked discord user
+    pub discord_mentions: Option<bool>,
+    //Waiting on the code from /link to be typed in clan chat
+    pub pending_discord_link: Option<PendingDiscordLink>,
     pub created_at: DateTime,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PendingDiscordLink {
+    pub discord_user_id: u64,
+    pub code: String,
+    pub expires_at: DateTime,
+    //How many wrong codes have been typed for this link
+    #[serde(default)]
+    pub wrong_codes: u32,
+}
+
 impl ClanMateModel {
     pub const COLLECTION_NAME: &'static str = "clan_mates";
 
+    /// One clan mate per name in each guild, names are compared without case like the game does.
+    /// The name indexes share the lookups collation so the lookups can use them
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            IndexModel::builder()
+                .keys(doc! { "guild_id": 1, "player_name": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name("guild_id_player_name_unique".to_string())
+                        .unique(true)
+                        .collation(name_collation())
+                        .build(),
+                )
+                .build(),
+            IndexModel::builder()
+                .keys(doc! { "guild_id": 1, "previous_names": 1 })
+                .options(
+                    IndexOptions::builder()
+                        .name("guild_id_previous_names".to_string())
+                        .collation(name_collation())
+                        .build(),
+                )
+                .build(),
+            index(
+                "guild_id_discord_user_id",
+                doc! { "guild_id": 1, "discord_user_id": 1 },
+            ),
+        ]
+    }
+
     pub fn new(guild_id: u64, player_name: String, wom_player_id: Option<u64>) -> Self {
         Self {
             id: bson::oid::ObjectId::new(),
@@ -33,14 +95,86 @@ impl ClanMateModel {
             previous_names: Vec::new(),
             player_name,
             rank: None,
+            lost_hardcore_status_at: None,
+            account_type: None,
+            discord_user_id: None,
+            discord_mentions: None,
+            pending_discord_link: None,
             created_at: DateTime::now(),
         }
     }
 }
 
+/// Collections that save data for a clan mate and the field holding the clan mates id,
+/// all of it moves with the clan mate when clan mates are merged
+const CLAN_MATE_DATA_COLLECTIONS: [(&str, &str); 8] = [
+    (PersonalBestRecordsModel::COLLECTION_NAME, "clan_mate_id"),
+    (PersonalBestHistoryModel::COLLECTION_NAME, "clan_mate_id"),
+    (
+        ClanMateCollectionLogTotalModel::COLLECTION_NAME,
+        "player_id",
+    ),
+    (
+        ClanMateCollectionLogEntryModel::COLLECTION_NAME,
+        "player_id",
+    ),
+    (ClanMateCombatAchievementModel::COLLECTION_NAME, "player_id"),
+    (ClanMateLeaguesProgressModel::COLLECTION_NAME, "player_id"),
+    (ClanMatePetModel::COLLECTION_NAME, "player_id"),
+    (ClanMateSkillModel::COLLECTION_NAME, "player_id"),
+];
+
+/// Collections whose data is removed with the clan mate when they leave or are expelled.
+/// The personal best history and collection log entries are append only and kept, so past
+/// results and the gains over a period are not lost
+const CLAN_MATE_REMOVED_COLLECTIONS: [(&str, &str); 6] = [
+    (PersonalBestRecordsModel::COLLECTION_NAME, "clan_mate_id"),
+    (
+        ClanMateCollectionLogTotalModel::COLLECTION_NAME,
+        "player_id",
+    ),
+    (ClanMateCombatAchievementModel::COLLECTION_NAME, "player_id"),
+    (ClanMateLeaguesProgressModel::COLLECTION_NAME, "player_id"),
+    (ClanMatePetModel::COLLECTION_NAME, "player_id"),
+    (ClanMateSkillModel::COLLECTION_NAME, "player_id"),
+];
+
+/// Collections where a clan mate only has one record for each value of these other fields, with
+/// the sort that puts the record worth keeping first when two clan mates records come together
+fn unique_clan_mate_data(collection_name: &str) -> Option<(&'static [&'static str], Document)> {
+    match collection_name {
+        PersonalBestRecordsModel::COLLECTION_NAME => Some((
+            &["activity_id"],
+            doc! { "time_in_seconds": 1, "updated_at": 1 },
+        )),
+        ClanMateCollectionLogTotalModel::COLLECTION_NAME => {
+            Some((&["guild_id"], doc! { "total": -1 }))
+        }
+        ClanMateCombatAchievementModel::COLLECTION_NAME => Some((
+            &["guild_id"],
+            doc! { "highest_tier_ranking": -1, "updated_at": 1 },
+        )),
+        ClanMateLeaguesProgressModel::COLLECTION_NAME => {
+            Some((&["guild_id"], doc! { "updated_at": -1 }))
+        }
+        ClanMateSkillModel::COLLECTION_NAME => {
+            Some((&["guild_id", "skill"], doc! { "level": -1, "xp": -1 }))
+        }
+        _ => None,
+    }
+}
+
+/// What splitting the clan mates shared between guilds changed
+#[derive(Debug, Default)]
+pub struct ClanMateSplitReport {
+    pub clan_mates_created: u64,
+    pub duplicates_merged: u64,
+    pub records_reassigned: u64,
+}
+
 #[automock]
 #[async_trait]
-pub trait ClanMates {
+pub trait ClanMates: Send + Sync {
     fn new_instance(mongodb: Database) -> Self;
 
     async fn find_or_create_clan_mate(
@@ -58,16 +192,24 @@ pub trait ClanMates {
 
     async fn find_by_current_name(
         &self,
+        guild_id: u64,
         player_name: String,
     ) -> Result<Option<ClanMateModel>, anyhow::Error>;
 
     async fn find_by_previous_name(
         &self,
+        guild_id: u64,
         player_name: String,
     ) -> Result<Option<ClanMateModel>, anyhow::Error>;
 
     async fn update_clan_mate(&self, model: ClanMateModel) -> Result<ClanMateModel, anyhow::Error>;
 
+    async fn find_by_discord_user_id(
+        &self,
+        guild_id: u64,
+        discord_user_id: u64,
+    ) -> Result<Vec<ClanMateModel>, anyhow::Error>;
+
     async fn get_clan_member_count(&self, guild_id: u64) -> Result<u64, Error>;
 
     async fn get_clan_mates_by_guild_id(&self, guild_id: u64) -> Result<Vec<ClanMateModel>, Error>;
@@ -93,14 +235,23 @@ impl ClanMates for ClanMatesDb {
         guild_id: u64,
         player_name: String,
     ) -> Result<ClanMateModel, Error> {
-        let possible_clan_mate = self.find_by_current_name(player_name.clone()).await?;
-        return Ok(match possible_clan_mate {
-            None => {
-                self.create_new_clan_mate(guild_id, player_name.replace(" ", "\u{a0}"), None)
-                    .await?
-            }
-            Some(clan_mate) => clan_mate,
-        });
+        let possible_clan_mate = self
+            .find_by_current_name(guild_id, player_name.clone())
+            .await?;
+        if let Some(clan_mate) = possible_clan_mate {
+            return Ok(clan_mate);
+        }
+        match self
+            .create_new_clan_mate(guild_id, player_name.clone(), None)
+            .await
+        {
+            Ok(clan_mate) => Ok(clan_mate),
+            //Another job may have made them first, the unique index stops a second copy
+            Err(error) => self
+                .find_by_current_name(guild_id, player_name)
+                .await?
+                .ok_or(error),
+        }
     }
 
     async fn create_new_clan_mate(
@@ -120,44 +271,39 @@ impl ClanMates for ClanMatesDb {
 
     async fn find_by_current_name(
         &self,
+        guild_id: u64,
         player_name: String,
     ) -> Result<Option<ClanMateModel>, anyhow::Error> {
         let collection = self
             .db
             .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
         let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
             "player_name": bson::to_bson(&player_name.replace(" ", "\u{a0}")).unwrap(),
         };
-        //TODO possible lower case look up
-        // let agg = vec![doc! {
-        //     "$match": doc!{
-        //         "$expr": doc!{
-        //             "$eq": doc!{
-        //                 "$toLower": doc! {"player_name": bson::to_bson(&player_name.to_lowercase().replace(" ", "\u{a0}")).unwrap()}
-        //             }
-        //         }
-        //     }
-        // }];
-        // let mut multi_results = collection.aggregate(agg, None).await?;
-        // while let Some(result) = multi_results.try_next().await? {
-        //     let doc = bson::from_document(result)?;
-        //     // println!("* {}", doc);
-        // }
-        let result = collection.find_one(filter, None).await?;
+        let options = FindOneOptions::builder()
+            .collation(name_collation())
+            .build();
+        let result = collection.find_one(filter, options).await?;
         Ok(result)
     }
 
     async fn find_by_previous_name(
         &self,
+        guild_id: u64,
         player_name: String,
     ) -> Result<Option<ClanMateModel>, anyhow::Error> {
         let collection = self
             .db
             .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
         let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
             "previous_names": bson::to_bson(&player_name.replace(" ", "\u{a0}")).unwrap(),
         };
-        let result = collection.find_one(filter, None).await?;
+        let options = FindOneOptions::builder()
+            .collation(name_collation())
+            .build();
+        let result = collection.find_one(filter, options).await?;
         Ok(result)
     }
 
@@ -173,6 +319,23 @@ impl ClanMates for ClanMatesDb {
         Ok(model)
     }
 
+    async fn find_by_discord_user_id(
+        &self,
+        guild_id: u64,
+        discord_user_id: u64,
+    ) -> Result<Vec<ClanMateModel>, Error> {
+        let collection = self
+            .db
+            .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "discord_user_id": bson::to_bson(&discord_user_id).unwrap(),
+        };
+        let result = collection.find(filter, None).await?;
+        let clan_mates = result.try_collect().await?;
+        Ok(clan_mates)
+    }
+
     async fn get_clan_member_count(&self, guild_id: u64) -> Result<u64, Error> {
         let collection = self
             .db
@@ -197,14 +360,13 @@ impl ClanMates for ClanMatesDb {
     }
 
     async fn remove_clan_mate(&self, guild_id: u64, player_name: String) -> Result<(), Error> {
-        //TODO add a bit to clean up other collections too
-
-        //TODO dont forget to remove PBs
         let collection = self
             .db
             .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
 
-        let possible_player = self.find_by_current_name(player_name.clone()).await?;
+        let possible_player = self
+            .find_by_current_name(guild_id, player_name.clone())
+            .await?;
         if possible_player.is_none() {
             return Err(anyhow::anyhow!(format!(
                 "Failed to find clan mate: {}",
@@ -213,43 +375,23 @@ impl ClanMates for ClanMatesDb {
         }
         let player = possible_player.unwrap();
 
-        //Removes other data from db may move else where
-        let delete_filter = doc! {
-            "guild_id": bson::to_bson(&guild_id).unwrap(),
-            "player_id": player.id.clone(),
-        };
-
-        let collection_log_collection = self.db.collection::<ClanMateCollectionLogTotalModel>(
-            ClanMateCollectionLogTotalModel::COLLECTION_NAME,
-        );
-
-        let collection_log_result = collection_log_collection
-            .delete_many(delete_filter.clone(), None)
-            .await;
-        if collection_log_result.is_err() {
-            println!(
-                "Failed to remove collection log totals for clan mate: {}",
-                player_name
-            );
-            println!("Error: {:?}", collection_log_result.err());
-        }
-
-        let pb_collection = self
-            .db
-            .collection::<PersonalBestRecordsModel>(PersonalBestRecordsModel::COLLECTION_NAME);
-        let pb_records_result = pb_collection.delete_many(delete_filter, None).await;
-        if pb_records_result.is_err() {
-            println!(
-                "Failed to remove personal best records for clan mate: {}",
-                player_name
-            );
-            println!("Error: {:?}", pb_records_result.err());
+        for (collection_name, id_field) in CLAN_MATE_REMOVED_COLLECTIONS {
+            let result = self
+                .db
+                .collection::<Document>(collection_name)
+                .delete_many(doc! { id_field: player.id }, None)
+                .await;
+            if result.is_err() {
+                println!(
+                    "Failed to remove {} for clan mate: {}",
+                    collection_name, player_name
+                );
+                println!("Error: {:?}", result.err());
+            }
         }
-        //End of removing from other collections
 
         let filter = doc! {
-                "guild_id":bson::to_bson(&guild_id).unwrap(),
-                "player_name": bson::to_bson(&player_name.replace(" ", "\u{a0}")).unwrap()
+            "_id": player.id,
         };
         let result = collection.delete_one(filter, None).await?;
         if result.deleted_count == 0 {
@@ -264,14 +406,13 @@ impl ClanMates for ClanMatesDb {
         old_name: String,
         new_name: String,
     ) -> Result<(), Error> {
-        let clan_mate = self.find_by_current_name(old_name.clone()).await?;
+        let clan_mate = self
+            .find_by_current_name(guild_id, old_name.clone())
+            .await?;
         if clan_mate.is_none() {
             return Err(anyhow::anyhow!("Failed to find clan mate"));
         }
         let mut clan_mate = clan_mate.unwrap();
-        if clan_mate.guild_id != guild_id {
-            return Err(anyhow::anyhow!("Clan mate is not in this clan!"));
-        }
         clan_mate
             .previous_names
             .push(old_name.replace(" ", "\u{a0}"));
@@ -281,6 +422,209 @@ impl ClanMates for ClanMatesDb {
     }
 }
 
+impl ClanMatesDb {
+    /// Name lookups used to ignore the guild, so one clan mate could end up with records from
+    /// several clans. This merges clan mates with the same name in a guild, then gives every
+    /// guild its own clan mate and moves that guilds records over to it.
+    /// Collection log totals were saved under the found clan mates guild, so a mixed total
+    /// can not be told apart and stays with the original clan mate.
+    /// A dry run only counts what would change.
+    pub async fn split_cross_guild_clan_mates(
+        &self,
+        dry_run: bool,
+    ) -> Result<ClanMateSplitReport, Error> {
+        let collection = self
+            .db
+            .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
+        let mut report = ClanMateSplitReport::default();
+
+        let mut clan_mates: Vec<ClanMateModel> =
+            collection.find(None, None).await?.try_collect().await?;
+        //Oldest first so the clan mate that has been around longest is the one kept
+        clan_mates.sort_by_key(|clan_mate| clan_mate.created_at);
+        let mut clan_mates_by_id: HashMap<ObjectId, ClanMateModel> = HashMap::new();
+        let mut clan_mate_ids_by_name: HashMap<(u64, String), ObjectId> = HashMap::new();
+        let mut merged_clan_mate_ids: HashMap<ObjectId, ObjectId> = HashMap::new();
+        for clan_mate in clan_mates {
+            let name_key = (clan_mate.guild_id, name_normalize(&clan_mate.player_name));
+            let Some(kept_id) = clan_mate_ids_by_name.get(&name_key).copied() else {
+                clan_mate_ids_by_name.insert(name_key, clan_mate.id);
+                clan_mates_by_id.insert(clan_mate.id, clan_mate);
+                continue;
+            };
+
+            report.records_reassigned += self
+                .move_clan_mate_data(clan_mate.id, kept_id, dry_run)
+                .await?;
+            merged_clan_mate_ids.insert(clan_mate.id, kept_id);
+            let kept = clan_mates_by_id
+                .get_mut(&kept_id)
+                .expect("Kept clan mate is always saved by id");
+            for previous_name in clan_mate.previous_names {
+                if !kept.previous_names.contains(&previous_name) {
+                    kept.previous_names.push(previous_name);
+                }
+            }
+            kept.wom_player_id = kept.wom_player_id.or(clan_mate.wom_player_id);
+            kept.discord_user_id = kept.discord_user_id.or(clan_mate.discord_user_id);
+            kept.account_type = kept.account_type.clone().or(clan_mate.account_type);
+            if !dry_run {
+                self.update_clan_mate(kept.clone()).await?;
+                collection
+                    .delete_one(doc! { "_id": clan_mate.id }, None)
+                    .await?;
+            }
+            report.duplicates_merged += 1;
+        }
+
+        for (collection_name, id_field) in CLAN_MATE_DATA_COLLECTIONS {
+            let data_collection = self.db.collection::<Document>(collection_name);
+            let mut records = data_collection.find(None, None).await?;
+            while let Some(record) = records.try_next().await? {
+                let Ok(clan_mate_id) = record.get_object_id(id_field) else {
+                    continue;
+                };
+                //Only needed on a dry run, otherwise the merged records were already moved
+                let clan_mate_id = merged_clan_mate_ids
+                    .get(&clan_mate_id)
+                    .copied()
+                    .unwrap_or(clan_mate_id);
+                let Some(guild_id) = record
+                    .get("guild_id")
+                    .and_then(|guild_id| bson::from_bson::<u64>(guild_id.clone()).ok())
+                else {
+                    continue;
+                };
+                let Some(clan_mate) = clan_mates_by_id.get(&clan_mate_id).cloned() else {
+                    error!(
+                        "{} record {:?} belongs to a missing clan mate",
+                        collection_name,
+                        record.get("_id")
+                    );
+                    continue;
+                };
+                if clan_mate.guild_id == guild_id {
+                    continue;
+                }
+
+                let name_key = (guild_id, name_normalize(&clan_mate.player_name));
+                let guild_clan_mate_id = match clan_mate_ids_by_name.get(&name_key) {
+                    Some(guild_clan_mate_id) => *guild_clan_mate_id,
+                    None => {
+                        //The account is the same so what we know about it carries over, the link and rank do not
+                        let mut guild_clan_mate = ClanMateModel::new(
+                            guild_id,
+                            clan_mate.player_name.clone(),
+                            clan_mate.wom_player_id,
+                        );
+                        guild_clan_mate.previous_names = clan_mate.previous_names.clone();
+                        guild_clan_mate.account_type = clan_mate.account_type.clone();
+                        guild_clan_mate.lost_hardcore_status_at = clan_mate.lost_hardcore_status_at;
+                        if !dry_run {
+                            collection.insert_one(guild_clan_mate.clone(), None).await?;
+                        }
+                        report.clan_mates_created += 1;
+                        clan_mate_ids_by_name.insert(name_key, guild_clan_mate.id);
+                        clan_mates_by_id.insert(guild_clan_mate.id, guild_clan_mate.clone());
+                        guild_clan_mate.id
+                    }
+                };
+                if !dry_run {
+                    self.move_record(collection_name, id_field, &record, guild_clan_mate_id)
+                        .await?;
+                }
+                report.records_reassigned += 1;
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Points every record saved for one clan mate at another, returns how many were moved
+    async fn move_clan_mate_data(
+        &self,
+        from: ObjectId,
+        to: ObjectId,
+        dry_run: bool,
+    ) -> Result<u64, Error> {
+        let mut moved = 0;
+        for (collection_name, id_field) in CLAN_MATE_DATA_COLLECTIONS {
+            let data_collection = self.db.collection::<Document>(collection_name);
+            if dry_run {
+                moved += data_collection
+                    .count_documents(doc! { id_field: from }, None)
+                    .await?;
+                continue;
+            }
+            let records: Vec<Document> = data_collection
+                .find(doc! { id_field: from }, None)
+                .await?
+                .try_collect()
+                .await?;
+            for record in &records {
+                self.move_record(collection_name, id_field, record, to)
+                    .await?;
+            }
+            moved += records.len() as u64;
+        }
+        Ok(moved)
+    }
+
+    /// Points one record at another clan mate. If that clan mate already has the record a unique
+    /// index only allows one of, the better of the two is kept and the other one deleted
+    async fn move_record(
+        &self,
+        collection_name: &str,
+        id_field: &str,
+        record: &Document,
+        to: ObjectId,
+    ) -> Result<(), Error> {
+        let data_collection = self.db.collection::<Document>(collection_name);
+        let record_id = record.get("_id").cloned();
+        if let Some((key_fields, keep_first)) = unique_clan_mate_data(collection_name) {
+            let mut conflict_filter = doc! { id_field: to };
+            for field in key_fields {
+                conflict_filter.insert(*field, record.get(*field).cloned().unwrap_or(Bson::Null));
+            }
+            if let Some(existing) = data_collection.find_one(conflict_filter, None).await? {
+                let existing_id = existing.get("_id").cloned();
+                let kept_id = data_collection
+                    .find_one(
+                        doc! { "_id": { "$in": [existing_id.clone(), record_id.clone()] } },
+                        FindOneOptions::builder().sort(keep_first).build(),
+                    )
+                    .await?
+                    .and_then(|kept| kept.get("_id").cloned());
+                if kept_id == existing_id {
+                    data_collection
+                        .delete_one(doc! { "_id": record_id }, None)
+                        .await?;
+                    return Ok(());
+                }
+                data_collection
+                    .delete_one(doc! { "_id": existing_id }, None)
+                    .await?;
+            }
+        }
+        data_collection
+            .update_one(
+                doc! { "_id": record_id },
+                doc! { "$set": { id_field: to } },
+                None,
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+/// Names are matched without case, the unique name index is made with the same collation
+fn name_collation() -> Collation {
+    Collation::builder()
+        .locale("en")
+        .strength(CollationStrength::Secondary)
+        .build()
+}
+
 pub fn name_compare(name1: &str, name2: &str) -> bool {
     name_normalize(name1) == name_normalize(name2)
 }
@@ -1,46 +1,110 @@
+use crate::database::indexes::index;
 use crate::database::DropLogsDb;
-use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::DropItemBroadcast;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{BroadcastType, DropItemBroadcast};
 use async_trait::async_trait;
 use futures::TryStreamExt;
 use mockall::predicate::*;
 use mockall::*;
 use mongodb::bson::{doc, DateTime};
-use mongodb::{bson, Database};
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
 
+/// One row of the clan's loot ledger. Every broadcast that gives a player an item is logged
+/// here so totals cover drops, raids, clues, loot keys and collection log items alike
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DropLogModel {
     #[serde(rename = "_id")]
     id: bson::oid::ObjectId,
     pub guild_id: u64,
     pub drop_item: DropItemBroadcast,
+    //Logs saved before the ledger tracked types were all item drops
+    #[serde(default = "default_broadcast_type")]
+    pub broadcast_type: BroadcastType,
+    /// What one of the item sold for on the GE when it was dropped, kept apart from the
+    /// item_value the broadcast or the guilds price source gave it
+    #[serde(default)]
+    pub ge_price: Option<i64>,
     pub created_at: DateTime,
 }
 
+fn default_broadcast_type() -> BroadcastType {
+    BroadcastType::ItemDrop
+}
+
 impl DropLogModel {
     pub const COLLECTION_NAME: &'static str = "drop_logs";
 
-    pub fn new(drop_item: DropItemBroadcast, guild_id: u64) -> Self {
+    /// The ledger is always read for a guild over a range of dates
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![index(
+            "guild_id_created_at",
+            doc! { "guild_id": 1, "created_at": -1 },
+        )]
+    }
+
+    pub fn new(
+        drop_item: DropItemBroadcast,
+        broadcast_type: BroadcastType,
+        ge_price: Option<i64>,
+        guild_id: u64,
+    ) -> Self {
         Self {
             id: bson::oid::ObjectId::new(),
             guild_id,
             drop_item,
+            broadcast_type,
+            ge_price,
             created_at: DateTime::now(),
         }
     }
 }
 
+//How many of the most valuable items to show for each drop source
+const TOP_ITEMS_PER_SOURCE: i64 = 5;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DropSourceItemStats {
+    pub item_name: String,
+    pub item_id: Option<i64>,
+    pub quantity: i64,
+    pub total_gp: i64,
+}
+
+/// Totals for every drop from one monster or activity, kept apart for each type of broadcast
+/// so raid loot and loot keys are not mixed in with item drops. drop_source is None for drops
+/// where the broadcast did not say where it came from
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DropSourceStats {
+    pub broadcast_type: BroadcastType,
+    pub drop_source: Option<String>,
+    pub total_drops: i64,
+    pub total_gp: i64,
+    pub top_items: Vec<DropSourceItemStats>,
+}
+
 #[automock]
 #[async_trait]
-pub trait DropLogs {
+pub trait DropLogs: Send + Sync {
     fn new_instance(mongodb: Database) -> Self;
-    async fn new_drop_log(&self, drop_log: DropItemBroadcast, guild_id: u64);
+    async fn new_drop_log(
+        &self,
+        drop_log: DropItemBroadcast,
+        broadcast_type: BroadcastType,
+        ge_price: Option<i64>,
+        guild_id: u64,
+    ) -> anyhow::Result<()>;
     async fn get_drops_between_dates(
         &self,
         guild_id: u64,
         start_date: DateTime,
         end_date: DateTime,
     ) -> anyhow::Result<Vec<DropLogModel>>;
+    async fn get_drop_source_stats(
+        &self,
+        guild_id: u64,
+        start_date: DateTime,
+        end_date: DateTime,
+    ) -> anyhow::Result<Vec<DropSourceStats>>;
 }
 
 #[async_trait]
@@ -49,14 +113,18 @@ impl DropLogs for DropLogsDb {
         Self { db: mongodb }
     }
 
-    async fn new_drop_log(&self, drop_broadcast: DropItemBroadcast, guild_id: u64) {
+    async fn new_drop_log(
+        &self,
+        drop_broadcast: DropItemBroadcast,
+        broadcast_type: BroadcastType,
+        ge_price: Option<i64>,
+        guild_id: u64,
+    ) -> anyhow::Result<()> {
         let collection = self.db.collection(DropLogModel::COLLECTION_NAME);
-        let new_drop_log = DropLogModel::new(drop_broadcast, guild_id);
+        let new_drop_log = DropLogModel::new(drop_broadcast, broadcast_type, ge_price, guild_id);
 
-        collection
-            .insert_one(new_drop_log, None)
-            .await
-            .expect("Failed to insert document for a new drop log.");
+        collection.insert_one(new_drop_log, None).await?;
+        Ok(())
     }
 
     async fn get_drops_between_dates(
@@ -82,4 +150,88 @@ impl DropLogs for DropLogsDb {
             Err(e) => Err(anyhow::Error::new(e)),
         };
     }
+
+    async fn get_drop_source_stats(
+        &self,
+        guild_id: u64,
+        start_date: DateTime,
+        end_date: DateTime,
+    ) -> anyhow::Result<Vec<DropSourceStats>> {
+        let collection = self
+            .db
+            .collection::<DropLogModel>(DropLogModel::COLLECTION_NAME);
+
+        let mut cursor = collection
+            .aggregate(
+                vec![
+                    doc! {
+                        "$match": {
+                            "guild_id": bson::to_bson(&guild_id).unwrap(),
+                            "created_at": {
+                                "$gte": bson::to_bson(&start_date).unwrap(),
+                                "$lte": bson::to_bson(&end_date).unwrap()
+                            }
+                        }
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": {
+                                "broadcast_type": {
+                                    "$ifNull": ["$broadcast_type", bson::to_bson(&default_broadcast_type())?]
+                                },
+                                "drop_source": "$drop_item.drop_source",
+                                "item_name": "$drop_item.item_name"
+                            },
+                            "item_id": { "$max": "$drop_item.item_id" },
+                            "drops": { "$sum": 1_i64 },
+                            "quantity": { "$sum": "$drop_item.item_quantity" },
+                            "total_gp": { "$sum": { "$ifNull": ["$drop_item.item_value", 0_i64] } }
+                        }
+                    },
+                    doc! {
+                        "$sort": { "total_gp": -1 }
+                    },
+                    doc! {
+                        "$group": {
+                            "_id": {
+                                "broadcast_type": "$_id.broadcast_type",
+                                "drop_source": "$_id.drop_source"
+                            },
+                            "total_drops": { "$sum": "$drops" },
+                            "total_gp": { "$sum": "$total_gp" },
+                            "top_items": {
+                                "$push": {
+                                    "item_name": "$_id.item_name",
+                                    "item_id": "$item_id",
+                                    "quantity": "$quantity",
+                                    "total_gp": "$total_gp"
+                                }
+                            }
+                        }
+                    },
+                    doc! {
+                        "$project": {
+                            "_id": 0,
+                            "broadcast_type": "$_id.broadcast_type",
+                            "drop_source": "$_id.drop_source",
+                            "total_drops": 1,
+                            "total_gp": 1,
+                            "top_items": { "$slice": ["$top_items", TOP_ITEMS_PER_SOURCE] }
+                        }
+                    },
+                    doc! {
+                        "$sort": { "total_gp": -1 }
+                    },
+                ],
+                None,
+            )
+            .await?;
+
+        let mut results: Vec<DropSourceStats> = Vec::new();
+        while let Some(result) = cursor.try_next().await? {
+            //A row that can not be read would leave the totals short without anyone knowing
+            results.push(bson::from_document::<DropSourceStats>(result)?);
+        }
+        Ok(results)
+    }
 }
@@ -1,15 +1,18 @@
+use crate::broadcast_rules::{set_setting_rules, BroadcastRule, RuleSetting};
+use crate::database::indexes::unique_index;
 use crate::database::GuildsDb;
+use crate::embed_templates::EmbedTemplate;
+use crate::ge_api::ge_api::DropPriceSource;
 use crate::helpers::hash_string;
-use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
-    BroadcastType, DiaryTier, QuestDifficulty,
-};
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+use crate::role_pings::RolePing;
 use anyhow::Result;
 use async_recursion::async_recursion;
 use futures::TryStreamExt;
 use mockall::predicate::*;
 use mongodb::bson::{doc, DateTime};
 use mongodb::options::FindOptions;
-use mongodb::{bson, Database};
+use mongodb::{bson, Database, IndexModel};
 use rand::Rng;
 use serde::{Deserialize, Serialize};
 use std::string::ToString;
@@ -25,21 +28,39 @@ pub struct RegisteredGuildModel {
     //Channel to send clan chats messages
     pub clan_chat_channel: Option<u64>,
     pub leagues_broadcast_channel: Option<u64>,
-    pub drop_price_threshold: Option<i64>,
-    pub disallowed_broadcast_types: Vec<BroadcastType>,
+    //Which price drop thresholds are checked against, the broadcast value when not set
+    pub drop_price_source: Option<DropPriceSource>,
     pub verification_code: String,
     pub hashed_verification_code: String,
-    pub min_quest_difficulty: Option<QuestDifficulty>,
-    pub min_diary_tier: Option<DiaryTier>,
-    pub pk_value_threshold: Option<i64>,
     pub wom_id: Option<i64>,
     pub created_at: Option<DateTime>,
-    pub custom_drop_broadcast_filter: Option<std::collections::HashMap<BroadcastType, Vec<String>>>,
-    pub collection_log_max_percentage: Option<f64>,
+    //Checked in order, the setting commands like /threshold and /toggle save their rules here too
+    pub broadcast_rules: Option<Vec<BroadcastRule>>,
+    //Sends a broadcast type to its own channel instead of broadcast_channel
+    pub broadcast_type_channels: Option<std::collections::HashMap<BroadcastType, u64>>,
+    //Changes how a broadcast type's embed looks
+    pub embed_templates: Option<std::collections::HashMap<BroadcastType, EmbedTemplate>>,
+    //Clan rank title to the discord role linked clan mates with that rank get
+    pub rank_roles: Option<std::collections::HashMap<String, u64>>,
+    pub rank_role_sync_enabled: Option<bool>,
+    //Discord role mentioned on a broadcast type
+    pub role_pings: Option<std::collections::HashMap<BroadcastType, RolePing>>,
 }
 
 impl RegisteredGuildModel {
     pub const COLLECTION_NAME: &'static str = "guilds";
+
+    /// Guilds are looked up by their discord id and by the code the plugin sends
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            unique_index("guild_id_unique", doc! { "guild_id": 1 }),
+            unique_index(
+                "hashed_verification_code_unique",
+                doc! { "hashed_verification_code": 1 },
+            ),
+        ]
+    }
+
     pub fn new(guild_id: u64) -> Self {
         let verification_code = Self::generate_code();
         let hashed_verification_code = hash_string(verification_code.clone());
@@ -50,20 +71,45 @@ impl RegisteredGuildModel {
             broadcast_channel: None,
             clan_chat_channel: None,
             leagues_broadcast_channel: None,
-            drop_price_threshold: None,
-            disallowed_broadcast_types: Vec::new(),
+            drop_price_source: None,
             verification_code,
             hashed_verification_code,
-            min_quest_difficulty: None,
-            min_diary_tier: None,
-            pk_value_threshold: None,
             wom_id: None,
             created_at: DateTime::now().into(),
-            custom_drop_broadcast_filter: Some(std::collections::HashMap::new()),
-            collection_log_max_percentage: None,
+            broadcast_rules: None,
+            broadcast_type_channels: None,
+            embed_templates: None,
+            rank_roles: None,
+            rank_role_sync_enabled: None,
+            role_pings: None,
+        }
+    }
+
+    /// The channel a broadcast of this type goes to when no rule routes it somewhere else
+    pub fn channel_for_broadcast(
+        &self,
+        broadcast_type: &BroadcastType,
+        is_league_world: bool,
+    ) -> Option<u64> {
+        if let Some(channel_id) = self
+            .broadcast_type_channels
+            .as_ref()
+            .and_then(|channels| channels.get(broadcast_type))
+        {
+            return Some(*channel_id);
+        }
+        match is_league_world {
+            true => self.leagues_broadcast_channel,
+            false => self.broadcast_channel,
         }
     }
 
+    /// Replaces the broadcast rules the setting command saved before with the new ones
+    pub fn set_setting_rules(&mut self, setting: RuleSetting, new_rules: Vec<BroadcastRule>) {
+        let rules = self.broadcast_rules.get_or_insert_with(Vec::new);
+        set_setting_rules(rules, setting, new_rules);
+    }
+
     fn generate_code() -> String {
         let mut code = String::new();
         let mut rng = rand::thread_rng();
@@ -0,0 +1,242 @@
+use crate::database::clan_mate_collection_log_totals::{
+    ClanMateCollectionLogEntryModel, ClanMateCollectionLogTotalModel,
+};
+use crate::database::clan_mate_combat_achievements::ClanMateCombatAchievementModel;
+use crate::database::clan_mate_leagues_progress::ClanMateLeaguesProgressModel;
+use crate::database::clan_mate_pets::ClanMatePetModel;
+use crate::database::clan_mate_skills::ClanMateSkillModel;
+use crate::database::clan_mates::ClanMateModel;
+use crate::database::drop_logs_db::DropLogModel;
+use crate::database::guilds_db::RegisteredGuildModel;
+use crate::database::pb_activities_db::PersonalBestActivitiesModel;
+use crate::database::pb_records_db::{PersonalBestHistoryModel, PersonalBestRecordsModel};
+use crate::database::{BroadcastsDb, IndexesDb};
+use anyhow::Error;
+use futures::TryStreamExt;
+use log::{error, info, warn};
+use mongodb::bson::{self, Bson, Document};
+use mongodb::error::ErrorKind;
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+
+/// An index with a name, the name is how it is matched with what the database has
+pub fn index(name: &str, keys: Document) -> IndexModel {
+    IndexModel::builder()
+        .keys(keys)
+        .options(IndexOptions::builder().name(name.to_string()).build())
+        .build()
+}
+
+/// An index that only allows one document per key
+pub fn unique_index(name: &str, keys: Document) -> IndexModel {
+    IndexModel::builder()
+        .keys(keys)
+        .options(
+            IndexOptions::builder()
+                .name(name.to_string())
+                .unique(true)
+                .build(),
+        )
+        .build()
+}
+
+/// Every collection and the indexes its model declares
+pub fn declared_indexes() -> Vec<(&'static str, Vec<IndexModel>)> {
+    vec![
+        (
+            RegisteredGuildModel::COLLECTION_NAME,
+            RegisteredGuildModel::indexes(),
+        ),
+        (ClanMateModel::COLLECTION_NAME, ClanMateModel::indexes()),
+        (DropLogModel::COLLECTION_NAME, DropLogModel::indexes()),
+        (
+            PersonalBestRecordsModel::COLLECTION_NAME,
+            PersonalBestRecordsModel::indexes(),
+        ),
+        (
+            PersonalBestHistoryModel::COLLECTION_NAME,
+            PersonalBestHistoryModel::indexes(),
+        ),
+        (
+            PersonalBestActivitiesModel::COLLECTION_NAME,
+            PersonalBestActivitiesModel::indexes(),
+        ),
+        (
+            ClanMateCollectionLogTotalModel::COLLECTION_NAME,
+            ClanMateCollectionLogTotalModel::indexes(),
+        ),
+        (
+            ClanMateCollectionLogEntryModel::COLLECTION_NAME,
+            ClanMateCollectionLogEntryModel::indexes(),
+        ),
+        (
+            ClanMateCombatAchievementModel::COLLECTION_NAME,
+            ClanMateCombatAchievementModel::indexes(),
+        ),
+        (
+            ClanMateLeaguesProgressModel::COLLECTION_NAME,
+            ClanMateLeaguesProgressModel::indexes(),
+        ),
+        (
+            ClanMatePetModel::COLLECTION_NAME,
+            ClanMatePetModel::indexes(),
+        ),
+        (
+            ClanMateSkillModel::COLLECTION_NAME,
+            ClanMateSkillModel::indexes(),
+        ),
+        (BroadcastsDb::COLLECTION_NAME, BroadcastsDb::indexes()),
+    ]
+}
+
+/// How the indexes in the database compare to the declared ones.
+/// Each entry is the collection and index name
+#[derive(Debug, Clone, Default)]
+pub struct IndexReport {
+    pub created: Vec<String>,
+    /// Has the declared name but different keys or options, it is left as is to be fixed by hand
+    pub changed: Vec<String>,
+    /// In the database but not declared by the model
+    pub undeclared: Vec<String>,
+    /// Could not be made, usually a unique index over documents that are duplicates
+    pub failed: Vec<String>,
+}
+
+impl IndexReport {
+    pub fn has_drift(&self) -> bool {
+        !self.changed.is_empty() || !self.undeclared.is_empty() || !self.failed.is_empty()
+    }
+}
+
+impl IndexesDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    /// Makes any declared index that is missing and logs the ones that drifted from the declarations.
+    /// Run once by prepare_on_startup when a binary starts, after the migrations have removed the
+    /// duplicates a unique index can not be made over
+    pub async fn ensure_indexes(&self) -> Result<IndexReport, Error> {
+        let mut report = IndexReport::default();
+        for (collection_name, declared) in declared_indexes() {
+            let collection = self.db.collection::<Document>(collection_name);
+            let existing = list_indexes(&self.db, collection_name).await?;
+            let declared_names: Vec<String> = declared.iter().map(index_name).collect();
+
+            for declared_index in declared {
+                let name = index_name(&declared_index);
+                let full_name = format!("{}.{}", collection_name, name);
+                match existing.iter().find(|index| index_name(index) == name) {
+                    Some(existing_index) => {
+                        if !same_index(&declared_index, existing_index) {
+                            report.changed.push(full_name);
+                        }
+                    }
+                    None => match collection.create_index(declared_index, None).await {
+                        Ok(_) => report.created.push(full_name),
+                        Err(error) => {
+                            error!("Failed to create the index {}: {:?}", full_name, error);
+                            report.failed.push(full_name);
+                        }
+                    },
+                }
+            }
+
+            for existing_index in &existing {
+                let name = index_name(existing_index);
+                if name != "_id_" && !declared_names.contains(&name) {
+                    report
+                        .undeclared
+                        .push(format!("{}.{}", collection_name, name));
+                }
+            }
+        }
+
+        if !report.failed.is_empty() {
+            error!(
+                "Indexes that could not be made, they are tried again on the next start: {}",
+                report.failed.join(", ")
+            );
+        }
+        if !report.created.is_empty() {
+            info!("Created the indexes: {}", report.created.join(", "));
+        }
+        if !report.changed.is_empty() {
+            warn!(
+                "Indexes that differ from their declaration and need to be dropped to be remade: {}",
+                report.changed.join(", ")
+            );
+        }
+        if !report.undeclared.is_empty() {
+            warn!(
+                "Indexes in the database that no model declares: {}",
+                report.undeclared.join(", ")
+            );
+        }
+        Ok(report)
+    }
+}
+
+async fn list_indexes(db: &Database, collection_name: &str) -> Result<Vec<IndexModel>, Error> {
+    let collection = db.collection::<Document>(collection_name);
+    match collection.list_indexes(None).await {
+        Ok(cursor) => Ok(cursor.try_collect().await?),
+        //The collection does not exist yet, it is made with its first index
+        Err(error) if is_namespace_not_found(&error) => Ok(Vec::new()),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn is_namespace_not_found(error: &mongodb::error::Error) -> bool {
+    match *error.kind {
+        ErrorKind::Command(ref command_error) => command_error.code == 26,
+        _ => false,
+    }
+}
+
+fn index_name(index: &IndexModel) -> String {
+    index
+        .options
+        .as_ref()
+        .and_then(|options| options.name.clone())
+        .unwrap_or_default()
+}
+
+fn same_index(declared: &IndexModel, existing: &IndexModel) -> bool {
+    let declared_options = declared.options.clone().unwrap_or_default();
+    let existing_options = existing.options.clone().unwrap_or_default();
+
+    let declared_collation = declared_options
+        .collation
+        .map(|collation| (collation.locale, bson::to_bson(&collation.strength).ok()));
+    let existing_collation = existing_options
+        .collation
+        .map(|collation| (collation.locale, bson::to_bson(&collation.strength).ok()));
+
+    same_keys(&declared.keys, &existing.keys)
+        && declared_options.unique.unwrap_or(false) == existing_options.unique.unwrap_or(false)
+        && declared_collation == existing_collation
+}
+
+//The server can send back 1 as an int or a double, so directions are compared as numbers
+fn same_keys(declared: &Document, existing: &Document) -> bool {
+    declared.len() == existing.len()
+        && declared.iter().zip(existing.iter()).all(
+            |((declared_field, declared_value), (existing_field, existing_value))| {
+                declared_field == existing_field
+                    && match (key_direction(declared_value), key_direction(existing_value)) {
+                        (Some(declared), Some(existing)) => declared == existing,
+                        _ => declared_value == existing_value,
+                    }
+            },
+        )
+}
+
+fn key_direction(value: &Bson) -> Option<f64> {
+    match value {
+        Bson::Int32(direction) => Some(*direction as f64),
+        Bson::Int64(direction) => Some(*direction as f64),
+        Bson::Double(direction) => Some(*direction),
+        _ => None,
+    }
+}
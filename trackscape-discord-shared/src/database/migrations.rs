@@ -0,0 +1,886 @@
+use crate::broadcast_rules::{
+    account_type_filter_rules, collection_log_max_percentage_rules, custom_drop_filter_rules,
+    drop_rarity_rules, min_tier_rules, set_setting_rules, toggle_rules, value_threshold_rules,
+    BroadcastRule, RuleSetting,
+};
+use crate::database::broadcasts::BroadcastModel;
+use crate::database::clan_mate_collection_log_totals::{
+    ClanMateCollectionLogEntryModel, ClanMateCollectionLogTotalModel,
+};
+use crate::database::clan_mate_combat_achievements::ClanMateCombatAchievementModel;
+use crate::database::clan_mate_leagues_progress::ClanMateLeaguesProgressModel;
+use crate::database::clan_mates::{ClanMateModel, ClanMates};
+use crate::database::guilds_db::RegisteredGuildModel;
+use crate::database::pb_records_db::{PersonalBestHistoryModel, PersonalBestRecordsModel};
+use crate::database::{BroadcastsDb, ClanMatesDb, MigrationsDb};
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+    collection_log_broadcast_extractor, AccountType, BroadcastType, CombatAchievementTier,
+    DiaryTier, QuestDifficulty,
+};
+use anyhow::{anyhow, Error};
+use async_trait::async_trait;
+use futures::TryStreamExt;
+use log::{error, info};
+use mongodb::bson::{doc, oid::ObjectId, Bson, DateTime, Document};
+use mongodb::error::{ErrorKind, WriteFailure};
+use mongodb::options::{AggregateOptions, FindOneAndUpdateOptions, FindOneOptions, FindOptions};
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::env;
+use std::time::Duration;
+
+const LOCK_ID: &str = "schema_migrations";
+//The lock is renewed while the migrations run, so this is only how long a crashed instance's
+//lock blocks the others before it is taken over
+const LOCK_SECONDS: i64 = 120;
+const LOCK_RENEW_SECONDS: u64 = 30;
+const LOCK_RETRY_SECONDS: u64 = 10;
+
+/// A migration that has been applied, saved so it never runs again
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SchemaMigrationModel {
+    #[serde(rename = "_id")]
+    pub version: u32,
+    pub name: String,
+    pub documents_changed: u64,
+    pub applied_at: DateTime,
+}
+
+impl SchemaMigrationModel {
+    pub const COLLECTION_NAME: &'static str = "schema_migrations";
+}
+
+/// Held by the instance running migrations so the API and workers do not run them at once
+#[derive(Debug, Serialize, Deserialize, Clone)]
+struct SchemaMigrationLockModel {
+    #[serde(rename = "_id")]
+    id: String,
+    owner: ObjectId,
+    expires_at: DateTime,
+}
+
+impl SchemaMigrationLockModel {
+    const COLLECTION_NAME: &'static str = "schema_migrations_lock";
+}
+
+/// What happened to a pending migration
+#[derive(Debug, Clone)]
+pub struct MigrationOutcome {
+    pub version: u32,
+    pub name: &'static str,
+    pub documents_changed: u64,
+    pub dry_run: bool,
+}
+
+#[async_trait]
+pub trait Migration: Sync {
+    /// Migrations run in version order, once a version is applied it is never run again
+    fn version(&self) -> u32;
+
+    fn name(&self) -> &'static str;
+
+    /// Changes the documents and returns how many changed. On a dry run nothing is written and
+    /// it returns how many would change. Has to be safe to run again if it stops part way
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error>;
+}
+
+/// Every migration, new ones go on the end with the next version
+pub fn migrations() -> Vec<&'static dyn Migration> {
+    vec![
+        &NormalizeClanMateNames,
+        &SplitCrossGuildClanMates,
+        &BackfillGuildSettings,
+        &RemoveDuplicateRecords,
+        &SeedPersonalBestHistory,
+        &BackfillCollectionLogEntries,
+        &MoveGuildSettingsToRules,
+    ]
+}
+
+impl MigrationsDb {
+    pub fn new_instance(mongodb: Database) -> Self {
+        Self { db: mongodb }
+    }
+
+    /// Runs the pending migrations, called by prepare_on_startup when a binary starts.
+    /// Set MIGRATIONS_DRY_RUN=true to only log what they would change
+    pub async fn run_on_startup(&self) -> Result<Vec<MigrationOutcome>, Error> {
+        let dry_run = env::var("MIGRATIONS_DRY_RUN").is_ok_and(|dry_run| dry_run == "true");
+        let outcomes = self.run(dry_run).await;
+        if let Err(error) = &outcomes {
+            error!("Failed to run the schema migrations: {:?}", error);
+        }
+        outcomes
+    }
+
+    /// Runs every migration that has not been applied yet in version order, stopping at the first
+    /// that fails. A dry run does not take the lock or record anything
+    pub async fn run(&self, dry_run: bool) -> Result<Vec<MigrationOutcome>, Error> {
+        let migrations = migrations();
+        if migrations
+            .windows(2)
+            .any(|pair| pair[0].version() >= pair[1].version())
+        {
+            return Err(anyhow!(
+                "Migration versions have to go up one after another"
+            ));
+        }
+
+        if dry_run {
+            return self.run_pending(&migrations, true).await;
+        }
+
+        let owner = self.acquire_lock().await?;
+        let renewal = tokio::spawn(renew_lock(self.db.clone(), owner));
+        let outcomes = self.run_pending(&migrations, false).await;
+        renewal.abort();
+        self.release_lock(owner).await;
+        outcomes
+    }
+
+    pub async fn applied_versions(&self) -> Result<Vec<u32>, Error> {
+        let collection = self
+            .db
+            .collection::<SchemaMigrationModel>(SchemaMigrationModel::COLLECTION_NAME);
+        let applied: Vec<SchemaMigrationModel> =
+            collection.find(None, None).await?.try_collect().await?;
+        Ok(applied.iter().map(|migration| migration.version).collect())
+    }
+
+    async fn run_pending(
+        &self,
+        migrations: &[&'static dyn Migration],
+        dry_run: bool,
+    ) -> Result<Vec<MigrationOutcome>, Error> {
+        let applied_versions = self.applied_versions().await?;
+        let collection = self
+            .db
+            .collection::<SchemaMigrationModel>(SchemaMigrationModel::COLLECTION_NAME);
+        let mut outcomes = Vec::new();
+        for migration in migrations {
+            if applied_versions.contains(&migration.version()) {
+                continue;
+            }
+            let documents_changed = migration.up(&self.db, dry_run).await.map_err(|error| {
+                anyhow!(
+                    "Migration {} {} failed: {:?}",
+                    migration.version(),
+                    migration.name(),
+                    error
+                )
+            })?;
+            if dry_run {
+                info!(
+                    "Dry run: migration {} {} would change {} documents",
+                    migration.version(),
+                    migration.name(),
+                    documents_changed
+                );
+            } else {
+                collection
+                    .insert_one(
+                        SchemaMigrationModel {
+                            version: migration.version(),
+                            name: migration.name().to_string(),
+                            documents_changed,
+                            applied_at: DateTime::now(),
+                        },
+                        None,
+                    )
+                    .await?;
+                info!(
+                    "Applied migration {} {}, {} documents changed",
+                    migration.version(),
+                    migration.name(),
+                    documents_changed
+                );
+            }
+            outcomes.push(MigrationOutcome {
+                version: migration.version(),
+                name: migration.name(),
+                documents_changed,
+                dry_run,
+            });
+        }
+        Ok(outcomes)
+    }
+
+    /// Waits for the lock while another instance is running the migrations, a lock that was not
+    /// renewed in time is taken over
+    async fn acquire_lock(&self) -> Result<ObjectId, Error> {
+        let collection = self
+            .db
+            .collection::<SchemaMigrationLockModel>(SchemaMigrationLockModel::COLLECTION_NAME);
+        let owner = ObjectId::new();
+        loop {
+            let expires_at = lock_expires_at();
+            //Only matches an expired lock, when the lock is held the upsert hits the unique _id
+            let result = collection
+                .find_one_and_update(
+                    doc! { "_id": LOCK_ID, "expires_at": { "$lt": DateTime::now() } },
+                    doc! { "$set": { "owner": owner, "expires_at": expires_at } },
+                    FindOneAndUpdateOptions::builder().upsert(true).build(),
+                )
+                .await;
+            match result {
+                Ok(_) => return Ok(owner),
+                Err(error) if is_duplicate_key(&error) => {
+                    info!("Waiting for another instance to finish the schema migrations");
+                    tokio::time::sleep(Duration::from_secs(LOCK_RETRY_SECONDS)).await;
+                }
+                Err(error) => return Err(error.into()),
+            }
+        }
+    }
+
+    async fn release_lock(&self, owner: ObjectId) {
+        let collection = self
+            .db
+            .collection::<SchemaMigrationLockModel>(SchemaMigrationLockModel::COLLECTION_NAME);
+        let result = collection
+            .delete_one(doc! { "_id": LOCK_ID, "owner": owner }, None)
+            .await;
+        if let Err(error) = result {
+            error!("Failed to release the schema migrations lock: {:?}", error);
+        }
+    }
+}
+
+fn lock_expires_at() -> DateTime {
+    DateTime::from_millis(DateTime::now().timestamp_millis() + LOCK_SECONDS * 1000)
+}
+
+/// Pushes the lock's expiry back until it is aborted once the migrations finish
+async fn renew_lock(db: Database, owner: ObjectId) {
+    let collection =
+        db.collection::<SchemaMigrationLockModel>(SchemaMigrationLockModel::COLLECTION_NAME);
+    loop {
+        tokio::time::sleep(Duration::from_secs(LOCK_RENEW_SECONDS)).await;
+        let result = collection
+            .update_one(
+                doc! { "_id": LOCK_ID, "owner": owner },
+                doc! { "$set": { "expires_at": lock_expires_at() } },
+                None,
+            )
+            .await;
+        match result {
+            Ok(result) if result.matched_count == 0 => {
+                error!("Lost the schema migrations lock while the migrations were running");
+                return;
+            }
+            Ok(_) => {}
+            Err(error) => error!("Failed to renew the schema migrations lock: {:?}", error),
+        }
+    }
+}
+
+pub(crate) fn is_duplicate_key(error: &mongodb::error::Error) -> bool {
+    match *error.kind {
+        ErrorKind::Command(ref command_error) => command_error.code == 11000,
+        ErrorKind::Write(WriteFailure::WriteError(ref write_error)) => write_error.code == 11000,
+        _ => false,
+    }
+}
+
+/// Names were not always saved with the non breaking spaces the game uses
+pub struct NormalizeClanMateNames;
+
+#[async_trait]
+impl Migration for NormalizeClanMateNames {
+    fn version(&self) -> u32 {
+        1
+    }
+
+    fn name(&self) -> &'static str {
+        "normalize_clan_mate_names"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let collection = db.collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME);
+        let filter = doc! {
+            "$or": [
+                { "player_name": { "$regex": " " } },
+                { "previous_names": { "$regex": " " } },
+            ]
+        };
+        if dry_run {
+            return Ok(collection.count_documents(filter, None).await?);
+        }
+
+        let clan_mates: Vec<ClanMateModel> =
+            collection.find(filter, None).await?.try_collect().await?;
+        for clan_mate in &clan_mates {
+            let previous_names: Vec<String> = clan_mate
+                .previous_names
+                .iter()
+                .map(|name| name.replace(" ", "\u{a0}"))
+                .collect();
+            collection
+                .update_one(
+                    doc! { "_id": clan_mate.id },
+                    doc! { "$set": {
+                        "player_name": clan_mate.player_name.replace(" ", "\u{a0}"),
+                        "previous_names": previous_names,
+                    } },
+                    None,
+                )
+                .await?;
+        }
+        Ok(clan_mates.len() as u64)
+    }
+}
+
+/// Gives each guild its own clan mates, after the names are the same so the duplicates are found
+pub struct SplitCrossGuildClanMates;
+
+#[async_trait]
+impl Migration for SplitCrossGuildClanMates {
+    fn version(&self) -> u32 {
+        2
+    }
+
+    fn name(&self) -> &'static str {
+        "split_cross_guild_clan_mates"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let report = ClanMatesDb::new_instance(db.clone())
+            .split_cross_guild_clan_mates(dry_run)
+            .await?;
+        info!("Clan mates split between guilds: {:?}", report);
+        Ok(report.clan_mates_created + report.duplicates_merged + report.records_reassigned)
+    }
+}
+
+/// Guilds saved before a setting was added are missing it, this gives every guild the same shape
+pub struct BackfillGuildSettings;
+
+impl BackfillGuildSettings {
+    //The optional guild settings there were when this migration was added
+    const OPTIONAL_FIELDS: [&'static str; 24] = [
+        "clan_name",
+        "broadcast_channel",
+        "clan_chat_channel",
+        "leagues_broadcast_channel",
+        "drop_price_threshold",
+        "drop_price_source",
+        "min_quest_difficulty",
+        "min_diary_tier",
+        "min_combat_achievement_tier",
+        "pk_value_threshold",
+        "loot_key_value_threshold",
+        "wom_id",
+        "created_at",
+        "custom_drop_broadcast_filter",
+        "collection_log_max_percentage",
+        "drop_rarity_threshold",
+        "account_type_drop_price_thresholds",
+        "account_type_broadcast_filter",
+        "broadcast_rules",
+        "broadcast_type_channels",
+        "embed_templates",
+        "rank_roles",
+        "rank_role_sync_enabled",
+        "role_pings",
+    ];
+}
+
+#[async_trait]
+impl Migration for BackfillGuildSettings {
+    fn version(&self) -> u32 {
+        3
+    }
+
+    fn name(&self) -> &'static str {
+        "backfill_guild_settings"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let collection =
+            db.collection::<RegisteredGuildModel>(RegisteredGuildModel::COLLECTION_NAME);
+        let mut fields: Vec<(&str, Bson)> = Self::OPTIONAL_FIELDS
+            .iter()
+            .map(|field| (*field, Bson::Null))
+            .collect();
+        fields.push(("disallowed_broadcast_types", Bson::Array(Vec::new())));
+
+        let missing_any_field: Vec<_> = fields
+            .iter()
+            .map(|(field, _)| doc! { *field: { "$exists": false } })
+            .collect();
+        let documents_changed = collection
+            .count_documents(doc! { "$or": missing_any_field }, None)
+            .await?;
+        if dry_run {
+            return Ok(documents_changed);
+        }
+
+        for (field, default) in fields {
+            collection
+                .update_many(
+                    doc! { field: { "$exists": false } },
+                    doc! { "$set": { field: default } },
+                    None,
+                )
+                .await?;
+        }
+        Ok(documents_changed)
+    }
+}
+
+/// Records that should have been one per clan mate were sometimes saved twice, and merging
+/// clan mates brought their records together. Keeps the best of each so the unique indexes
+/// can be made
+pub struct RemoveDuplicateRecords;
+
+impl RemoveDuplicateRecords {
+    //The collection, the fields that should be unique and the sort that puts the one kept first
+    fn duplicates() -> Vec<(&'static str, Document, Document)> {
+        vec![
+            (
+                PersonalBestRecordsModel::COLLECTION_NAME,
+                doc! { "clan_mate_id": "$clan_mate_id", "activity_id": "$activity_id" },
+                doc! { "time_in_seconds": 1, "updated_at": 1 },
+            ),
+            (
+                ClanMateCollectionLogTotalModel::COLLECTION_NAME,
+                doc! { "guild_id": "$guild_id", "player_id": "$player_id" },
+                doc! { "total": -1 },
+            ),
+            (
+                ClanMateCombatAchievementModel::COLLECTION_NAME,
+                doc! { "guild_id": "$guild_id", "player_id": "$player_id" },
+                doc! { "highest_tier_ranking": -1, "updated_at": 1 },
+            ),
+            (
+                ClanMateLeaguesProgressModel::COLLECTION_NAME,
+                doc! { "guild_id": "$guild_id", "player_id": "$player_id" },
+                doc! { "updated_at": -1 },
+            ),
+        ]
+    }
+}
+
+#[async_trait]
+impl Migration for RemoveDuplicateRecords {
+    fn version(&self) -> u32 {
+        4
+    }
+
+    fn name(&self) -> &'static str {
+        "remove_duplicate_records"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let mut documents_changed = 0;
+        for (collection_name, unique_fields, keep_first) in Self::duplicates() {
+            let collection = db.collection::<Document>(collection_name);
+            let groups: Vec<Document> = collection
+                .aggregate(
+                    vec![
+                        doc! { "$sort": keep_first },
+                        doc! { "$group": {
+                            "_id": unique_fields,
+                            "ids": { "$push": "$_id" },
+                            "count": { "$sum": 1 },
+                        } },
+                        doc! { "$match": { "count": { "$gt": 1 } } },
+                    ],
+                    AggregateOptions::builder().allow_disk_use(true).build(),
+                )
+                .await?
+                .try_collect()
+                .await?;
+
+            let duplicate_ids: Vec<Bson> = groups
+                .iter()
+                .filter_map(|group| group.get_array("ids").ok())
+                .flat_map(|ids| ids.iter().skip(1).cloned())
+                .collect();
+            if duplicate_ids.is_empty() {
+                continue;
+            }
+            info!(
+                "{} duplicate documents in {}",
+                duplicate_ids.len(),
+                collection_name
+            );
+            documents_changed += duplicate_ids.len() as u64;
+            if !dry_run {
+                collection
+                    .delete_many(doc! { "_id": { "$in": duplicate_ids } }, None)
+                    .await?;
+            }
+        }
+        Ok(documents_changed)
+    }
+}
+
+/// Only the best time was kept before the history, so each record starts its clan mates history
+pub struct SeedPersonalBestHistory;
+
+#[async_trait]
+impl Migration for SeedPersonalBestHistory {
+    fn version(&self) -> u32 {
+        5
+    }
+
+    fn name(&self) -> &'static str {
+        "seed_personal_best_history"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let records: Vec<PersonalBestRecordsModel> = db
+            .collection::<PersonalBestRecordsModel>(PersonalBestRecordsModel::COLLECTION_NAME)
+            .find(None, None)
+            .await?
+            .try_collect()
+            .await?;
+        let history_collection =
+            db.collection::<PersonalBestHistoryModel>(PersonalBestHistoryModel::COLLECTION_NAME);
+
+        let mut documents_changed = 0;
+        for record in records {
+            //Skips the records that already have history if it stopped part way
+            let has_history = history_collection
+                .count_documents(
+                    doc! { "clan_mate_id": record.clan_mate_id, "activity_id": record.activity_id },
+                    None,
+                )
+                .await?
+                > 0;
+            if has_history {
+                continue;
+            }
+            documents_changed += 1;
+            if dry_run {
+                continue;
+            }
+            history_collection
+                .insert_one(
+                    PersonalBestHistoryModel {
+                        id: ObjectId::new(),
+                        clan_mate_id: record.clan_mate_id,
+                        activity_id: record.activity_id,
+                        guild_id: record.guild_id,
+                        time_in_seconds: record.time_in_seconds,
+                        previous_time_in_seconds: None,
+                        broadcast: None,
+                        created_at: record.updated_at,
+                        clan_mate: None,
+                    },
+                    None,
+                )
+                .await?;
+        }
+        Ok(documents_changed)
+    }
+}
+
+/// Makes the collection log entries from the collection log broadcasts saved before they were kept
+pub struct BackfillCollectionLogEntries;
+
+#[async_trait]
+impl Migration for BackfillCollectionLogEntries {
+    fn version(&self) -> u32 {
+        6
+    }
+
+    fn name(&self) -> &'static str {
+        "backfill_collection_log_entries"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let broadcasts: Vec<BroadcastModel> = db
+            .collection::<BroadcastModel>(BroadcastsDb::COLLECTION_NAME)
+            .find(
+                doc! { "broadcast.type_of_broadcast": "CollectionLog" },
+                FindOptions::builder()
+                    .sort(doc! { "created_at": 1 })
+                    .build(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+        let entries_collection = db.collection::<ClanMateCollectionLogEntryModel>(
+            ClanMateCollectionLogEntryModel::COLLECTION_NAME,
+        );
+        let clan_mates_db = ClanMatesDb::new_instance(db.clone());
+
+        let mut documents_changed = 0;
+        for saved_broadcast in broadcasts {
+            //Leagues collection logs never changed the totals
+            if saved_broadcast.broadcast.title.contains("Leagues") {
+                continue;
+            }
+            let Some(collection_log_broadcast) =
+                collection_log_broadcast_extractor(saved_broadcast.broadcast.message.clone())
+            else {
+                continue;
+            };
+            let player_name = collection_log_broadcast.player_it_happened_to.clone();
+            let clan_mate = match clan_mates_db
+                .find_by_current_name(saved_broadcast.guild_id, player_name.clone())
+                .await?
+            {
+                Some(clan_mate) => Some(clan_mate),
+                None => {
+                    clan_mates_db
+                        .find_by_previous_name(saved_broadcast.guild_id, player_name)
+                        .await?
+                }
+            };
+            let Some(clan_mate) = clan_mate else {
+                continue;
+            };
+
+            //Skips the broadcasts that were already backfilled or recorded when they came in
+            let already_recorded = entries_collection
+                .count_documents(
+                    doc! {
+                        "$or": [
+                            { "broadcast_id": saved_broadcast.id },
+                            {
+                                "guild_id": mongodb::bson::to_bson(&saved_broadcast.guild_id)?,
+                                "player_id": clan_mate.id,
+                                "total": collection_log_broadcast.log_slots
+                            }
+                        ]
+                    },
+                    None,
+                )
+                .await?
+                > 0;
+            if already_recorded {
+                continue;
+            }
+            documents_changed += 1;
+            if dry_run {
+                continue;
+            }
+
+            let previous_total = entries_collection
+                .find_one(
+                    doc! {
+                        "guild_id": mongodb::bson::to_bson(&saved_broadcast.guild_id)?,
+                        "player_id": clan_mate.id,
+                        "created_at": { "$lt": saved_broadcast.created_at }
+                    },
+                    FindOneOptions::builder()
+                        .sort(doc! { "created_at": -1 })
+                        .build(),
+                )
+                .await?
+                .map(|entry| entry.total);
+            let mut entry = ClanMateCollectionLogEntryModel::new(
+                saved_broadcast.guild_id,
+                clan_mate.id,
+                Some(collection_log_broadcast.item_name),
+                collection_log_broadcast.log_slots,
+                previous_total,
+            );
+            entry.broadcast_id = Some(saved_broadcast.id);
+            entry.created_at = saved_broadcast.created_at;
+            entries_collection.insert_one(entry, None).await?;
+        }
+        Ok(documents_changed)
+    }
+}
+
+/// The filter and threshold settings guilds had before the setting commands saved broadcast rules
+#[derive(Debug, Deserialize)]
+struct LegacyGuildSettings {
+    #[serde(default)]
+    broadcast_rules: Option<Vec<BroadcastRule>>,
+    #[serde(default)]
+    disallowed_broadcast_types: Option<Vec<BroadcastType>>,
+    #[serde(default)]
+    custom_drop_broadcast_filter: Option<HashMap<BroadcastType, Vec<String>>>,
+    #[serde(default)]
+    account_type_broadcast_filter: Option<HashMap<BroadcastType, Vec<AccountType>>>,
+    #[serde(default)]
+    collection_log_max_percentage: Option<f64>,
+    #[serde(default)]
+    min_quest_difficulty: Option<QuestDifficulty>,
+    #[serde(default)]
+    min_diary_tier: Option<DiaryTier>,
+    #[serde(default)]
+    min_combat_achievement_tier: Option<CombatAchievementTier>,
+    #[serde(default)]
+    pk_value_threshold: Option<i64>,
+    #[serde(default)]
+    loot_key_value_threshold: Option<i64>,
+    #[serde(default)]
+    drop_rarity_threshold: Option<f64>,
+    #[serde(default)]
+    account_type_drop_price_thresholds: Option<HashMap<AccountType, i64>>,
+    #[serde(default)]
+    drop_price_threshold: Option<i64>,
+}
+
+impl LegacyGuildSettings {
+    /// The guilds own rules followed by the rules that do what its settings did
+    fn into_rules(self) -> Vec<BroadcastRule> {
+        let mut rules = self.broadcast_rules.unwrap_or_default();
+        if let Some(disallowed_broadcast_types) = self.disallowed_broadcast_types {
+            set_setting_rules(
+                &mut rules,
+                RuleSetting::Toggle,
+                toggle_rules(disallowed_broadcast_types),
+            );
+        }
+        for (broadcast_type, filters) in self.custom_drop_broadcast_filter.unwrap_or_default() {
+            let new_rules = custom_drop_filter_rules(broadcast_type.clone(), &filters);
+            set_setting_rules(
+                &mut rules,
+                RuleSetting::CustomDropFilter(broadcast_type),
+                new_rules,
+            );
+        }
+        for (broadcast_type, allowed_account_types) in
+            self.account_type_broadcast_filter.unwrap_or_default()
+        {
+            let new_rules =
+                account_type_filter_rules(broadcast_type.clone(), allowed_account_types);
+            set_setting_rules(
+                &mut rules,
+                RuleSetting::AccountTypeFilter(broadcast_type),
+                new_rules,
+            );
+        }
+        if let Some(max_percentage) = self.collection_log_max_percentage {
+            set_setting_rules(
+                &mut rules,
+                RuleSetting::CollectionLogMaxPercentage,
+                collection_log_max_percentage_rules(max_percentage),
+            );
+        }
+        let min_tiers = [
+            (
+                RuleSetting::MinQuestDifficulty,
+                self.min_quest_difficulty.map(|tier| tier.ranking()),
+            ),
+            (
+                RuleSetting::MinDiaryTier,
+                self.min_diary_tier.map(|tier| tier.ranking()),
+            ),
+            (
+                RuleSetting::MinCombatAchievementTier,
+                self.min_combat_achievement_tier.map(|tier| tier.ranking()),
+            ),
+        ];
+        for (setting, min_ranking) in min_tiers {
+            if let Some(min_ranking) = min_ranking {
+                set_setting_rules(
+                    &mut rules,
+                    setting.clone(),
+                    min_tier_rules(setting, min_ranking),
+                );
+            }
+        }
+        let mut thresholds = vec![
+            (RuleSetting::PkValueThreshold, self.pk_value_threshold),
+            (
+                RuleSetting::LootKeyValueThreshold,
+                self.loot_key_value_threshold,
+            ),
+            (RuleSetting::DropPriceThreshold, self.drop_price_threshold),
+        ];
+        for (account_type, threshold) in self.account_type_drop_price_thresholds.unwrap_or_default()
+        {
+            thresholds.push((
+                RuleSetting::AccountTypeDropPriceThreshold(account_type),
+                Some(threshold),
+            ));
+        }
+        for (setting, threshold) in thresholds {
+            if let Some(threshold) = threshold {
+                set_setting_rules(
+                    &mut rules,
+                    setting.clone(),
+                    value_threshold_rules(setting, threshold),
+                );
+            }
+        }
+        if let Some(one_in) = self.drop_rarity_threshold {
+            set_setting_rules(
+                &mut rules,
+                RuleSetting::DropRarityThreshold,
+                drop_rarity_rules(one_in),
+            );
+        }
+        rules
+    }
+}
+
+/// The setting commands save broadcast rules now, this turns the settings guilds already had into
+/// those rules and removes the settings
+pub struct MoveGuildSettingsToRules;
+
+impl MoveGuildSettingsToRules {
+    const LEGACY_FIELDS: [&'static str; 12] = [
+        "disallowed_broadcast_types",
+        "custom_drop_broadcast_filter",
+        "account_type_broadcast_filter",
+        "collection_log_max_percentage",
+        "min_quest_difficulty",
+        "min_diary_tier",
+        "min_combat_achievement_tier",
+        "pk_value_threshold",
+        "loot_key_value_threshold",
+        "drop_rarity_threshold",
+        "account_type_drop_price_thresholds",
+        "drop_price_threshold",
+    ];
+}
+
+#[async_trait]
+impl Migration for MoveGuildSettingsToRules {
+    fn version(&self) -> u32 {
+        7
+    }
+
+    fn name(&self) -> &'static str {
+        "move_guild_settings_to_rules"
+    }
+
+    async fn up(&self, db: &Database, dry_run: bool) -> Result<u64, Error> {
+        let collection = db.collection::<Document>(RegisteredGuildModel::COLLECTION_NAME);
+        let has_any_field: Vec<_> = Self::LEGACY_FIELDS
+            .iter()
+            .map(|field| doc! { *field: { "$exists": true } })
+            .collect();
+        let guilds: Vec<Document> = collection
+            .find(doc! { "$or": has_any_field }, None)
+            .await?
+            .try_collect()
+            .await?;
+        if dry_run {
+            return Ok(guilds.len() as u64);
+        }
+
+        let unset_fields: Document = Self::LEGACY_FIELDS
+            .iter()
+            .map(|field| (field.to_string(), Bson::String(String::new())))
+            .collect();
+        let mut documents_changed = 0;
+        for guild in guilds {
+            let settings: LegacyGuildSettings = mongodb::bson::from_document(guild.clone())?;
+            let rules = mongodb::bson::to_bson(&settings.into_rules())?;
+            //Setting the rules and removing the settings together means a rerun skips the guild
+            collection
+                .update_one(
+                    doc! { "_id": guild.get_object_id("_id")? },
+                    doc! {
+                        "$set": { "broadcast_rules": rules },
+                        "$unset": unset_fields.clone()
+                    },
+                    None,
+                )
+                .await?;
+            documents_changed += 1;
+        }
+        Ok(documents_changed)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
 use crate::database::clan_mates::ClanMates;
 use crate::database::drop_logs_db::DropLogs;
+use crate::database::pb_records_db::PersonalBests;
 use async_trait::async_trait;
 use mockall::automock;
 use mongodb::bson::doc;
@@ -9,9 +10,15 @@ use mongodb::Database;
 
 pub mod broadcasts;
 pub mod clan_mate_collection_log_totals;
+pub mod clan_mate_combat_achievements;
+pub mod clan_mate_leagues_progress;
+pub mod clan_mate_pets;
+pub mod clan_mate_skills;
 pub mod clan_mates;
 pub mod drop_logs_db;
 pub mod guilds_db;
+pub mod indexes;
+pub mod migrations;
 pub mod pb_activities_db;
 pub mod pb_records_db;
 
@@ -27,9 +34,15 @@ pub struct BotMongoDb {
     pub drop_logs: DropLogsDb,
     pub clan_mates: ClanMatesDb,
     pub clan_mate_collection_log_totals: ClanMateCollectionLogTotalsDb,
+    pub clan_mate_combat_achievements: ClanMateCombatAchievementsDb,
+    pub clan_mate_leagues_progress: ClanMateLeaguesProgressDb,
+    pub clan_mate_pets: ClanMatePetsDb,
+    pub clan_mate_skills: ClanMateSkillsDb,
     pub broadcasts: BroadcastsDb,
     pub pb_activities: PersonalBestActivitiesDb,
     pub pb_records: PersonalBestRecordsDb,
+    pub migrations: MigrationsDb,
+    pub indexes: IndexesDb,
 }
 
 #[derive(Clone)]
@@ -52,6 +65,26 @@ pub struct ClanMateCollectionLogTotalsDb {
     db: Database,
 }
 
+#[derive(Clone)]
+pub struct ClanMateCombatAchievementsDb {
+    db: Database,
+}
+
+#[derive(Clone)]
+pub struct ClanMateLeaguesProgressDb {
+    db: Database,
+}
+
+#[derive(Clone)]
+pub struct ClanMatePetsDb {
+    db: Database,
+}
+
+#[derive(Clone)]
+pub struct ClanMateSkillsDb {
+    db: Database,
+}
+
 #[derive(Clone)]
 pub struct BroadcastsDb {
     db: Database,
@@ -67,6 +100,16 @@ pub struct PersonalBestRecordsDb {
     db: Database,
 }
 
+#[derive(Clone)]
+pub struct MigrationsDb {
+    db: Database,
+}
+
+#[derive(Clone)]
+pub struct IndexesDb {
+    db: Database,
+}
+
 #[async_trait]
 impl MongoDb for BotMongoDb {
     async fn new_db_instance(db_url: String) -> Self {
@@ -84,9 +127,28 @@ impl MongoDb for BotMongoDb {
             clan_mate_collection_log_totals: ClanMateCollectionLogTotalsDb::new_instance(
                 db.clone(),
             ),
+            clan_mate_combat_achievements: ClanMateCombatAchievementsDb::new_instance(db.clone()),
+            clan_mate_leagues_progress: ClanMateLeaguesProgressDb::new_instance(db.clone()),
+            clan_mate_pets: ClanMatePetsDb::new_instance(db.clone()),
+            clan_mate_skills: ClanMateSkillsDb::new_instance(db.clone()),
             broadcasts: BroadcastsDb::new_instance(db.clone()),
             pb_activities: PersonalBestActivitiesDb::new_instance(db.clone()),
-            pb_records: PersonalBestRecordsDb::new_instance(db),
+            pb_records: PersonalBestRecordsDb::new_instance(db.clone()),
+            migrations: MigrationsDb::new_instance(db.clone()),
+            indexes: IndexesDb::new_instance(db),
+        }
+    }
+}
+
+impl BotMongoDb {
+    /// Every binary calls this when it starts. Runs the pending migrations and then makes the
+    /// declared indexes, unique indexes over duplicate documents can only be made once the
+    /// migrations remove them. Startup should stop if the migrations failed
+    pub async fn prepare_on_startup(&self) -> Result<(), anyhow::Error> {
+        self.migrations.run_on_startup().await?;
+        if let Err(error) = self.indexes.ensure_indexes().await {
+            log::error!("Failed to ensure the database indexes: {:?}", error);
         }
+        Ok(())
     }
 }
@@ -1,10 +1,11 @@
+use crate::database::indexes::index;
 use bson::serde_helpers::serialize_object_id_as_hex_string;
 use bson::DateTime;
 use futures::TryStreamExt;
 use mockall::predicate::*;
 use mongodb::bson::doc;
 use mongodb::options::FindOptions;
-use mongodb::{bson, Database};
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
 
 use super::PersonalBestActivitiesDb;
@@ -20,6 +21,10 @@ pub struct PersonalBestActivitiesModel {
 
 impl PersonalBestActivitiesModel {
     pub const COLLECTION_NAME: &'static str = "personal_best_activities";
+
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![index("activity_name", doc! { "activity_name": 1 })]
+    }
 }
 
 impl PersonalBestActivitiesDb {
@@ -32,26 +37,14 @@ impl PersonalBestActivitiesDb {
         activity_name: String,
     ) -> Result<PersonalBestActivitiesModel, mongodb::error::Error> {
         let trimmed_activity_name = activity_name.trim();
-        let collection = self.db.collection::<PersonalBestActivitiesModel>(
-            PersonalBestActivitiesModel::COLLECTION_NAME,
-        );
-
-        //Matches case insensitive and exact name from start and end
-        let name_filter = format!("^{}$", trimmed_activity_name);
-        let re = mongodb::bson::Regex {
-            pattern: name_filter,
-            options: "i".to_string(),
-        };
-
-        let filter = doc! {
-            "activity_name": re
-        };
-
-        match collection.find_one(filter.clone(), None).await? {
+        match self.get_activity(activity_name.clone()).await? {
             Some(activity) => {
                 return Ok(activity);
             }
             None => {
+                let collection = self.db.collection::<PersonalBestActivitiesModel>(
+                    PersonalBestActivitiesModel::COLLECTION_NAME,
+                );
                 let new_activity = PersonalBestActivitiesModel {
                     id: bson::oid::ObjectId::new(),
                     activity_name: trimmed_activity_name.to_string(),
@@ -63,6 +56,28 @@ impl PersonalBestActivitiesDb {
         }
     }
 
+    /// The activity with the name, ignoring case. None if no time has been saved for it yet
+    pub async fn get_activity(
+        &self,
+        activity_name: String,
+    ) -> Result<Option<PersonalBestActivitiesModel>, mongodb::error::Error> {
+        let collection = self.db.collection::<PersonalBestActivitiesModel>(
+            PersonalBestActivitiesModel::COLLECTION_NAME,
+        );
+
+        //Matches case insensitive and exact name from start and end
+        let name_filter = format!("^{}$", regex::escape(activity_name.trim()));
+        let re = mongodb::bson::Regex {
+            pattern: name_filter,
+            options: "i".to_string(),
+        };
+
+        let filter = doc! {
+            "activity_name": re
+        };
+        collection.find_one(filter, None).await
+    }
+
     pub async fn get_activities(
         &self,
     ) -> Result<Vec<PersonalBestActivitiesModel>, mongodb::error::Error> {
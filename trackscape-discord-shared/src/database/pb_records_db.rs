@@ -1,10 +1,15 @@
 use super::clan_mates::ClanMateModel;
-use super::PersonalBestRecordsDb;
+use super::{PersonalBestActivitiesDb, PersonalBestRecordsDb};
+use crate::database::indexes::{index, unique_index};
+use crate::database::migrations::is_duplicate_key;
+use async_trait::async_trait;
 use bson::DateTime;
 use futures::TryStreamExt;
 use mockall::predicate::*;
+use mockall::*;
 use mongodb::bson::doc;
-use mongodb::{bson, Database};
+use mongodb::options::{FindOneAndUpdateOptions, FindOneOptions, FindOptions, ReturnDocument};
+use mongodb::{bson, Database, IndexModel};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -28,56 +33,347 @@ pub struct PersonalBestRecordsViewModel {
 
 impl PersonalBestRecordsModel {
     pub const COLLECTION_NAME: &'static str = "personal_best_records";
+
+    /// A clan mate has one record per activity, leaderboards read a guilds records for an activity
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            unique_index(
+                "clan_mate_id_activity_id_unique",
+                doc! { "clan_mate_id": 1, "activity_id": 1 },
+            ),
+            index(
+                "guild_id_activity_id",
+                doc! { "guild_id": 1, "activity_id": 1 },
+            ),
+        ]
+    }
 }
 
-impl PersonalBestRecordsDb {
-    pub fn new_instance(mongodb: Database) -> Self {
+/// Every time a clan mate beat their best on an activity. Entries are only ever added, the
+/// newest one is the same as their record
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct PersonalBestHistoryModel {
+    #[serde(rename = "_id")]
+    pub id: bson::oid::ObjectId,
+    pub clan_mate_id: bson::oid::ObjectId,
+    pub activity_id: bson::oid::ObjectId,
+    pub guild_id: u64,
+    pub time_in_seconds: f64,
+    //None for the first time recorded on the activity
+    pub previous_time_in_seconds: Option<f64>,
+    //The broadcast that had the time, None when it came from the !pb chat command
+    pub broadcast: Option<String>,
+    pub created_at: DateTime,
+    pub clan_mate: Option<ClanMateModel>,
+}
+
+impl PersonalBestHistoryModel {
+    pub const COLLECTION_NAME: &'static str = "personal_best_history";
+
+    /// Progression is read for a clan mate or a whole guild on an activity, oldest first
+    pub fn indexes() -> Vec<IndexModel> {
+        vec![
+            index(
+                "clan_mate_id_activity_id_created_at",
+                doc! { "clan_mate_id": 1, "activity_id": 1, "created_at": 1 },
+            ),
+            index(
+                "guild_id_activity_id_created_at",
+                doc! { "guild_id": 1, "activity_id": 1, "created_at": 1 },
+            ),
+        ]
+    }
+}
+
+/// What recording a time changed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PersonalBestChange {
+    /// The clan mates best before this time, None on their first time for the activity
+    pub previous_best: Option<f64>,
+    /// False when the time was not faster than their best, nothing is saved then
+    pub new_best: bool,
+    /// The guilds fastest time before this one, only set when this time beat it
+    pub beaten_clan_record: Option<ClanRecord>,
+}
+
+/// The fastest time in a guild on an activity
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClanRecord {
+    pub player_name: String,
+    pub time_in_seconds: f64,
+}
+
+#[automock]
+#[async_trait]
+pub trait PersonalBests: Send + Sync {
+    fn new_instance(mongodb: Database) -> Self;
+
+    /// The clan mates best time on the activity, None before their first time. Only reads, the
+    /// time from a broadcast is saved by the record_new_pb job
+    async fn get_personal_best(
+        &self,
+        clan_mate_id: bson::oid::ObjectId,
+        activity_name: String,
+    ) -> Result<Option<f64>, anyhow::Error>;
+}
+
+#[async_trait]
+impl PersonalBests for PersonalBestRecordsDb {
+    fn new_instance(mongodb: Database) -> Self {
         Self { db: mongodb }
     }
 
+    async fn get_personal_best(
+        &self,
+        clan_mate_id: bson::oid::ObjectId,
+        activity_name: String,
+    ) -> Result<Option<f64>, anyhow::Error> {
+        let Some(activity) = PersonalBestActivitiesDb::new_instance(self.db.clone())
+            .get_activity(activity_name)
+            .await?
+        else {
+            return Ok(None);
+        };
+        let record = self
+            .db
+            .collection::<PersonalBestRecordsModel>(PersonalBestRecordsModel::COLLECTION_NAME)
+            .find_one(
+                doc! { "clan_mate_id": clan_mate_id, "activity_id": activity.id },
+                None,
+            )
+            .await?;
+        Ok(record.map(|record| record.time_in_seconds))
+    }
+}
+
+impl PersonalBestRecordsDb {
+    /// Saves the time if it is the clan mates best on the activity, the broadcast is kept with it
+    pub async fn record_personal_best(
+        &self,
+        guild_id: u64,
+        clan_mate_id: bson::oid::ObjectId,
+        activity_name: String,
+        time_in_seconds: f64,
+        broadcast: Option<String>,
+    ) -> Result<PersonalBestChange, anyhow::Error> {
+        let activity = PersonalBestActivitiesDb::new_instance(self.db.clone())
+            .create_or_get_activity(activity_name)
+            .await?;
+        self.create_or_update_pb_record(
+            clan_mate_id,
+            activity.id,
+            guild_id,
+            time_in_seconds,
+            broadcast,
+        )
+        .await
+    }
+
+    /// Updates the clan mates record and adds it to their history when the time is faster.
+    /// The record is changed with a single $min update, the record from before it says whether
+    /// the time was a new best so two times arriving at once can not both be counted
     pub async fn create_or_update_pb_record(
         &self,
         clan_mate_id: bson::oid::ObjectId,
         activity_id: bson::oid::ObjectId,
         guild_id: u64,
         time_in_seconds: f64,
-    ) -> Result<(), anyhow::Error> {
+        broadcast: Option<String>,
+    ) -> Result<PersonalBestChange, anyhow::Error> {
         let collection = self
             .db
             .collection::<PersonalBestRecordsModel>(PersonalBestRecordsModel::COLLECTION_NAME);
 
         let filter = doc! {
-            "clan_mate_id": clan_mate_id.clone(),
+            "clan_mate_id": clan_mate_id,
             "activity_id": activity_id
         };
-        match collection.find_one(filter.clone(), None).await? {
-            Some(recorded_record) => {
-                if time_in_seconds < recorded_record.time_in_seconds {
-                    let update = doc! {
-                        "$set": {
-                            "time_in_seconds": time_in_seconds,
-                            "updated_at": bson::DateTime::now()
-                        }
-                    };
-                    collection.update_one(filter, update, None).await?;
-                }
-                Ok(())
+        let now = bson::DateTime::now();
+        let faster = doc! {
+            "$or": [
+                { "$eq": [{ "$type": "$time_in_seconds" }, "missing"] },
+                { "$lt": [time_in_seconds, "$time_in_seconds"] }
+            ]
+        };
+        let update = vec![doc! {
+            "$set": {
+                "guild_id": bson::to_bson(&guild_id)?,
+                "time_in_seconds": { "$min": ["$time_in_seconds", time_in_seconds] },
+                "updated_at": { "$cond": [faster, now, "$updated_at"] },
+                "created_at": { "$ifNull": ["$created_at", now] }
+            }
+        }];
+        let options = FindOneAndUpdateOptions::builder()
+            .upsert(true)
+            .return_document(ReturnDocument::Before)
+            .build();
+        let previous_record = match collection
+            .find_one_and_update(filter.clone(), update.clone(), options.clone())
+            .await
+        {
+            Ok(previous_record) => previous_record,
+            //Two first times at once both try to insert, the second one updates the new record
+            Err(error) if is_duplicate_key(&error) => {
+                collection
+                    .find_one_and_update(filter, update, options)
+                    .await?
             }
-            None => {
-                let new_pb = PersonalBestRecordsModel {
+            Err(error) => return Err(error.into()),
+        };
+
+        let previous_best = previous_record.map(|previous_record| previous_record.time_in_seconds);
+        if previous_best.is_some_and(|previous_best| time_in_seconds >= previous_best) {
+            return Ok(PersonalBestChange {
+                previous_best,
+                new_best: false,
+                beaten_clan_record: None,
+            });
+        }
+
+        //The guilds record before this time was the fastest of the clan mates old best and everyone else's
+        let fastest_other = self
+            .get_fastest_other_record(activity_id, guild_id, clan_mate_id)
+            .await?;
+        let previous_clan_record = match (fastest_other, previous_best) {
+            (Some(other), Some(previous_best)) if previous_best < other.time_in_seconds => {
+                Some(self.clan_record_of(clan_mate_id, previous_best).await?)
+            }
+            (Some(other), _) => Some(other),
+            (None, Some(previous_best)) => {
+                Some(self.clan_record_of(clan_mate_id, previous_best).await?)
+            }
+            (None, None) => None,
+        };
+        let beaten_clan_record = previous_clan_record
+            .filter(|clan_record| time_in_seconds < clan_record.time_in_seconds);
+
+        let history_collection = self
+            .db
+            .collection::<PersonalBestHistoryModel>(PersonalBestHistoryModel::COLLECTION_NAME);
+        history_collection
+            .insert_one(
+                PersonalBestHistoryModel {
                     id: bson::oid::ObjectId::new(),
-                    clan_mate_id: clan_mate_id.clone(),
-                    activity_id: activity_id.clone(),
+                    clan_mate_id,
+                    activity_id,
                     guild_id,
-                    time_in_seconds: time_in_seconds.clone(),
-                    created_at: bson::DateTime::now(),
-                    updated_at: bson::DateTime::now(),
+                    time_in_seconds,
+                    previous_time_in_seconds: previous_best,
+                    broadcast,
+                    created_at: now,
                     clan_mate: None,
-                };
-                collection.insert_one(new_pb, None).await?;
-                Ok(())
-            }
+                },
+                None,
+            )
+            .await?;
+
+        Ok(PersonalBestChange {
+            previous_best,
+            new_best: true,
+            beaten_clan_record,
+        })
+    }
+
+    /// The fastest time in the guild on the activity from anyone but the clan mate, the first to
+    /// get the time keeps the record on a tie
+    async fn get_fastest_other_record(
+        &self,
+        activity_id: bson::oid::ObjectId,
+        guild_id: u64,
+        clan_mate_id: bson::oid::ObjectId,
+    ) -> Result<Option<ClanRecord>, anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<PersonalBestRecordsModel>(PersonalBestRecordsModel::COLLECTION_NAME);
+        let filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "activity_id": activity_id,
+            "clan_mate_id": { "$ne": clan_mate_id }
+        };
+        let options = FindOneOptions::builder()
+            .sort(doc! { "time_in_seconds": 1, "updated_at": 1 })
+            .build();
+        let Some(record) = collection.find_one(filter, options).await? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            self.clan_record_of(record.clan_mate_id, record.time_in_seconds)
+                .await?,
+        ))
+    }
+
+    async fn clan_record_of(
+        &self,
+        clan_mate_id: bson::oid::ObjectId,
+        time_in_seconds: f64,
+    ) -> Result<ClanRecord, anyhow::Error> {
+        let clan_mate = self
+            .db
+            .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME)
+            .find_one(doc! { "_id": clan_mate_id }, None)
+            .await?;
+        Ok(ClanRecord {
+            player_name: clan_mate
+                .map(|clan_mate| clan_mate.player_name)
+                .unwrap_or_default(),
+            time_in_seconds,
+        })
+    }
+
+    /// Every best the guild has had on the activity, oldest first with the clan mate attached.
+    /// Can be narrowed to one clan mate for their own chart
+    pub async fn get_pb_progression(
+        &self,
+        activity_id: bson::oid::ObjectId,
+        guild_id: u64,
+        clan_mate_id: Option<bson::oid::ObjectId>,
+    ) -> Result<Vec<PersonalBestHistoryModel>, anyhow::Error> {
+        let collection = self
+            .db
+            .collection::<PersonalBestHistoryModel>(PersonalBestHistoryModel::COLLECTION_NAME);
+        let mut filter = doc! {
+            "guild_id": bson::to_bson(&guild_id).unwrap(),
+            "activity_id": activity_id
+        };
+        if let Some(clan_mate_id) = clan_mate_id {
+            filter.insert("clan_mate_id", clan_mate_id);
         }
+
+        let history: Vec<PersonalBestHistoryModel> = collection
+            .find(
+                filter,
+                FindOptions::builder()
+                    .sort(doc! { "created_at": 1 })
+                    .build(),
+            )
+            .await?
+            .try_collect()
+            .await?;
+        let mut clan_mate_ids: Vec<bson::oid::ObjectId> =
+            history.iter().map(|entry| entry.clan_mate_id).collect();
+        clan_mate_ids.sort();
+        clan_mate_ids.dedup();
+        let clan_mates: Vec<ClanMateModel> = self
+            .db
+            .collection::<ClanMateModel>(ClanMateModel::COLLECTION_NAME)
+            .find(doc! { "_id": { "$in": clan_mate_ids } }, None)
+            .await?
+            .try_collect()
+            .await?;
+
+        //Clan mates that were removed from the clan are left off like the leaderboard does
+        Ok(history
+            .into_iter()
+            .filter_map(|mut entry| {
+                entry.clan_mate = Some(
+                    clan_mates
+                        .iter()
+                        .find(|clan_mate| clan_mate.id == entry.clan_mate_id)?
+                        .clone(),
+                );
+                Some(entry)
+            })
+            .collect())
     }
 
     pub async fn get_pb_records_leaderboard(
@@ -0,0 +1,237 @@
+use crate::embed_templates::RenderedEmbed;
+use anyhow::anyhow;
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+
+const DISCORD_API_BASE_URL: &str = "https://discord.com/api/v10";
+
+/// Calls the Discord REST api with the bot token for work done outside the bot, like the jobs
+pub struct DiscordApiClient {
+    bot_token: String,
+    web_client: reqwest::Client,
+}
+
+//The most members discord returns in one page
+const MEMBERS_PAGE_SIZE: usize = 1000;
+
+#[derive(Deserialize)]
+struct GuildMember {
+    user: Option<GuildMemberUser>,
+    roles: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct GuildMemberUser {
+    id: String,
+}
+
+/// A member of the discord server and the role ids they have
+#[derive(Debug, Clone)]
+pub struct DiscordMember {
+    pub discord_user_id: u64,
+    pub roles: Vec<u64>,
+}
+
+fn parse_role_ids(roles: &[String]) -> Vec<u64> {
+    roles
+        .iter()
+        .filter_map(|role_id| role_id.parse::<u64>().ok())
+        .collect()
+}
+
+impl DiscordApiClient {
+    pub fn new(bot_token: String) -> Self {
+        DiscordApiClient {
+            bot_token,
+            web_client: reqwest::Client::new(),
+        }
+    }
+
+    /// The role ids the member has, None if they are not in the discord server
+    pub async fn get_member_roles(
+        &self,
+        guild_id: u64,
+        discord_user_id: u64,
+    ) -> Result<Option<Vec<u64>>, anyhow::Error> {
+        let resp = self
+            .web_client
+            .get(format!(
+                "{}/guilds/{}/members/{}",
+                DISCORD_API_BASE_URL, guild_id, discord_user_id
+            ))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+        if resp.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Failed to get discord member {}: {}",
+                discord_user_id,
+                resp.status()
+            ));
+        }
+        let member = resp.json::<GuildMember>().await?;
+        Ok(Some(parse_role_ids(&member.roles)))
+    }
+
+    /// Every member of the discord server, read a page at a time.
+    /// Needs the bot to have the server members intent
+    pub async fn list_guild_members(
+        &self,
+        guild_id: u64,
+    ) -> Result<Vec<DiscordMember>, anyhow::Error> {
+        let mut members: Vec<DiscordMember> = Vec::new();
+        let mut after: u64 = 0;
+        loop {
+            let resp = self
+                .web_client
+                .get(format!(
+                    "{}/guilds/{}/members?limit={}&after={}",
+                    DISCORD_API_BASE_URL, guild_id, MEMBERS_PAGE_SIZE, after
+                ))
+                .header("Authorization", format!("Bot {}", self.bot_token))
+                .send()
+                .await?;
+            if !resp.status().is_success() {
+                return Err(anyhow!(
+                    "Failed to list the members of guild {}: {}",
+                    guild_id,
+                    resp.status()
+                ));
+            }
+            let page = resp.json::<Vec<GuildMember>>().await?;
+            let page_size = page.len();
+            for member in page {
+                let Some(discord_user_id) =
+                    member.user.and_then(|user| user.id.parse::<u64>().ok())
+                else {
+                    continue;
+                };
+                after = after.max(discord_user_id);
+                members.push(DiscordMember {
+                    discord_user_id,
+                    roles: parse_role_ids(&member.roles),
+                });
+            }
+            if page_size < MEMBERS_PAGE_SIZE {
+                return Ok(members);
+            }
+        }
+    }
+
+    pub async fn add_member_role(
+        &self,
+        guild_id: u64,
+        discord_user_id: u64,
+        role_id: u64,
+    ) -> Result<(), anyhow::Error> {
+        let resp = self
+            .web_client
+            .put(self.member_role_url(guild_id, discord_user_id, role_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .header("Content-Length", "0")
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Failed to add role {} to {}: {}",
+                role_id,
+                discord_user_id,
+                resp.status()
+            ));
+        }
+        Ok(())
+    }
+
+    pub async fn remove_member_role(
+        &self,
+        guild_id: u64,
+        discord_user_id: u64,
+        role_id: u64,
+    ) -> Result<(), anyhow::Error> {
+        let resp = self
+            .web_client
+            .delete(self.member_role_url(guild_id, discord_user_id, role_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Failed to remove role {} from {}: {}",
+                role_id,
+                discord_user_id,
+                resp.status()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Sends the embed to the channel. Only the users and roles given can be pinged, never
+    /// @everyone or @here
+    pub async fn send_embed(
+        &self,
+        channel_id: u64,
+        embed: &RenderedEmbed,
+        discord_user_ids: Vec<u64>,
+        role_ids: Vec<u64>,
+    ) -> Result<(), anyhow::Error> {
+        let mentions: Vec<String> = role_ids
+            .iter()
+            .map(|role_id| format!("<@&{}>", role_id))
+            .chain(
+                discord_user_ids
+                    .iter()
+                    .map(|discord_user_id| format!("<@{}>", discord_user_id)),
+            )
+            .collect();
+        let mut embed_json = json!({
+            "title": embed.title,
+            "description": embed.description,
+            "color": embed.color,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        });
+        if let Some(image_url) = &embed.image_url {
+            embed_json["image"] = json!({ "url": image_url });
+        }
+        if let Some(thumbnail_url) = &embed.thumbnail_url {
+            embed_json["thumbnail"] = json!({ "url": thumbnail_url });
+        }
+        let body = json!({
+            "content": mentions.join(" "),
+            "embeds": [embed_json],
+            "allowed_mentions": {
+                "parse": [],
+                "users": discord_user_ids.iter().map(|id| id.to_string()).collect::<Vec<String>>(),
+                "roles": role_ids.iter().map(|id| id.to_string()).collect::<Vec<String>>(),
+            },
+        });
+        let resp = self
+            .web_client
+            .post(format!(
+                "{}/channels/{}/messages",
+                DISCORD_API_BASE_URL, channel_id
+            ))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            return Err(anyhow!(
+                "Failed to send a message to channel {}: {}",
+                channel_id,
+                resp.status()
+            ));
+        }
+        Ok(())
+    }
+
+    fn member_role_url(&self, guild_id: u64, discord_user_id: u64, role_id: u64) -> String {
+        format!(
+            "{}/guilds/{}/members/{}/roles/{}",
+            DISCORD_API_BASE_URL, guild_id, discord_user_id, role_id
+        )
+    }
+}
@@ -0,0 +1,226 @@
+use crate::database::guilds_db::RegisteredGuildModel;
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::BroadcastType;
+use crate::osrs_broadcast_handler::BroadcastMessageToDiscord;
+use crate::wiki_api::wiki_api::format_drop_rate;
+use num_format::{Locale, ToFormattedString};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_EMBED_COLOR: u32 = 0x0000FF;
+
+/// Every placeholder a template can use
+pub const TEMPLATE_PLACEHOLDERS: [&str; 11] = [
+    "{player}",
+    "{item}",
+    "{value}",
+    "{ge_high}",
+    "{ge_low}",
+    "{kc}",
+    "{time}",
+    "{previous_time}",
+    "{time_change}",
+    "{rate}",
+    "{message}",
+];
+
+/// Where the broadcasts icon goes on the embed
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum TemplateImage {
+    Image,
+    Thumbnail,
+    Hidden,
+}
+
+/// A guilds override for how a broadcast type looks. Anything left empty uses the default
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct EmbedTemplate {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub color: Option<u32>,
+    pub image: Option<TemplateImage>,
+}
+
+/// The values the placeholders are filled with. Missing ones render as an empty string
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TemplateValues {
+    pub item: Option<String>,
+    pub value: Option<i64>,
+    //Live GE prices of the whole drop, next to the value from the broadcast
+    pub ge_high: Option<i64>,
+    pub ge_low: Option<i64>,
+    //Could be kc, task count, etc
+    pub kc: Option<i64>,
+    pub time: Option<String>,
+    //The best time before a personal best or clan record and how much faster the new one is
+    pub previous_time: Option<String>,
+    pub time_change: Option<String>,
+    //Wiki drop rate, like 1/512
+    pub rate: Option<String>,
+}
+
+/// What the api and bot turn into a discord embed
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderedEmbed {
+    pub title: String,
+    pub description: String,
+    pub color: u32,
+    pub image_url: Option<String>,
+    pub thumbnail_url: Option<String>,
+}
+
+impl EmbedTemplate {
+    pub fn to_string(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if let Some(title) = &self.title {
+            parts.push(format!("Title: `{}`", title));
+        }
+        if let Some(description) = &self.description {
+            parts.push(format!("Description: `{}`", description));
+        }
+        if let Some(color) = self.color {
+            parts.push(format!("Color: `#{:06X}`", color));
+        }
+        if let Some(image) = &self.image {
+            parts.push(format!("Icon: {:?}", image));
+        }
+        match parts.is_empty() {
+            true => "Default".to_string(),
+            false => parts.join(", "),
+        }
+    }
+}
+
+/// Fills in the placeholders in the text. Unknown placeholders are left as they are
+pub fn render_template_text(
+    text: &str,
+    broadcast: &BroadcastMessageToDiscord,
+    values: &TemplateValues,
+) -> String {
+    text.replace("{player}", &broadcast.player_it_happened_to)
+        .replace("{item}", values.item.as_deref().unwrap_or(""))
+        .replace(
+            "{value}",
+            &values
+                .value
+                .map(|value| value.to_formatted_string(&Locale::en))
+                .unwrap_or_default(),
+        )
+        .replace(
+            "{ge_high}",
+            &values
+                .ge_high
+                .map(|ge_high| ge_high.to_formatted_string(&Locale::en))
+                .unwrap_or_default(),
+        )
+        .replace(
+            "{ge_low}",
+            &values
+                .ge_low
+                .map(|ge_low| ge_low.to_formatted_string(&Locale::en))
+                .unwrap_or_default(),
+        )
+        .replace(
+            "{kc}",
+            &values
+                .kc
+                .map(|kc| kc.to_formatted_string(&Locale::en))
+                .unwrap_or_default(),
+        )
+        .replace("{time}", values.time.as_deref().unwrap_or(""))
+        .replace(
+            "{previous_time}",
+            values.previous_time.as_deref().unwrap_or(""),
+        )
+        .replace("{time_change}", values.time_change.as_deref().unwrap_or(""))
+        .replace("{rate}", values.rate.as_deref().unwrap_or(""))
+        .replace("{message}", &broadcast.message)
+}
+
+/// Renders the broadcast with the template, falling back to the handlers title, message and icon
+pub fn render_broadcast_embed(
+    template: Option<&EmbedTemplate>,
+    broadcast: &BroadcastMessageToDiscord,
+) -> RenderedEmbed {
+    let default_template = EmbedTemplate::default();
+    let template = template.unwrap_or(&default_template);
+    let values = &broadcast.template_values;
+
+    let title = match &template.title {
+        Some(title) => render_template_text(title, broadcast, values),
+        None => broadcast.title.clone(),
+    };
+    let description = match &template.description {
+        Some(description) => render_template_text(description, broadcast, values),
+        None => broadcast.message.clone(),
+    };
+    let (image_url, thumbnail_url) = match template.image {
+        None | Some(TemplateImage::Image) => (broadcast.icon_url.clone(), None),
+        Some(TemplateImage::Thumbnail) => (None, broadcast.icon_url.clone()),
+        Some(TemplateImage::Hidden) => (None, None),
+    };
+
+    RenderedEmbed {
+        title,
+        description,
+        color: template.color.unwrap_or(DEFAULT_EMBED_COLOR),
+        image_url,
+        thumbnail_url,
+    }
+}
+
+/// Renders the broadcast with the guilds template for its type
+pub fn render_guild_broadcast_embed(
+    registered_guild: &RegisteredGuildModel,
+    broadcast: &BroadcastMessageToDiscord,
+) -> RenderedEmbed {
+    let template = registered_guild
+        .embed_templates
+        .as_ref()
+        .and_then(|templates| templates.get(&broadcast.type_of_broadcast));
+    render_broadcast_embed(template, broadcast)
+}
+
+/// A made up broadcast to show what a template looks like
+pub fn example_broadcast(broadcast_type: BroadcastType) -> BroadcastMessageToDiscord {
+    BroadcastMessageToDiscord {
+        player_it_happened_to: "Zezima".to_string(),
+        message: format!("Zezima has a new {} broadcast.", broadcast_type.to_string()),
+        title: format!(":tada: New {} broadcast!", broadcast_type.to_string()),
+        type_of_broadcast: broadcast_type,
+        icon_url: Some("https://oldschool.runescape.wiki/images/Twisted_bow.png".to_string()),
+        item_quantity: None,
+        route_to_channel: None,
+        ping_role_id: None,
+        template_values: TemplateValues {
+            item: Some("Twisted bow".to_string()),
+            value: Some(1_234_567_890),
+            ge_high: Some(1_250_000_000),
+            ge_low: Some(1_230_000_000),
+            kc: Some(512),
+            time: Some(format_time(83.4)),
+            previous_time: Some(format_time(86.8)),
+            time_change: Some(format_time_change(3.4)),
+            rate: Some(format_drop_rate(512.0)),
+        },
+    }
+}
+
+/// Formats seconds like the game does, 1:23.40 or 1:02:03.00
+pub fn format_time(time_in_seconds: f64) -> String {
+    let hundredths = (time_in_seconds * 100.0).round() as i64;
+    let hours = hundredths / 360_000;
+    let minutes = (hundredths / 6_000) % 60;
+    let seconds = (hundredths / 100) % 60;
+    let hundredths = hundredths % 100;
+    match hours > 0 {
+        true => format!("{}:{:02}:{:02}.{:02}", hours, minutes, seconds, hundredths),
+        false => format!("{}:{:02}.{:02}", minutes, seconds, hundredths),
+    }
+}
+
+/// How much faster a time is, seconds under a minute like -3.40s otherwise like -1:02.00
+pub fn format_time_change(seconds_faster: f64) -> String {
+    match seconds_faster < 60.0 {
+        true => format!("-{:.2}s", seconds_faster),
+        false => format!("-{}", format_time(seconds_faster)),
+    }
+}
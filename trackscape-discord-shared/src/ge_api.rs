@@ -2,11 +2,25 @@ pub mod ge_api {
     use redis::Connection;
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
+    use std::collections::HashMap;
 
-    use crate::redis_helpers::{fetch_redis_json_object, write_to_cache_with_seconds};
+    use crate::redis_helpers::{
+        fetch_redis_json_object, redis_exists, write_to_cache_with_seconds,
+    };
+    use anyhow::anyhow;
 
     const CACHE_KEY: &str = "ge_mapping";
 
+    const LATEST_PRICES_CACHE_KEY: &str = "ge_latest_prices";
+
+    //Prices move quickly so they are only cached for a few minutes
+    const LATEST_PRICES_CACHE_SECONDS: usize = 300;
+
+    const LATEST_PRICES_FAILURE_KEY: &str = "failure:ge_latest_prices";
+
+    //How long broadcasts go without prices after the prices api fails, before it is tried again
+    const LATEST_PRICES_FAILURE_SECONDS: usize = 60;
+
     static APP_USER_AGENT: &str = concat!(
         env!("CARGO_PKG_NAME"),
         "/",
@@ -72,8 +86,163 @@ pub mod ge_api {
         Ok(price)
     }
 
+    /// The latest high and low prices of every traded item, by item id
+    pub async fn get_latest_prices(
+        redis_connection: &mut Connection,
+    ) -> Result<GePrices, anyhow::Error> {
+        let cached_result =
+            fetch_redis_json_object::<GePrices>(redis_connection, LATEST_PRICES_CACHE_KEY).await;
+        if let Ok(prices) = cached_result {
+            return Ok(prices);
+        }
+
+        // Prices are fetched while broadcasts come in, so don't wait on the api every time it is down
+        if redis_exists(redis_connection, LATEST_PRICES_FAILURE_KEY).await {
+            return Err(anyhow!(
+                "Failed to fetch the latest prices previously; skipping external API call for up to a minute"
+            ));
+        }
+
+        match fetch_latest_prices().await {
+            Ok(prices) => {
+                write_to_cache_with_seconds(
+                    redis_connection,
+                    LATEST_PRICES_CACHE_KEY,
+                    prices.clone(),
+                    LATEST_PRICES_CACHE_SECONDS,
+                )
+                .await;
+                Ok(prices)
+            }
+            Err(e) => {
+                write_to_cache_with_seconds(
+                    redis_connection,
+                    LATEST_PRICES_FAILURE_KEY,
+                    true,
+                    LATEST_PRICES_FAILURE_SECONDS,
+                )
+                .await;
+                Err(e)
+            }
+        }
+    }
+
+    async fn fetch_latest_prices() -> Result<GePrices, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .build()?;
+        let latest_prices = client
+            .get(format!("{}{}", BASE_URL, "osrs/latest").as_str())
+            .send()
+            .await?
+            .json::<LatestPrices>()
+            .await?;
+        let prices: GePrices = latest_prices
+            .data
+            .into_iter()
+            .filter_map(|(id, price)| {
+                let id = id.parse::<i64>().ok()?;
+                Some((
+                    id,
+                    GeItemPrice {
+                        high: price.high.unwrap_or(0),
+                        high_time: price.high_time.unwrap_or(0),
+                        low: price.low.unwrap_or(0),
+                        low_time: price.low_time.unwrap_or(0),
+                    },
+                ))
+            })
+            .collect();
+        Ok(prices)
+    }
+
     pub type GeItemMapping = Vec<GetItem>;
 
+    pub type GePrices = HashMap<i64, GeItemPrice>;
+
+    /// Which price a guilds drop thresholds are checked against
+    #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+    pub enum DropPriceSource {
+        //The coin value in the broadcast text
+        #[default]
+        Broadcast,
+        GeHigh,
+        GeLow,
+        HighAlch,
+    }
+
+    impl DropPriceSource {
+        pub fn iter() -> Vec<DropPriceSource> {
+            vec![
+                DropPriceSource::Broadcast,
+                DropPriceSource::GeHigh,
+                DropPriceSource::GeLow,
+                DropPriceSource::HighAlch,
+            ]
+        }
+
+        pub fn to_string(&self) -> String {
+            match self {
+                DropPriceSource::Broadcast => "Broadcast value".to_string(),
+                DropPriceSource::GeHigh => "GE high".to_string(),
+                DropPriceSource::GeLow => "GE low".to_string(),
+                DropPriceSource::HighAlch => "High alch".to_string(),
+            }
+        }
+
+        pub fn to_slug(&self) -> String {
+            match self {
+                DropPriceSource::Broadcast => "broadcast".to_string(),
+                DropPriceSource::GeHigh => "ge_high".to_string(),
+                DropPriceSource::GeLow => "ge_low".to_string(),
+                DropPriceSource::HighAlch => "high_alch".to_string(),
+            }
+        }
+
+        pub fn from_slug(slug: &str) -> Option<DropPriceSource> {
+            DropPriceSource::iter()
+                .into_iter()
+                .find(|source| source.to_slug() == slug)
+        }
+
+        /// The value of the whole drop from this source. Falls back to the broadcast value when
+        /// the item has no price here, like untradeables on the GE
+        pub fn drop_value(
+            &self,
+            broadcast_value: Option<i64>,
+            item: Option<&GetItem>,
+            price: Option<&GeItemPrice>,
+            quantity: i64,
+        ) -> Option<i64> {
+            let each = match self {
+                DropPriceSource::Broadcast => None,
+                DropPriceSource::GeHigh => price.map(|price| price.high),
+                DropPriceSource::GeLow => price.map(|price| price.low),
+                DropPriceSource::HighAlch => item.and_then(|item| item.highalch),
+            };
+            match each {
+                Some(each) if each > 0 => Some(each * quantity.max(1)),
+                _ => broadcast_value,
+            }
+        }
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct LatestPrices {
+        data: HashMap<String, LatestPrice>,
+    }
+
+    //Items that have not traded in a while are missing a high or low
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct LatestPrice {
+        high: Option<i64>,
+        high_time: Option<i64>,
+        low: Option<i64>,
+        low_time: Option<i64>,
+    }
+
     #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct GetItem {
@@ -0,0 +1,47 @@
+use celery::prelude::*;
+
+use crate::{
+    database::clan_mates::ClanMates, jobs::job_helpers::get_mongodb,
+    osrs_broadcast_extractor::osrs_broadcast_extractor::CombatAchievementTierBroadcast,
+};
+
+#[celery::task]
+pub async fn record_combat_achievement_tier(
+    combat_achievement_tier: CombatAchievementTierBroadcast,
+    guild_id: u64,
+) -> TaskResult<i32> {
+    println!(
+        "Recording new combat achievement tier: {:?}",
+        combat_achievement_tier
+    );
+    let db = get_mongodb().await;
+
+    let clan_mate = db
+        .clan_mates
+        .find_or_create_clan_mate(guild_id, combat_achievement_tier.player)
+        .await;
+    match clan_mate {
+        Ok(clan_mate) => {
+            let result = db
+                .clan_mate_combat_achievements
+                .update_or_create_highest_tier(
+                    guild_id,
+                    clan_mate.id,
+                    combat_achievement_tier.tier,
+                )
+                .await;
+            if result.is_err() {
+                println!(
+                    "Failed to save combat achievement tier: {:?}",
+                    result.err()
+                );
+                return Ok(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
+        }
+    }
+    Ok(4)
+}
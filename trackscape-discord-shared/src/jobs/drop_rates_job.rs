@@ -0,0 +1,24 @@
+use crate::jobs::job_helpers::get_redis_connection;
+use crate::wiki_api::wiki_api::refresh_drop_rates;
+use celery::task::TaskResult;
+use log::{error, info};
+
+/// Gets the drop rates from the wiki again, broadcasts only ever read them from the cache
+#[celery::task]
+pub async fn refresh_wiki_drop_rates() -> TaskResult<()> {
+    let mut redis_connection = match get_redis_connection() {
+        Ok(redis_connection) => redis_connection,
+        Err(e) => {
+            error!(
+                "Failed to connect to redis to refresh the drop rates: {:?}",
+                e
+            );
+            return Ok(());
+        }
+    };
+    match refresh_drop_rates(&mut redis_connection).await {
+        Ok(drop_rates) => info!("Refreshed the drop rates of {} items", drop_rates.len()),
+        Err(e) => error!("Failed to refresh the drop rates: {:?}", e),
+    }
+    Ok(())
+}
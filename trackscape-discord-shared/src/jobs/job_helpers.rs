@@ -1,4 +1,5 @@
 use crate::database::{BotMongoDb, MongoDb};
+use crate::discord_api::DiscordApiClient;
 use redis::{Client, Connection, RedisResult};
 use serde::Serialize;
 use std::env;
@@ -10,6 +11,11 @@ pub async fn get_mongodb() -> BotMongoDb {
     BotMongoDb::new_db_instance(mongodb_url).await
 }
 
+pub fn get_discord_api_client() -> DiscordApiClient {
+    let token = env::var("DISCORD_TOKEN").expect("DISCORD_TOKEN not set!");
+    DiscordApiClient::new(token)
+}
+
 pub fn get_redis_connection() -> RedisResult<Connection> {
     let redis_url = env::var("REDIS_ADDR").expect("REDIS_ADDR not set!");
     redis::Client::open(redis_url)
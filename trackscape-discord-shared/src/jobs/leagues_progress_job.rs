@@ -0,0 +1,37 @@
+use celery::prelude::*;
+
+use crate::{
+    database::clan_mate_leagues_progress::LeaguesProgressUpdate, database::clan_mates::ClanMates,
+    jobs::job_helpers::get_mongodb,
+};
+
+#[celery::task]
+pub async fn record_leagues_progress(
+    leagues_progress: LeaguesProgressUpdate,
+    guild_id: u64,
+) -> TaskResult<i32> {
+    println!("Recording new leagues progress: {:?}", leagues_progress);
+    let db = get_mongodb().await;
+
+    let clan_mate = db
+        .clan_mates
+        .find_or_create_clan_mate(guild_id, leagues_progress.player.clone())
+        .await;
+    match clan_mate {
+        Ok(clan_mate) => {
+            let result = db
+                .clan_mate_leagues_progress
+                .update_progress(guild_id, clan_mate.id, leagues_progress)
+                .await;
+            if result.is_err() {
+                println!("Failed to save leagues progress: {:?}", result.err());
+                return Ok(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
+        }
+    }
+    Ok(4)
+}
@@ -6,18 +6,24 @@ use celery::Celery;
 use std::sync::Arc;
 
 pub mod add_job;
+pub mod combat_achievement_job;
+pub mod drop_rates_job;
 pub mod job_helpers;
+pub mod leagues_progress_job;
 pub mod name_change_job;
 pub mod new_pb_job;
 pub mod parse_rl_chat_command;
+pub mod pet_drop_job;
+pub mod rank_roles_job;
 pub mod remove_clanmate_job;
 mod runelite_commands;
+pub mod skill_milestone_job;
 pub mod update_create_clanmate_job;
 pub mod wom_guild_sync_job;
 pub mod wom_guild_sync_logic;
 
 #[async_trait]
-pub trait JobQueue {
+pub trait JobQueue: Send + Sync {
     async fn send_task<T: Task>(&self, task_sig: Signature<T>) -> Result<AsyncResult, CeleryError>;
 }
 
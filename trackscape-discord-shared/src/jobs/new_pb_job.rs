@@ -1,44 +1,149 @@
+use anyhow::anyhow;
 use celery::prelude::*;
+use std::sync::Arc;
 
 use crate::{
-    database::clan_mates::ClanMates, jobs::job_helpers::get_mongodb,
-    osrs_broadcast_extractor::osrs_broadcast_extractor::PersonalBestBroadcast,
+    broadcast_processors::achievements::clan_record_broadcast,
+    database::{clan_mates::ClanMates, pb_records_db::ClanRecord, BotMongoDb},
+    embed_templates::render_guild_broadcast_embed,
+    jobs::{
+        get_celery_caller,
+        job_helpers::{get_discord_api_client, get_mongodb, get_redis_connection},
+        CeleryJobQueue,
+    },
+    osrs_broadcast_extractor::osrs_broadcast_extractor::{
+        BroadcastType, ClanMessage, PersonalBestBroadcast,
+    },
+    osrs_broadcast_handler::OSRSBroadcastHandler,
+    role_pings::role_ping_off_cooldown,
 };
 
 #[celery::task]
-pub async fn record_new_pb(pb: PersonalBestBroadcast, guild_id: u64) -> TaskResult<i32> {
+pub async fn record_new_pb(
+    pb: PersonalBestBroadcast,
+    clan_message: ClanMessage,
+    guild_id: u64,
+) -> TaskResult<i32> {
     println!("Recording new PB: {:?}", pb);
     let db = get_mongodb().await;
 
-    let activity_name = match pb.variant {
-        Some(variant) => variant,
-        None => pb.activity,
-    };
-    let clan_mate = db
+    let activity_name = pb.variant.clone().unwrap_or(pb.activity.clone());
+    let clan_mate = match db
         .clan_mates
-        .find_or_create_clan_mate(guild_id, pb.player)
-        .await;
-    if clan_mate.is_err() {
-        println!("Failed to find or create clan mate: {:?}", clan_mate.err());
-        return Ok(1);
-    }
-    let activity = db.pb_activities.create_or_get_activity(activity_name).await;
-    match activity {
-        Ok(activity) => {
-            let _ = db
-                .pb_records
-                .create_or_update_pb_record(
-                    clan_mate.unwrap().id,
-                    activity.id,
-                    guild_id,
-                    pb.time_in_seconds,
-                )
-                .await;
+        .find_or_create_clan_mate(guild_id, pb.player.clone())
+        .await
+    {
+        Ok(clan_mate) => clan_mate,
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
         }
+    };
+    let change = match db
+        .pb_records
+        .record_personal_best(
+            guild_id,
+            clan_mate.id,
+            activity_name,
+            pb.time_in_seconds,
+            Some(clan_message.message.clone()),
+        )
+        .await
+    {
+        Ok(change) => change,
         Err(e) => {
-            println!("Failed to get activity: {:?}", e);
+            println!("Failed to save personal best: {:?}", e);
             return Ok(1);
         }
     };
+
+    //Only the write knows if the guilds record was beaten, so the announcement is sent from here
+    if let Some(clan_record) = change.beaten_clan_record {
+        let discord_user_id = match clan_mate.discord_mentions {
+            Some(false) => None,
+            _ => clan_mate.discord_user_id,
+        };
+        if let Err(e) = announce_clan_record(
+            &db,
+            &pb,
+            clan_message,
+            guild_id,
+            &clan_record,
+            discord_user_id,
+        )
+        .await
+        {
+            println!("Failed to announce clan record: {:?}", e);
+            return Ok(1);
+        }
+    }
     Ok(4)
 }
+
+async fn announce_clan_record(
+    db: &BotMongoDb,
+    pb: &PersonalBestBroadcast,
+    clan_message: ClanMessage,
+    guild_id: u64,
+    clan_record: &ClanRecord,
+    discord_user_id: Option<u64>,
+) -> Result<(), anyhow::Error> {
+    let registered_guild = db
+        .guilds
+        .get_by_guild_id(guild_id)
+        .await?
+        .ok_or(anyhow!("Guild {} is not registered", guild_id))?;
+    let league_world = clan_message.is_league_world.unwrap_or(false);
+    let handler = OSRSBroadcastHandler::new(
+        clan_message,
+        Err(anyhow!("Not needed for clan records")),
+        Err(anyhow!("Not needed for clan records")),
+        Err(anyhow!("Not needed for clan records")),
+        Err(anyhow!("Not needed for clan records")),
+        Err(anyhow!("Not needed for clan records")),
+        registered_guild.clone(),
+        league_world,
+        db.drop_logs.clone(),
+        db.clan_mate_collection_log_totals.clone(),
+        db.clan_mates.clone(),
+        db.pb_records.clone(),
+        Arc::new(CeleryJobQueue {
+            celery: get_celery_caller().await,
+        }),
+    );
+    //The guilds rules can still turn the broadcast off
+    let Some(broadcast) = clan_record_broadcast(&handler, pb, clan_record).await else {
+        return Ok(());
+    };
+    db.broadcasts
+        .create_broadcast(guild_id, broadcast.clone())
+        .await?;
+
+    //A rule's channel wins, then the broadcast type's channel, then the default one
+    let Some(channel_id) = broadcast
+        .route_to_channel
+        .or(registered_guild.channel_for_broadcast(&BroadcastType::ClanRecord, league_world))
+    else {
+        return Ok(());
+    };
+    let role_ids = match get_redis_connection() {
+        Ok(mut redis_connection) => {
+            role_ping_off_cooldown(&mut redis_connection, &registered_guild, &broadcast)
+                .await
+                .into_iter()
+                .collect()
+        }
+        Err(e) => {
+            println!("Failed to connect to redis for the role ping: {:?}", e);
+            Vec::new()
+        }
+    };
+    get_discord_api_client()
+        .send_embed(
+            channel_id,
+            &render_guild_broadcast_embed(&registered_guild, &broadcast),
+            discord_user_id.into_iter().collect(),
+            role_ids,
+        )
+        .await
+}
@@ -1,3 +1,4 @@
+use super::runelite_commands::link_command::confirm_link;
 use super::runelite_commands::pb_command::get_pb;
 use celery::{error::TaskError, task::TaskResult};
 
@@ -11,6 +12,14 @@ pub async fn parse_command(message: String, player: String, guild_id: u64) -> Ta
                 return Err(TaskError::ExpectedError(e.to_string()));
             }
         };
+    } else if message.to_lowercase().starts_with("!link") {
+        match confirm_link(message, player, guild_id).await {
+            Ok(_) => {}
+            Err(e) => {
+                println!("Error linking discord user: {:?}", e);
+                return Err(TaskError::ExpectedError(e.to_string()));
+            }
+        };
     }
     Ok(0)
 }
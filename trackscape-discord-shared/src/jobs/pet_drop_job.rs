@@ -0,0 +1,34 @@
+use celery::prelude::*;
+
+use crate::{
+    database::clan_mates::ClanMates, jobs::job_helpers::get_mongodb,
+    osrs_broadcast_extractor::osrs_broadcast_extractor::PetDropBroadcast,
+};
+
+#[celery::task]
+pub async fn record_pet_drop(pet_drop: PetDropBroadcast, guild_id: u64) -> TaskResult<i32> {
+    println!("Recording new pet drop: {:?}", pet_drop);
+    let db = get_mongodb().await;
+
+    let clan_mate = db
+        .clan_mates
+        .find_or_create_clan_mate(guild_id, pet_drop.player_it_happened_to.clone())
+        .await;
+    match clan_mate {
+        Ok(clan_mate) => {
+            let result = db
+                .clan_mate_pets
+                .new_pet(guild_id, clan_mate.id, pet_drop)
+                .await;
+            if result.is_err() {
+                println!("Failed to save pet drop: {:?}", result.err());
+                return Ok(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
+        }
+    }
+    Ok(4)
+}
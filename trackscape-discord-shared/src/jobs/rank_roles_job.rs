@@ -0,0 +1,36 @@
+use crate::jobs::job_helpers::{get_discord_api_client, get_mongodb};
+use crate::rank_roles::reconcile_guild_rank_roles;
+use celery::task::TaskResult;
+use log::{error, info};
+
+/// Fixes discord roles that drifted from the clan ranks, like changes made while the sync was down
+#[celery::task]
+pub async fn reconcile_rank_roles() -> TaskResult<()> {
+    let mongodb = get_mongodb().await;
+    let discord_api_client = get_discord_api_client();
+
+    let guilds = mongodb
+        .guilds
+        .list_clans()
+        .await
+        .expect("Failed to get all guilds");
+
+    for guild in guilds {
+        if !guild.rank_role_sync_enabled.unwrap_or(false) {
+            continue;
+        }
+        match reconcile_guild_rank_roles(&guild, &mongodb, &discord_api_client, false).await {
+            Ok(role_changes) => info!(
+                "Updated the roles of {} members for guild: {:?}",
+                role_changes.len(),
+                guild.clan_name
+            ),
+            Err(e) => error!(
+                "Failed to reconcile rank roles for guild {:?}: {:?}",
+                guild.clan_name, e
+            ),
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,65 @@
+use crate::database::clan_mates::{ClanMates, PendingDiscordLink};
+use crate::jobs::job_helpers::get_mongodb;
+use anyhow::anyhow;
+use mongodb::bson::DateTime;
+
+//After this many wrong codes the link is cancelled so the code can not be guessed
+const MAX_WRONG_CODES: u32 = 3;
+
+/// Finishes a /link once the code shows up in clan chat from the account being linked
+pub async fn confirm_link(
+    message: String,
+    player: String,
+    guild_id: u64,
+) -> Result<(), anyhow::Error> {
+    let code = message
+        .split_whitespace()
+        .nth(1)
+        .ok_or(anyhow!("No link code in message: {}", message))?
+        .to_string();
+
+    let db = get_mongodb().await;
+    let mut clan_mate = db
+        .clan_mates
+        .find_by_current_name(guild_id, player.clone())
+        .await?
+        .ok_or(anyhow!("Could not find {} in this clan", player))?;
+
+    let pending_link = clan_mate
+        .pending_discord_link
+        .clone()
+        .ok_or(anyhow!("{} does not have a link waiting", player))?;
+    if pending_link.expires_at < DateTime::now() {
+        clan_mate.pending_discord_link = None;
+        db.clan_mates.update_clan_mate(clan_mate).await?;
+        return Err(anyhow!("The link code for {} has expired", player));
+    }
+    if pending_link.code != code {
+        let wrong_codes = pending_link.wrong_codes + 1;
+        if wrong_codes >= MAX_WRONG_CODES {
+            clan_mate.pending_discord_link = None;
+            db.clan_mates.update_clan_mate(clan_mate).await?;
+            return Err(anyhow!(
+                "Too many wrong link codes from {}, the link was cancelled",
+                player
+            ));
+        }
+        clan_mate.pending_discord_link = Some(PendingDiscordLink {
+            wrong_codes,
+            ..pending_link
+        });
+        db.clan_mates.update_clan_mate(clan_mate).await?;
+        return Err(anyhow!("Wrong link code from {}", player));
+    }
+
+    clan_mate.discord_user_id = Some(pending_link.discord_user_id);
+    //Mentions start on for whoever linked the account last
+    clan_mate.discord_mentions = None;
+    clan_mate.pending_discord_link = None;
+    db.clan_mates.update_clan_mate(clan_mate).await?;
+    println!(
+        "Linked {} to discord user {}",
+        player, pending_link.discord_user_id
+    );
+    Ok(())
+}
@@ -1,6 +1,7 @@
 use super::job_helpers::get_redis_connection;
 use redis::{Commands, RedisResult};
 
+pub mod link_command;
 pub mod pb_command;
 
 const RUNELITE_BASE_URL: &str = "https://api.runelite.net/runelite-";
@@ -57,7 +57,7 @@ async fn log_pb(
         .await;
     let _ = db
         .pb_records
-        .create_or_update_pb_record(clan_mate.unwrap().id, activity.id, guild_id, pb_time)
+        .create_or_update_pb_record(clan_mate.unwrap().id, activity.id, guild_id, pb_time, None)
         .await?;
     Ok(())
 }
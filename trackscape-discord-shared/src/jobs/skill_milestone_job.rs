@@ -0,0 +1,79 @@
+use celery::prelude::*;
+
+use crate::{
+    database::clan_mates::ClanMates,
+    jobs::job_helpers::get_mongodb,
+    osrs_broadcast_extractor::osrs_broadcast_extractor::{
+        LevelMilestoneBroadcast, XPMilestoneBroadcast,
+    },
+};
+
+#[celery::task]
+pub async fn record_level_milestone(
+    level_milestone: LevelMilestoneBroadcast,
+    guild_id: u64,
+) -> TaskResult<i32> {
+    println!("Recording new level milestone: {:?}", level_milestone);
+    if level_milestone.new_skill_level.is_none() {
+        println!("Could not read the level, not saving it");
+        return Ok(1);
+    }
+    let db = get_mongodb().await;
+
+    let clan_mate = db
+        .clan_mates
+        .find_or_create_clan_mate(guild_id, level_milestone.clan_mate.clone())
+        .await;
+    match clan_mate {
+        Ok(clan_mate) => {
+            let result = db
+                .clan_mate_skills
+                .record_level(guild_id, clan_mate.id, &level_milestone)
+                .await;
+            if result.is_err() {
+                println!("Failed to save level milestone: {:?}", result.err());
+                return Ok(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
+        }
+    }
+    Ok(4)
+}
+
+#[celery::task]
+pub async fn record_xp_milestone(
+    xp_milestone: XPMilestoneBroadcast,
+    guild_id: u64,
+) -> TaskResult<i32> {
+    println!("Recording new XP milestone: {:?}", xp_milestone);
+    let Some(new_skill_xp) = xp_milestone.new_skill_xp else {
+        println!("Could not read the XP, not saving it");
+        return Ok(1);
+    };
+    let db = get_mongodb().await;
+
+    let clan_mate = db
+        .clan_mates
+        .find_or_create_clan_mate(guild_id, xp_milestone.clan_mate.clone())
+        .await;
+    match clan_mate {
+        Ok(clan_mate) => {
+            let result = db
+                .clan_mate_skills
+                .record_xp(guild_id, clan_mate.id, &xp_milestone.skill, new_skill_xp)
+                .await;
+            if result.is_err() {
+                println!("Failed to save XP milestone: {:?}", result.err());
+                return Ok(1);
+            }
+        }
+        Err(e) => {
+            println!("Failed to find or create clan mate: {:?}", e);
+            return Ok(1);
+        }
+    }
+    Ok(4)
+}
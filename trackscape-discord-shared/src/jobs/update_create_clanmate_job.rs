@@ -1,19 +1,25 @@
 use crate::database::clan_mates::{ClanMateModel, ClanMates};
-use crate::jobs::job_helpers::{get_mongodb, get_redis_connection, write_to_cache};
+use crate::database::BotMongoDb;
+use crate::jobs::job_helpers::{
+    get_discord_api_client, get_mongodb, get_redis_connection, write_to_cache,
+};
+use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::AccountType;
+use crate::rank_roles::sync_linked_member_rank_roles;
 use crate::wom::{get_latest_name_change, get_wom_client};
 use celery::prelude::*;
 use redis::{Commands, RedisResult};
 
 ///
-/// Adds clan mates to the guild if they're not there already, and updates their rank if it's changed.
+/// Adds clan mates to the guild if they're not there already, and updates their rank and account type if they've changed.
 #[celery::task]
 pub async fn update_create_clanmate(
     player_name: String,
     rank: String,
     guild_id: u64,
+    account_type: Option<AccountType>,
 ) -> TaskResult<i32> {
     let mut redis_connection = get_redis_connection().expect("Failed to get redis client.");
-    let redis_key = format!("players:{}", player_name.clone());
+    let redis_key = format!("players:{}:{}", guild_id, player_name.clone());
     let exists: RedisResult<bool> = redis_connection.exists(redis_key.clone());
 
     let does_key_exist = match exists {
@@ -34,20 +40,17 @@ pub async fn update_create_clanmate(
                     let mut serialized_player: ClanMateModel =
                         serde_json::from_str(&cached_player).unwrap();
 
-                    if serialized_player.rank.is_none() {
-                        serialized_player.rank = Some(rank.clone());
-                    }
-                    if serialized_player.rank.clone().unwrap() == rank {
-                        //No need to update rank is the same
+                    if !update_chat_details(&mut serialized_player, &rank, &account_type) {
+                        //No need to update rank and account type are the same
                         return Ok(0);
                     }
 
-                    serialized_player.rank = Some(rank.clone());
                     mongodb
                         .clan_mates
                         .update_clan_mate(serialized_player.clone())
                         .await
                         .unwrap();
+                    sync_rank_roles(&mongodb, guild_id, serialized_player.discord_user_id).await;
                     write_to_cache(&mut redis_connection, redis_key, serialized_player).await;
                 }
                 Err(err) => {
@@ -59,7 +62,7 @@ pub async fn update_create_clanmate(
             //Checks to see if they had a name change
             let possible_saved_player = mongodb
                 .clan_mates
-                .find_by_current_name(player_name.clone())
+                .find_by_current_name(guild_id, player_name.clone())
                 .await;
 
             match possible_saved_player {
@@ -92,7 +95,10 @@ pub async fn update_create_clanmate(
                                     Some(name_change) => {
                                         let check_by_old_name = mongodb
                                             .clan_mates
-                                            .find_by_current_name(name_change.old_name.clone())
+                                            .find_by_current_name(
+                                                guild_id,
+                                                name_change.old_name.clone(),
+                                            )
                                             .await;
                                         if check_by_old_name.is_err() {
                                             if check_by_old_name.unwrap().is_none() {
@@ -131,26 +137,19 @@ pub async fn update_create_clanmate(
                             }
                         }
                         Some(mut player) => {
-                            if player.rank.is_none() {
-                                player.rank = Some(rank.clone());
-
-                                mongodb
-                                    .clan_mates
-                                    .update_clan_mate(player.clone())
-                                    .await
-                                    .unwrap();
-
-                                write_to_cache(&mut redis_connection, redis_key, player.clone())
-                                    .await;
-                            }
-
-                            if player.rank.clone().unwrap() == rank {
-                                //No need to update rank is the same
+                            if !update_chat_details(&mut player, &rank, &account_type) {
+                                //No need to update rank and account type are the same
                                 return Ok(0);
                             }
 
-                            player.rank = Some(rank.clone());
-                            mongodb.clan_mates.update_clan_mate(player).await.unwrap();
+                            mongodb
+                                .clan_mates
+                                .update_clan_mate(player.clone())
+                                .await
+                                .unwrap();
+                            sync_rank_roles(&mongodb, guild_id, player.discord_user_id).await;
+
+                            write_to_cache(&mut redis_connection, redis_key, player).await;
                         }
                     }
                 }
@@ -164,3 +163,44 @@ pub async fn update_create_clanmate(
     println!("update create clan mate job finished");
     Ok(4)
 }
+
+/// Rank changes are seen here first, so linked clan mates get their discord roles updated right away
+async fn sync_rank_roles(mongodb: &BotMongoDb, guild_id: u64, discord_user_id: Option<u64>) {
+    let discord_user_id = match discord_user_id {
+        Some(discord_user_id) => discord_user_id,
+        None => return,
+    };
+    let registered_guild = match mongodb.guilds.get_by_guild_id(guild_id).await {
+        Ok(Some(registered_guild)) => registered_guild,
+        _ => return,
+    };
+    if let Err(err) = sync_linked_member_rank_roles(
+        &registered_guild,
+        mongodb,
+        &get_discord_api_client(),
+        discord_user_id,
+    )
+    .await
+    {
+        println!("Failed to sync rank roles: {:?}", err);
+    }
+}
+
+/// Sets the rank and account type seen on the chat, returns true if either changed.
+/// Chats that hide the account type leave the saved one alone
+fn update_chat_details(
+    player: &mut ClanMateModel,
+    rank: &str,
+    account_type: &Option<AccountType>,
+) -> bool {
+    let mut changed = false;
+    if player.rank.as_deref() != Some(rank) {
+        player.rank = Some(rank.to_string());
+        changed = true;
+    }
+    if account_type.is_some() && player.account_type != *account_type {
+        player.account_type = account_type.clone();
+        changed = true;
+    }
+    changed
+}
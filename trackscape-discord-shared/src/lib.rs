@@ -1,12 +1,18 @@
 pub mod api_web_client;
+pub mod broadcast_processors;
+pub mod broadcast_rules;
 // pub mod database-old;
 pub mod database;
+pub mod discord_api;
 pub mod dto;
+pub mod embed_templates;
 pub mod ge_api;
 pub mod helpers;
 pub mod jobs;
 pub mod osrs_broadcast_extractor;
 pub mod osrs_broadcast_handler;
+pub mod rank_roles;
 pub mod redis_helpers;
+pub mod role_pings;
 pub mod wiki_api;
 pub mod wom;
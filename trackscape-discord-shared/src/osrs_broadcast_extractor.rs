@@ -1,27 +1,38 @@
 pub mod osrs_broadcast_extractor {
-    use regex::Regex;
+    use crate::broadcast_processors::{BroadcastTypeInfo, BROADCAST_TYPES};
+    use log::error;
     use once_cell::sync::Lazy;
+    use regex::{Captures, Regex, RegexSet};
     use serde::{Deserialize, Serialize};
 
     static RAID_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) received special loot from a raid: (?P<item>.*?)([.]|$)"#,).unwrap());
-    static DROP_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) received a drop: (?:((?P<quantity>[,\d]+) x )?)(?P<item>.*?)(?: \((?P<value>[,\d]+) coins\))?(?: from .*?)?[.]?$"#).unwrap());
+    static DROP_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) received a drop: (?:((?P<quantity>[,\d]+) x )?)(?P<item>.*?)(?: \((?P<value>[,\d]+) coins\))?(?: from (?P<source>.*?))?[.]?$"#).unwrap());
     static CLUE_ITEM_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) received a clue item: (?P<item>.*?)(?: \((?P<value>[,\d]+) coins\))?[.]?$"#).unwrap());
-    static PET_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) (?:has a funny feeling.*?|feels something weird sneaking into (?P<pronoun>her|his) backpack): (?P<pet_name>.*?) at (?P<count>[,\d]+) (?P<count_type>.*?)[.]$"#).unwrap());
+    static PET_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) (?:has a funny feeling like (?:he|she|they)(?:'s|'re| is| are)? (?:being followed|(?P<duplicate>would have been followed))|feels something weird sneaking into (?P<pronoun>her|his|their) backpack): (?P<pet_name>.*?) at (?P<count>[,\d]+) (?P<count_type>.*?)[.]$"#).unwrap());
     static QUEST_COMPLETED_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has completed a quest: (?P<quest_name>.+)$"#,).unwrap());
     static DIARY_COMPLETED_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has completed the (?P<diary_tier>Easy|Medium|Hard|Elite) (?P<diary_name>.*?).$"#).unwrap());
     static PK_BROADCAST_EXTRACTOR_WINNER: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<winner_name>.*?) has defeated (?P<loser_name>.*?) and received \((?P<gp_value>[0-9,]+) coins\) worth of loot!"#).unwrap());
     static PK_BROADCAST_EXTRACTOR_LOSER: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<loser_name>.*?) has been defeated by (?P<winner_name>.*?)(?: in (?P<location>The Wilderness))?(?: and lost \((?P<gp_value>[0-9,]+) coins\) worth of loot)?[!.]"#).unwrap());
     static INVITE_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<clan_joiner>.*?) has been invited into the clan by (?P<clan_inviter>.*?).$"#,).unwrap());
-    static LEVELMILESTONE_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<clan_mate>.*?) has reached (?:a )?(?:the highest possible )?(?P<skill>.*?) level(?: of)? (?P<level>.*?)[!.]"#).unwrap());
+    static LEVELMILESTONE_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<clan_mate>.*?) has reached (?:a )?(?P<maxed>the highest possible )?(?P<skill>.*?) level(?: of)? (?P<level>.*?)[!.]"#).unwrap());
     static XPMILESTONE_BROADCAST_EXTRACTOR: Lazy<Regex>  =  Lazy::new(|| Regex::new(r#"^(?P<clan_member>.*?) has reached (?P<xp>.*?) XP in (?P<skill>.*?)[!.]"#,).unwrap());
     static COLLECTION_LOG_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?P<name>[\w\s]+) received a new collection log item: (?P<item>.+?) \((?P<number>\d+)/\d+\)").unwrap());
     static LEFT_THE_CLAN_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?P<player>[\w\s]+) has left the clan.$").unwrap());
     static EXPELLED_FROM_CLAN_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(?P<mod>[\w\s]+) has expelled (?P<player>[\w\s]+) from the clan.$").unwrap());
-    static COFFER_DONATION_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?P<player>[\w\s]+) has (withdrawn|deposited) (?P<gp>[0-9,]+) coins (from|into) the coffer.").unwrap());
-    static COFFER_WITHDRAWAL_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?P<player>[\w\s]+) has (withdrawn|deposited) (?P<gp>[0-9,]+) coins (from|into) the coffer.").unwrap());
+    static COFFER_DONATION_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?P<player>[\w\s]+) has deposited (?P<gp>[0-9,]+) coins into the coffer.").unwrap());
+    static COFFER_WITHDRAWAL_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?P<player>[\w\s]+) has withdrawn (?P<gp>[0-9,]+) coins from the coffer.").unwrap());
     // RuneScape Player has achieved a new Vorkath personal best: 2:28
     static PERSONAL_BEST_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>[\w\s]+) has achieved a new (?P<activity>[\w\s\-'\.]+) personal best: (?<time>[\d:]+)"#,).unwrap());
     static PERSONAL_BEST_BROADCAST_EXTRACTOR_RAID: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>[\w\s]+) has achieved a new (?P<raid>[\w\s]+(?:\: [\w\s]+)?) \([Tt]eam [Ss]ize: (?P<team_size>[\w\s]+)\)(?:(?P<variant>[\w\s]+)?) personal best: (?<time>[\d:]+(?:\.\d{2})?)"#,).unwrap());
+    static HARDCORE_DEATH_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has died and lost (?:their|his|her) Hardcore Ironman status[!.]?$"#).unwrap());
+    static COMBAT_TASK_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has completed an? (?P<tier>(?i:easy|medium|hard|elite|master|grandmaster)) combat task: (?P<task_name>.*?)[.!]?$"#).unwrap());
+    static COMBAT_ACHIEVEMENT_TIER_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has unlocked the (?P<tier>(?i:easy|medium|hard|elite|master|grandmaster)) tier of rewards from Combat Achievements[.!]?$"#).unwrap());
+    static AREA_UNLOCK_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>.*?) has unlocked their (?P<areas_unlocked>\d+)(?:st|nd|rd|th) League area(?:: (?P<area>.+?))?[!.]$"#).unwrap());
+    static AREA_UNLOCK_BY_NAME_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>.*?) has unlocked the (?P<area>.+?) (?:League )?area[!.]$"#).unwrap());
+    static LEAGUES_RANK_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>.*?) has (?:reached|earned|unlocked) (?:the )?(?P<rank>Bronze|Iron|Steel|Mithril|Adamant|Rune|Dragon) League (?:rank|trophy)[!.]$"#).unwrap());
+    static COMBAT_MASTERIES_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>.*?) has earned their (?P<rank>\d+)(?:st|nd|rd|th) (?:(?P<mastery>Melee|Ranged|Magic) )?[Cc]ombat mastery point[!.]$"#).unwrap());
+    static RELIC_TIER_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player>.*?) has unlocked their tier (?P<tier>\d+) League relic(?:: (?P<relic>.+?))?[!.]$"#).unwrap());
+    static LOOT_KEY_BROADCAST_EXTRACTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"^(?P<player_name>.*?) has opened a loot key worth (?P<value>[,\d]+) coins[!.]?$"#).unwrap());
 
     #[derive(Deserialize, Serialize, Clone, Debug)]
     pub struct ClanMessage {
@@ -49,6 +60,88 @@ pub mod osrs_broadcast_extractor {
         pub is_league_world: Option<bool>,
     }
 
+    pub const IRONMAN_ICON_ID: i64 = 2;
+    pub const ULTIMATE_IRONMAN_ICON_ID: i64 = 3;
+    pub const HARDCORE_IRONMAN_ICON_ID: i64 = 10;
+
+    impl ClanMessage {
+        /// The account type of whoever sent the chat. No icon means a normal account,
+        /// icons like the moderator crown hide the account type so they give None
+        pub fn sender_account_type(&self) -> Option<AccountType> {
+            match self.icon_id {
+                None => Some(AccountType::Normal),
+                Some(icon_id) => AccountType::from_icon_id(icon_id),
+            }
+        }
+    }
+
+    #[derive(PartialEq, Deserialize, Serialize, Debug, Clone, Eq, Hash)]
+    pub enum AccountType {
+        Normal,
+        Ironman,
+        UltimateIronman,
+        HardcoreIronman,
+    }
+
+    impl AccountType {
+        pub fn from_icon_id(icon_id: i64) -> Option<AccountType> {
+            match icon_id {
+                IRONMAN_ICON_ID => Some(AccountType::Ironman),
+                ULTIMATE_IRONMAN_ICON_ID => Some(AccountType::UltimateIronman),
+                HARDCORE_IRONMAN_ICON_ID => Some(AccountType::HardcoreIronman),
+                _ => None,
+            }
+        }
+
+        pub fn from_string(account_type: String) -> AccountType {
+            match account_type.to_lowercase().replace("_", " ").as_str() {
+                "ironman" => AccountType::Ironman,
+                "ultimate ironman" => AccountType::UltimateIronman,
+                "hardcore ironman" => AccountType::HardcoreIronman,
+                _ => AccountType::Normal,
+            }
+        }
+
+        pub fn to_string(&self) -> String {
+            match self {
+                AccountType::Normal => "Normal".to_string(),
+                AccountType::Ironman => "Ironman".to_string(),
+                AccountType::UltimateIronman => "Ultimate Ironman".to_string(),
+                AccountType::HardcoreIronman => "Hardcore Ironman".to_string(),
+            }
+        }
+
+        pub fn to_slug(&self) -> String {
+            self.to_string().replace(" ", "_").to_lowercase()
+        }
+
+        pub fn iter() -> Vec<AccountType> {
+            vec![
+                AccountType::Normal,
+                AccountType::Ironman,
+                AccountType::UltimateIronman,
+                AccountType::HardcoreIronman,
+            ]
+        }
+
+        pub fn icon_url(&self) -> Option<String> {
+            match self {
+                AccountType::Normal => None,
+                AccountType::Ironman => Some(
+                    "https://oldschool.runescape.wiki/images/Ironman_chat_badge.png".to_string(),
+                ),
+                AccountType::UltimateIronman => Some(
+                    "https://oldschool.runescape.wiki/images/Ultimate_ironman_chat_badge.png"
+                        .to_string(),
+                ),
+                AccountType::HardcoreIronman => Some(
+                    "https://oldschool.runescape.wiki/images/Hardcore_ironman_chat_badge.png"
+                        .to_string(),
+                ),
+            }
+        }
+    }
+
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct DropItemBroadcast {
         pub player_it_happened_to: String,
@@ -56,8 +149,13 @@ pub mod osrs_broadcast_extractor {
         pub item_quantity: i64,
         pub item_value: Option<i64>,
         pub item_icon: Option<String>,
+        //Monster or activity from the end of the broadcast, only on some drops
+        pub drop_source: Option<String>,
+        //GE id resolved from the item mapping when the drop is handled
+        pub item_id: Option<i64>,
     }
 
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct PetDropBroadcast {
         pub player_it_happened_to: String,
         pub pet_name: String,
@@ -66,6 +164,43 @@ pub mod osrs_broadcast_extractor {
         pub actions_optioned_at: Option<i64>,
         //Could be kc, or task,  rift searches, permits, xp, etc
         pub action_for_pet: Option<String>,
+        pub count_type: PetCountType,
+        //"would have been followed" means they already have the pet
+        pub duplicate: bool,
+    }
+
+    #[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+    pub enum PetCountType {
+        KillCount,
+        Xp,
+        Harvests,
+        Permits,
+        RiftSearches,
+        Other(String),
+    }
+
+    impl PetCountType {
+        pub fn from_string(count_type: String) -> PetCountType {
+            match count_type.to_lowercase().as_str() {
+                "kills" | "kill count" | "kc" => PetCountType::KillCount,
+                "xp" => PetCountType::Xp,
+                "harvests" => PetCountType::Harvests,
+                "permits" => PetCountType::Permits,
+                "rift searches" => PetCountType::RiftSearches,
+                _ => PetCountType::Other(count_type),
+            }
+        }
+
+        pub fn to_string(&self) -> String {
+            match self {
+                PetCountType::KillCount => "KC".to_string(),
+                PetCountType::Xp => "XP".to_string(),
+                PetCountType::Harvests => "Harvests".to_string(),
+                PetCountType::Permits => "Permits".to_string(),
+                PetCountType::RiftSearches => "Rift Searches".to_string(),
+                PetCountType::Other(count_type) => count_type.clone(),
+            }
+        }
     }
 
     pub struct QuestCompletedBroadcast {
@@ -164,6 +299,78 @@ pub mod osrs_broadcast_extractor {
         }
     }
 
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub enum CombatAchievementTier {
+        Easy,
+        Medium,
+        Hard,
+        Elite,
+        Master,
+        Grandmaster,
+    }
+
+    impl CombatAchievementTier {
+        pub fn from_string(tier: String) -> CombatAchievementTier {
+            match tier.to_lowercase().as_str() {
+                "easy" => CombatAchievementTier::Easy,
+                "medium" => CombatAchievementTier::Medium,
+                "hard" => CombatAchievementTier::Hard,
+                "elite" => CombatAchievementTier::Elite,
+                "master" => CombatAchievementTier::Master,
+                "grandmaster" => CombatAchievementTier::Grandmaster,
+                _ => CombatAchievementTier::Easy,
+            }
+        }
+
+        pub fn to_string(&self) -> String {
+            match self {
+                CombatAchievementTier::Easy => "Easy".to_string(),
+                CombatAchievementTier::Medium => "Medium".to_string(),
+                CombatAchievementTier::Hard => "Hard".to_string(),
+                CombatAchievementTier::Elite => "Elite".to_string(),
+                CombatAchievementTier::Master => "Master".to_string(),
+                CombatAchievementTier::Grandmaster => "Grandmaster".to_string(),
+            }
+        }
+
+        pub fn iter() -> Vec<CombatAchievementTier> {
+            vec![
+                CombatAchievementTier::Easy,
+                CombatAchievementTier::Medium,
+                CombatAchievementTier::Hard,
+                CombatAchievementTier::Elite,
+                CombatAchievementTier::Master,
+                CombatAchievementTier::Grandmaster,
+            ]
+        }
+
+        pub fn ranking(&self) -> usize {
+            match self {
+                CombatAchievementTier::Easy => 1,
+                CombatAchievementTier::Medium => 2,
+                CombatAchievementTier::Hard => 3,
+                CombatAchievementTier::Elite => 4,
+                CombatAchievementTier::Master => 5,
+                CombatAchievementTier::Grandmaster => 6,
+            }
+        }
+    }
+
+    // RuneScape Player has completed a hard combat task: Whack-a-Mole.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CombatTaskBroadcast {
+        pub player: String,
+        pub task_name: String,
+        pub tier: CombatAchievementTier,
+    }
+
+    // RuneScape Player has unlocked the Elite tier of rewards from Combat Achievements!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CombatAchievementTierBroadcast {
+        pub player: String,
+        pub tier: CombatAchievementTier,
+    }
+
     pub struct DiaryCompletedBroadcast {
         pub player_it_happened_to: String,
         pub diary_name: String,
@@ -200,38 +407,68 @@ pub mod osrs_broadcast_extractor {
     // I Vision I has reached a total level of 2225.
     // Zillamanjaro has reached the highest possible combat level of 126!
     // Sad Bug has reached the highest possible total level of 2277!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct LevelMilestoneBroadcast {
         //name of clan mate that levelled up
         pub clan_mate: String,
         //name of skill that was levelled
         pub skill_levelled: String,
-        //new level of the skill that was levelled up
-        pub new_skill_level: String,
+        //new level of the skill that was levelled up, None when it could not be read
+        pub new_skill_level: Option<i64>,
+        //If it was a skill, the total level or the combat level
+        pub kind: LevelMilestoneKind,
+        //The highest possible total or combat level was reached
+        pub maxed: bool,
         //icon for skill levelled
         pub skill_icon: Option<String>,
     }
 
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub enum LevelMilestoneKind {
+        Skill,
+        Total,
+        Combat,
+    }
+
+    impl LevelMilestoneKind {
+        pub fn from_skill_levelled(skill_levelled: &str) -> Self {
+            match skill_levelled.to_lowercase().as_str() {
+                "total" => LevelMilestoneKind::Total,
+                "combat" => LevelMilestoneKind::Combat,
+                _ => LevelMilestoneKind::Skill,
+            }
+        }
+    }
+
     // Noble Five has reached 78,000,000 XP in Fishing.
     // Matrese has reached 15,000,000 XP in Fishing.
     // Marsel has reached 200,000,000 XP in Cooking.
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct XPMilestoneBroadcast {
         //name of clan mate that levelled up
         pub clan_mate: String,
         //name of skill that was levelled
         pub skill: String,
-        //new level of the skill that was levelled up
-        pub new_skill_xp: String,
+        //new xp of the skill that was levelled up, None when it could not be read
+        pub new_skill_xp: Option<i64>,
         //icon for skill
         pub skill_icon: Option<String>,
     }
 
     // KANlEL OUTIS has opened a loot key worth 1,148,040 coins!
     // Med-iocore has opened a loot key worth 489,181 coins!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct LootKey {
         pub player: String,
         pub value: i64,
     }
 
+    // Hardcore Iron has died and lost their Hardcore Ironman status!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct HardcoreDeathBroadcast {
+        pub player: String,
+    }
+
     pub struct ClueItemBroadcast {
         pub player_it_happened_to: String,
         pub item_name: String,
@@ -267,26 +504,42 @@ pub mod osrs_broadcast_extractor {
     }
 
     //Leagues broadcasts
-    //TODO may come back and add more descriptive broadcasts for Leagues, but right now just going the "firehose" route
-    // #[derive(Clone, Debug, Serialize, Deserialize)]
-    // pub struct AreaUnlockBroadcast {
-    //     pub area: String,
-    //     pub player: String
-    // }
-
-    // #[derive(Clone, Debug, Serialize, Deserialize)]
-    // pub struct LeaguesRankBroadcast {
-    //     pub area: String,
-    //     pub player: String
-    // }
-
-    // #[derive(Clone, Debug, Serialize, Deserialize)]
-    // pub struct CombatMasteriesBroadcast {
-    //     pub player: String,
-    //     pub combat_mastery: String,
-    //     pub rank: i64,
-    // }
-    
+    // RuneScape Player has unlocked their 3rd League area!
+    // RuneScape Player has unlocked their 3rd League area: Kandarin!
+    // RuneScape Player has unlocked the Kandarin area!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct AreaUnlockBroadcast {
+        pub player: String,
+        pub area: Option<String>,
+        //How many areas the player has unlocked, if the broadcast says
+        pub areas_unlocked: Option<i64>,
+    }
+
+    // RuneScape Player has reached the Adamant League rank!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct LeaguesRankBroadcast {
+        pub player: String,
+        pub rank: String,
+    }
+
+    // RuneScape Player has earned their 6th Combat mastery point!
+    // RuneScape Player has earned their 2nd Melee combat mastery point!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CombatMasteriesBroadcast {
+        pub player: String,
+        pub combat_mastery: Option<String>,
+        pub rank: i64,
+    }
+
+    // RuneScape Player has unlocked their tier 3 League relic!
+    // RuneScape Player has unlocked their tier 3 League relic: Fairy's Flight!
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct RelicTierBroadcast {
+        pub player: String,
+        pub tier: i64,
+        pub relic: Option<String>,
+    }
+
     #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
     pub enum LeaguesBroadCastType {
         AreaUnlock,
@@ -326,443 +579,835 @@ pub mod osrs_broadcast_extractor {
         ExpelledFromClan,
         CofferDonation,
         CofferWithdrawal,
-        PersonalBest,        
+        PersonalBest,
+        HardcoreDeath,
+        CombatTask,
+        CombatAchievementTier,
         //Leagues Broadcasts        
         AreaUnlock,
         LeaguesRank,
         CombatMasteries,
         RelicTier,
         Unknown,
-        ClueItem
+        ClueItem,
+        ClanRecord,
     }
 
     impl BroadcastType {
+        /// The registered processor for the type, it holds the types name and settings
+        fn info(&self) -> Option<&'static dyn BroadcastTypeInfo> {
+            BROADCAST_TYPES
+                .iter()
+                .copied()
+                .find(|info| info.broadcast_type() == *self)
+        }
+
         pub fn to_string(&self) -> String {
-            match self {
-                BroadcastType::ItemDrop => "Item Drop".to_string(),
-                BroadcastType::PetDrop => "Pet Drop".to_string(),
-                BroadcastType::Quest => "Quest".to_string(),
-                BroadcastType::Diary => "Diary".to_string(),
-                BroadcastType::RaidDrop => "Raid Drop".to_string(),
-                BroadcastType::Pk => "Pk".to_string(),
-                BroadcastType::Invite => "Invite".to_string(),
-                BroadcastType::LootKey => "Loot Key".to_string(),
-                BroadcastType::XPMilestone => "XP Milestone".to_string(),
-                BroadcastType::LevelMilestone => "Level Milestone".to_string(),
-                BroadcastType::CollectionLog => "Collection Log".to_string(),
-                BroadcastType::Unknown => "Unknown".to_string(),
-                BroadcastType::LeftTheClan => "Left The Clan".to_string(),
-                BroadcastType::ExpelledFromClan => "Expelled From Clan".to_string(),
-                BroadcastType::CofferDonation => "Coffer Donation".to_string(),
-                BroadcastType::CofferWithdrawal => "Coffer Withdrawal".to_string(),
-                BroadcastType::PersonalBest => "Personal Best".to_string(),                
-                //Leagues Broadcasts
-                BroadcastType::AreaUnlock => "Area Unlock".to_string(),
-                BroadcastType::LeaguesRank => "Leagues Rank".to_string(),
-                BroadcastType::CombatMasteries => "Combat Masteries".to_string(),
-                BroadcastType::RelicTier => "Relic Tier".to_string(),
-                BroadcastType::ClueItem => "Clue Item".to_string(),
-            }
+            self.info()
+                .map_or("Unknown", |info| info.name())
+                .to_string()
         }
 
         pub fn from_string(broadcast_type: String) -> BroadcastType {
-            match broadcast_type.as_str() {
-                "Item Drop" => BroadcastType::ItemDrop,
-                "Pet Drop" => BroadcastType::PetDrop,
-                "Quest" => BroadcastType::Quest,
-                "Diary" => BroadcastType::Diary,
-                "Raid Drop" => BroadcastType::RaidDrop,
-                "Pk" => BroadcastType::Pk,
-                "Invite" => BroadcastType::Invite,
-                "Loot Key" => BroadcastType::LootKey,
-                "XP Milestone" => BroadcastType::XPMilestone,
-                "Level Milestone" => BroadcastType::LevelMilestone,
-                "Collection Log" => BroadcastType::CollectionLog,
-                "Personal Best" => BroadcastType::PersonalBest,
-                //Leagues Broadcasts
-                "Area Unlock" => BroadcastType::AreaUnlock,
-                "Leagues Rank" => BroadcastType::LeaguesRank,
-                "Combat Masteries" => BroadcastType::CombatMasteries,
-                "Relic Tier" => BroadcastType::RelicTier,
-                "Clue Item" => BroadcastType::ClueItem,
-                _ => BroadcastType::Unknown,
-            }
+            BROADCAST_TYPES
+                .iter()
+                .find(|info| info.name() == broadcast_type)
+                .map_or(BroadcastType::Unknown, |info| info.broadcast_type())
         }
 
         pub fn iter() -> Vec<BroadcastType> {
-            vec![
-                BroadcastType::ItemDrop,
-                BroadcastType::PetDrop,
-                BroadcastType::Quest,
-                BroadcastType::Diary,
-                BroadcastType::RaidDrop,
-                BroadcastType::Pk,
-                BroadcastType::Invite,
-                BroadcastType::LootKey,
-                BroadcastType::XPMilestone,
-                BroadcastType::LevelMilestone,
-                BroadcastType::CollectionLog,
-                BroadcastType::Unknown,
-                BroadcastType::LeftTheClan,
-                BroadcastType::ExpelledFromClan,
-                BroadcastType::CofferDonation,
-                BroadcastType::CofferWithdrawal,
-                BroadcastType::PersonalBest,
-                //Leagues Broadcasts
-                BroadcastType::AreaUnlock,
-                BroadcastType::LeaguesRank,
-                BroadcastType::CombatMasteries,
-                BroadcastType::RelicTier,
-                BroadcastType::ClueItem,
-            ]
+            BROADCAST_TYPES
+                .iter()
+                .map(|info| info.broadcast_type())
+                .collect()
         }
 
         pub fn to_slug(&self) -> String {
+            self.to_string().replace(" ", "_")
+        }
+
+        /// Whether guilds can turn the broadcast type on and off
+        pub fn is_toggleable(&self) -> bool {
+            self.info().is_some_and(|info| info.toggleable())
+        }
+    }
+
+    /// A broadcast classified and extracted in one pass, so the type and the parsed fields always agree
+    pub enum ParsedBroadcast {
+        ItemDrop(DropItemBroadcast),
+        RaidDrop(DropItemBroadcast),
+        ClueItem(DropItemBroadcast),
+        PetDrop(PetDropBroadcast),
+        Quest(QuestCompletedBroadcast),
+        Diary(DiaryCompletedBroadcast),
+        Pk(PkBroadcast),
+        Invite(InviteBroadcast),
+        LootKey(LootKey),
+        XPMilestone(XPMilestoneBroadcast),
+        LevelMilestone(LevelMilestoneBroadcast),
+        CollectionLog(CollectionLogBroadcast),
+        LeftTheClan(String),
+        ExpelledFromClan(String),
+        CofferDonation(CofferTransactionBroadcast),
+        CofferWithdrawal(CofferTransactionBroadcast),
+        PersonalBest(PersonalBestBroadcast),
+        HardcoreDeath(HardcoreDeathBroadcast),
+        CombatTask(CombatTaskBroadcast),
+        CombatAchievementTier(CombatAchievementTierBroadcast),
+        Unknown,
+    }
+
+    impl ParsedBroadcast {
+        pub fn broadcast_type(&self) -> BroadcastType {
             match self {
-                _ => self.to_string().replace(" ", "_"),
+                ParsedBroadcast::ItemDrop(_) => BroadcastType::ItemDrop,
+                ParsedBroadcast::RaidDrop(_) => BroadcastType::RaidDrop,
+                ParsedBroadcast::ClueItem(_) => BroadcastType::ClueItem,
+                ParsedBroadcast::PetDrop(_) => BroadcastType::PetDrop,
+                ParsedBroadcast::Quest(_) => BroadcastType::Quest,
+                ParsedBroadcast::Diary(_) => BroadcastType::Diary,
+                ParsedBroadcast::Pk(_) => BroadcastType::Pk,
+                ParsedBroadcast::Invite(_) => BroadcastType::Invite,
+                ParsedBroadcast::LootKey(_) => BroadcastType::LootKey,
+                ParsedBroadcast::XPMilestone(_) => BroadcastType::XPMilestone,
+                ParsedBroadcast::LevelMilestone(_) => BroadcastType::LevelMilestone,
+                ParsedBroadcast::CollectionLog(_) => BroadcastType::CollectionLog,
+                ParsedBroadcast::LeftTheClan(_) => BroadcastType::LeftTheClan,
+                ParsedBroadcast::ExpelledFromClan(_) => BroadcastType::ExpelledFromClan,
+                ParsedBroadcast::CofferDonation(_) => BroadcastType::CofferDonation,
+                ParsedBroadcast::CofferWithdrawal(_) => BroadcastType::CofferWithdrawal,
+                ParsedBroadcast::PersonalBest(_) => BroadcastType::PersonalBest,
+                ParsedBroadcast::HardcoreDeath(_) => BroadcastType::HardcoreDeath,
+                ParsedBroadcast::CombatTask(_) => BroadcastType::CombatTask,
+                ParsedBroadcast::CombatAchievementTier(_) => BroadcastType::CombatAchievementTier,
+                ParsedBroadcast::Unknown => BroadcastType::Unknown,
+            }
+        }
+    }
+
+    /// A regex and how to read its captures into a broadcast
+    pub struct BroadcastPattern {
+        regex: &'static Lazy<Regex>,
+        parse: fn(&Captures) -> ParsedBroadcast,
+    }
+
+    pub(crate) static DROP_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &DROP_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::ItemDrop(drop_from_captures(caps)),
+    }];
+
+    pub(crate) static RAID_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &RAID_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::RaidDrop(raid_from_captures(caps)),
+    }];
+
+    pub(crate) static PET_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &PET_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::PetDrop(pet_from_captures(caps)),
+    }];
+
+    pub(crate) static CLUE_ITEM_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &CLUE_ITEM_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::ClueItem(clue_item_from_captures(caps)),
+    }];
+
+    pub(crate) static COMBAT_TASK_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &COMBAT_TASK_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::CombatTask(combat_task_from_captures(caps)),
+    }];
+
+    pub(crate) static COMBAT_ACHIEVEMENT_TIER_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &COMBAT_ACHIEVEMENT_TIER_BROADCAST_EXTRACTOR,
+        parse: |caps| {
+            ParsedBroadcast::CombatAchievementTier(combat_achievement_tier_from_captures(caps))
+        },
+    }];
+
+    pub(crate) static QUEST_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &QUEST_COMPLETED_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::Quest(quest_completed_from_captures(caps)),
+    }];
+
+    pub(crate) static DIARY_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &DIARY_COMPLETED_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::Diary(diary_completed_from_captures(caps)),
+    }];
+
+    pub(crate) static PK_PATTERNS: &[BroadcastPattern] = &[
+        BroadcastPattern {
+            regex: &PK_BROADCAST_EXTRACTOR_WINNER,
+            parse: |caps| ParsedBroadcast::Pk(pk_from_captures(caps, true)),
+        },
+        BroadcastPattern {
+            regex: &PK_BROADCAST_EXTRACTOR_LOSER,
+            parse: |caps| ParsedBroadcast::Pk(pk_from_captures(caps, false)),
+        },
+    ];
+
+    pub(crate) static INVITE_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &INVITE_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::Invite(invite_from_captures(caps)),
+    }];
+
+    pub(crate) static XPMILESTONE_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &XPMILESTONE_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::XPMilestone(xpmilestone_from_captures(caps)),
+    }];
+
+    pub(crate) static LEVELMILESTONE_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &LEVELMILESTONE_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::LevelMilestone(levelmilestone_from_captures(caps)),
+    }];
+
+    pub(crate) static COLLECTION_LOG_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &COLLECTION_LOG_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::CollectionLog(collection_log_from_captures(caps)),
+    }];
+
+    pub(crate) static LEFT_THE_CLAN_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &LEFT_THE_CLAN_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::LeftTheClan(caps["player"].to_string()),
+    }];
+
+    pub(crate) static EXPELLED_FROM_CLAN_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &EXPELLED_FROM_CLAN_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::ExpelledFromClan(caps["player"].to_string()),
+    }];
+
+    pub(crate) static COFFER_DONATION_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &COFFER_DONATION_BROADCAST_EXTRACTOR,
+        parse: |caps| {
+            ParsedBroadcast::CofferDonation(coffer_transaction_from_captures(
+                caps,
+                CofferTransaction::Donation,
+            ))
+        },
+    }];
+
+    pub(crate) static COFFER_WITHDRAWAL_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &COFFER_WITHDRAWAL_BROADCAST_EXTRACTOR,
+        parse: |caps| {
+            ParsedBroadcast::CofferWithdrawal(coffer_transaction_from_captures(
+                caps,
+                CofferTransaction::Withdrawal,
+            ))
+        },
+    }];
+
+    pub(crate) static PERSONAL_BEST_PATTERNS: &[BroadcastPattern] = &[
+        BroadcastPattern {
+            regex: &PERSONAL_BEST_BROADCAST_EXTRACTOR,
+            parse: |caps| ParsedBroadcast::PersonalBest(personal_best_from_captures(caps)),
+        },
+        BroadcastPattern {
+            regex: &PERSONAL_BEST_BROADCAST_EXTRACTOR_RAID,
+            parse: |caps| ParsedBroadcast::PersonalBest(raid_personal_best_from_captures(caps)),
+        },
+    ];
+
+    pub(crate) static LOOT_KEY_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &LOOT_KEY_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::LootKey(loot_key_from_captures(caps)),
+    }];
+
+    pub(crate) static HARDCORE_DEATH_PATTERNS: &[BroadcastPattern] = &[BroadcastPattern {
+        regex: &HARDCORE_DEATH_BROADCAST_EXTRACTOR,
+        parse: |caps| ParsedBroadcast::HardcoreDeath(hardcore_death_from_captures(caps)),
+    }];
+
+    // Every pattern with the type that registered it. A message is only parsed when exactly one pattern matches it
+    static BROADCAST_PATTERNS: Lazy<Vec<(BroadcastType, &'static BroadcastPattern)>> =
+        Lazy::new(|| {
+            BROADCAST_TYPES
+                .iter()
+                .flat_map(|info| {
+                    info.patterns()
+                        .iter()
+                        .map(move |pattern| (info.broadcast_type(), pattern))
+                })
+                .collect()
+        });
+
+    static BROADCAST_REGEX_SET: Lazy<RegexSet> = Lazy::new(|| {
+        RegexSet::new(
+            BROADCAST_PATTERNS
+                .iter()
+                .map(|(_, pattern)| pattern.regex.as_str()),
+        )
+        .unwrap()
+    });
+
+    /// The broadcast type of every pattern that matches the message, once per pattern
+    pub fn matching_broadcast_types(message: &str) -> Vec<BroadcastType> {
+        BROADCAST_REGEX_SET
+            .matches(message)
+            .iter()
+            .map(|index| BROADCAST_PATTERNS[index].0.clone())
+            .collect()
+    }
+
+    /// Parses the message with the one pattern that matches it. The patterns are written to not
+    /// overlap, a message matching more than one is left as Unknown instead of guessing by order
+    pub fn parse_broadcast(message: &str) -> ParsedBroadcast {
+        let matches: Vec<usize> = BROADCAST_REGEX_SET.matches(message).into_iter().collect();
+        match matches.as_slice() {
+            [index] => {
+                let (_, pattern) = BROADCAST_PATTERNS[*index];
+                match pattern.regex.captures(message) {
+                    Some(caps) => (pattern.parse)(&caps),
+                    None => ParsedBroadcast::Unknown,
+                }
+            }
+            [] => ParsedBroadcast::Unknown,
+            _ => {
+                error!(
+                    "Broadcast matches more than one pattern {:?}: {}",
+                    matching_broadcast_types(message),
+                    message
+                );
+                ParsedBroadcast::Unknown
             }
         }
     }
 
     pub fn raid_broadcast_extractor(message: String) -> Option<DropItemBroadcast> {
-        if let Some(caps) = RAID_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let item = caps.name("item").unwrap().as_str();
-
-            Some(DropItemBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                item_name: item.to_string(),
-                item_quantity: 1,
-                item_value: None,
-                item_icon: Some(get_wiki_image_url(item.to_string())),
-            })
-        } else {
-            None
+        RAID_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| raid_from_captures(&caps))
+    }
+
+    fn raid_from_captures(caps: &Captures) -> DropItemBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let item = caps.name("item").unwrap().as_str();
+
+        DropItemBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            item_name: item.to_string(),
+            item_quantity: 1,
+            item_value: None,
+            item_icon: Some(get_wiki_image_url(item.to_string())),
+            drop_source: None,
+            item_id: None,
         }
     }
 
     pub fn drop_broadcast_extractor(message: String) -> Option<DropItemBroadcast> {
-        if let Some(caps) = DROP_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let item_name = caps.name("item").unwrap().as_str();
-            // Extract and parse quantity
-            let quantity_str = caps.name("quantity").map_or("", |m| m.as_str());
-            let quantity: i64 = if !quantity_str.is_empty() {
-                quantity_str.replace(",", "").parse().unwrap_or(0)
-            } else {
-                1
-            };
-            let value_with_commas = caps.name("value").map_or("", |m| m.as_str());
-            let value: i64 = value_with_commas.replace(",", "").parse().unwrap_or(0);
-
-            Some(DropItemBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                item_name: item_name.to_string(),
-                item_quantity: quantity,
-                item_value: Some(value),
-                item_icon: Some(get_wiki_image_url(item_name.to_string())),
-            })
+        DROP_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| drop_from_captures(&caps))
+    }
+
+    fn drop_from_captures(caps: &Captures) -> DropItemBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let item_name = caps.name("item").unwrap().as_str();
+        // Extract and parse quantity
+        let quantity_str = caps.name("quantity").map_or("", |m| m.as_str());
+        let quantity: i64 = if !quantity_str.is_empty() {
+            quantity_str.replace(",", "").parse().unwrap_or(0)
         } else {
-            None
+            1
+        };
+        let value_with_commas = caps.name("value").map_or("", |m| m.as_str());
+        let value: i64 = value_with_commas.replace(",", "").parse().unwrap_or(0);
+
+        DropItemBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            item_name: item_name.to_string(),
+            item_quantity: quantity,
+            item_value: Some(value),
+            item_icon: Some(get_wiki_image_url(item_name.to_string())),
+            drop_source: caps.name("source").map(|m| m.as_str().to_string()),
+            item_id: None,
         }
     }
 
     pub fn pet_broadcast_extractor(message: String) -> Option<PetDropBroadcast> {
-        if let Some(caps) = PET_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let pet_name = caps.name("pet_name").unwrap().as_str();
-            let count = caps.name("count").unwrap().as_str().replace(",", "");
-            let count_type = caps.name("count_type").unwrap().as_str();
-            Some(PetDropBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                pet_name: pet_name.to_string(),
-                pet_icon: get_wiki_image_url(pet_name.to_string()).parse().ok(),
-                actions_optioned_at: count.parse().ok(),
-                action_for_pet: count_type.parse().ok(),
-            })
-        } else {
-            None
+        PET_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| pet_from_captures(&caps))
+    }
+
+    fn pet_from_captures(caps: &Captures) -> PetDropBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let pet_name = caps.name("pet_name").unwrap().as_str();
+        let count = caps.name("count").unwrap().as_str().replace(",", "");
+        let count_type = caps.name("count_type").unwrap().as_str();
+        PetDropBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            pet_name: pet_name.to_string(),
+            pet_icon: get_wiki_image_url(pet_name.to_string()).parse().ok(),
+            actions_optioned_at: count.parse().ok(),
+            action_for_pet: count_type.parse().ok(),
+            count_type: PetCountType::from_string(count_type.to_string()),
+            duplicate: caps.name("duplicate").is_some(),
         }
     }
 
     pub fn clue_item_broadcast_extractor(message: String) -> Option<DropItemBroadcast> {
-        if let Some(caps) = CLUE_ITEM_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let item = caps.name("item").unwrap().as_str();
-
-            let value_with_commas = caps.name("value").map_or("", |m| m.as_str());
-            let value: i64 = value_with_commas.replace(",", "").parse().unwrap_or(0);
-
-            Some(DropItemBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                item_name: item.to_string(),
-                item_quantity: 1,
-                item_value: Some(value),
-                item_icon: Some(get_wiki_image_url(item.to_string())),
-            })
-        } else {
-            None
+        CLUE_ITEM_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| clue_item_from_captures(&caps))
+    }
+
+    fn clue_item_from_captures(caps: &Captures) -> DropItemBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let item = caps.name("item").unwrap().as_str();
+
+        let value_with_commas = caps.name("value").map_or("", |m| m.as_str());
+        let value: i64 = value_with_commas.replace(",", "").parse().unwrap_or(0);
+
+        DropItemBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            item_name: item.to_string(),
+            item_quantity: 1,
+            item_value: Some(value),
+            item_icon: Some(get_wiki_image_url(item.to_string())),
+            drop_source: None,
+            item_id: None,
         }
     }
 
     pub fn quest_completed_broadcast_extractor(message: String) -> Option<QuestCompletedBroadcast> {
-        if let Some(caps) = QUEST_COMPLETED_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let quest_name = caps.name("quest_name").unwrap().as_str();
-
-            Some(QuestCompletedBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                quest_name: quest_name.to_string(),
-                quest_reward_scroll_icon: Some(get_quest_reward_scroll(quest_name.to_string())),
-            })
-        } else {
-            None
+        QUEST_COMPLETED_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| quest_completed_from_captures(&caps))
+    }
+
+    fn quest_completed_from_captures(caps: &Captures) -> QuestCompletedBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let quest_name = caps.name("quest_name").unwrap().as_str();
+
+        QuestCompletedBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            quest_name: quest_name.to_string(),
+            quest_reward_scroll_icon: Some(get_quest_reward_scroll(quest_name.to_string())),
         }
     }
 
     pub fn diary_completed_broadcast_extractor(message: String) -> Option<DiaryCompletedBroadcast> {
-        if let Some(caps) = DIARY_COMPLETED_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player_name = caps.name("player_name").unwrap().as_str();
-            let diary_name = caps.name("diary_name").unwrap().as_str();
-            let diary_tier = caps.name("diary_tier").unwrap().as_str();
-
-            Some(DiaryCompletedBroadcast {
-                player_it_happened_to: player_name.to_string(),
-                diary_name: diary_name.to_string(),
-                diary_tier: DiaryTier::from_string(diary_tier.to_string()),
-            })
-        } else {
-            None
+        DIARY_COMPLETED_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| diary_completed_from_captures(&caps))
+    }
+
+    fn diary_completed_from_captures(caps: &Captures) -> DiaryCompletedBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let diary_name = caps.name("diary_name").unwrap().as_str();
+        let diary_tier = caps.name("diary_tier").unwrap().as_str();
+
+        DiaryCompletedBroadcast {
+            player_it_happened_to: player_name.to_string(),
+            diary_name: diary_name.to_string(),
+            diary_tier: DiaryTier::from_string(diary_tier.to_string()),
         }
     }
 
     pub fn pk_broadcast_extractor(message: String) -> Option<PkBroadcast> {
-        let re = if message.contains("defeated by") {
-            &PK_BROADCAST_EXTRACTOR_LOSER
-        } else {
+        let clan_mate_won = !message.contains("defeated by");
+        let re = if clan_mate_won {
             &PK_BROADCAST_EXTRACTOR_WINNER
+        } else {
+            &PK_BROADCAST_EXTRACTOR_LOSER
         };
 
-        if let Some(caps) = re.captures(message.as_str()) {
-            let winner_name = caps.name("winner_name").unwrap().as_str();
-            let loser_name = caps.name("loser_name").unwrap().as_str();
-            let mut clan_mate_name = caps.name("winner_name").unwrap().as_str();
-            let mut clan_mate_winner = true;
-            if message.contains("defeated by") {
-                clan_mate_winner = false;
-                clan_mate_name = caps.name("loser_name").unwrap().as_str();
-            };
-            let gp_value_str = caps.name("gp_value").map_or("", |m| m.as_str());
-            let int_value: i64 = gp_value_str.replace(",", "").parse().unwrap_or(0);
-            let gp_value = if int_value == 0 {
-                None
-            } else {
-                Some(int_value)
-            };
-            Some(PkBroadcast {
-                winner: winner_name.to_string(),
-                loser: loser_name.to_string(),
-                clan_mate: clan_mate_name.to_string(),
-                gp_exchanged: gp_value,
-                clan_mate_won: clan_mate_winner,
-            })
+        re.captures(message.as_str())
+            .map(|caps| pk_from_captures(&caps, clan_mate_won))
+    }
+
+    fn pk_from_captures(caps: &Captures, clan_mate_won: bool) -> PkBroadcast {
+        let winner_name = caps.name("winner_name").unwrap().as_str();
+        let loser_name = caps.name("loser_name").unwrap().as_str();
+        let clan_mate_name = if clan_mate_won {
+            winner_name
         } else {
+            loser_name
+        };
+        let gp_value_str = caps.name("gp_value").map_or("", |m| m.as_str());
+        let int_value: i64 = gp_value_str.replace(",", "").parse().unwrap_or(0);
+        let gp_value = if int_value == 0 {
             None
+        } else {
+            Some(int_value)
+        };
+        PkBroadcast {
+            winner: winner_name.to_string(),
+            loser: loser_name.to_string(),
+            clan_mate: clan_mate_name.to_string(),
+            gp_exchanged: gp_value,
+            clan_mate_won,
         }
     }
 
     pub fn invite_broadcast_extractor(message: String) -> Option<InviteBroadcast> {
-        if let Some(caps) = INVITE_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let clan_mate = caps.name("clan_inviter").unwrap().as_str();
-            let new_clan_mate = caps.name("clan_joiner").unwrap().as_str();
-            Some(InviteBroadcast {
-                clan_mate: clan_mate.to_string(),
-                new_clan_mate: new_clan_mate.to_string(),
-            })
-        } else {
-            None
+        INVITE_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| invite_from_captures(&caps))
+    }
+
+    fn invite_from_captures(caps: &Captures) -> InviteBroadcast {
+        let clan_mate = caps.name("clan_inviter").unwrap().as_str();
+        let new_clan_mate = caps.name("clan_joiner").unwrap().as_str();
+        InviteBroadcast {
+            clan_mate: clan_mate.to_string(),
+            new_clan_mate: new_clan_mate.to_string(),
         }
     }
 
     pub fn levelmilestone_broadcast_extractor(message: String) -> Option<LevelMilestoneBroadcast> {
-        if let Some(caps) = LEVELMILESTONE_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let clan_mate = caps.name("clan_mate").unwrap().as_str();
-            let skill_levelled = caps.name("skill").unwrap().as_str();
-            let new_skill_level = caps.name("level").unwrap().as_str();
-            Some(LevelMilestoneBroadcast {
-                clan_mate: clan_mate.to_string(),
-                skill_levelled: skill_levelled.to_string(),
-                new_skill_level: new_skill_level.to_string(),
-                skill_icon: Some(get_skill_icon(skill_levelled.to_string())),
-            })
-        } else {
-            None
+        LEVELMILESTONE_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| levelmilestone_from_captures(&caps))
+    }
+
+    fn levelmilestone_from_captures(caps: &Captures) -> LevelMilestoneBroadcast {
+        let clan_mate = caps.name("clan_mate").unwrap().as_str();
+        let skill_levelled = caps.name("skill").unwrap().as_str();
+        let new_skill_level: Option<i64> = caps
+            .name("level")
+            .unwrap()
+            .as_str()
+            .replace(",", "")
+            .parse()
+            .ok();
+        let kind = LevelMilestoneKind::from_skill_levelled(skill_levelled);
+        let skill_icon = match kind {
+            LevelMilestoneKind::Skill => get_skill_icon(skill_levelled.to_string()),
+            LevelMilestoneKind::Total => {
+                "https://oldschool.runescape.wiki/images/Stats_icon.png".to_string()
+            }
+            LevelMilestoneKind::Combat => {
+                "https://oldschool.runescape.wiki/images/Combat_icon.png".to_string()
+            }
+        };
+        LevelMilestoneBroadcast {
+            clan_mate: clan_mate.to_string(),
+            skill_levelled: skill_levelled.to_string(),
+            new_skill_level,
+            kind,
+            maxed: caps.name("maxed").is_some(),
+            skill_icon: Some(skill_icon),
         }
     }
 
     pub fn xpmilestone_broadcast_extractor(message: String) -> Option<XPMilestoneBroadcast> {
-        if let Some(caps) = XPMILESTONE_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let clan_mate = caps.name("clan_member").unwrap().as_str();
-            let skill = caps.name("skill").unwrap().as_str();
-            let new_skill_xp = caps.name("xp").unwrap().as_str();
-            Some(XPMilestoneBroadcast {
-                clan_mate: clan_mate.to_string(),
-                skill: skill.to_string(),
-                new_skill_xp: new_skill_xp.to_string(),
-                skill_icon: Some(get_skill_icon(skill.to_string())),
-            })
-        } else {
-            None
+        XPMILESTONE_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| xpmilestone_from_captures(&caps))
+    }
+
+    fn xpmilestone_from_captures(caps: &Captures) -> XPMilestoneBroadcast {
+        let clan_mate = caps.name("clan_member").unwrap().as_str();
+        let skill = caps.name("skill").unwrap().as_str();
+        let new_skill_xp: Option<i64> = caps
+            .name("xp")
+            .unwrap()
+            .as_str()
+            .replace(",", "")
+            .parse()
+            .ok();
+        XPMilestoneBroadcast {
+            clan_mate: clan_mate.to_string(),
+            skill: skill.to_string(),
+            new_skill_xp,
+            skill_icon: Some(get_skill_icon(skill.to_string())),
         }
     }
 
     pub fn collection_log_broadcast_extractor(message: String) -> Option<CollectionLogBroadcast> {
-        if let Some(captures) = COLLECTION_LOG_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let name = captures.name("name").unwrap().as_str();
-            let item = captures.name("item").unwrap().as_str();
-            let number = captures.name("number").unwrap().as_str();
-
-            Some(CollectionLogBroadcast {
-                player_it_happened_to: name.to_string(),
-                item_name: item.to_string(),
-                log_slots: number.parse().unwrap(),
-                item_icon: Some(get_wiki_image_url(item.to_string())),
-            })
-        } else {
-            None
-        }
+        COLLECTION_LOG_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| collection_log_from_captures(&caps))
     }
 
-    pub fn left_the_clan_broadcast_extractor(message: String) -> Option<String> {
-        if let Some(captures) = LEFT_THE_CLAN_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let name = captures.name("player").unwrap().as_str();
+    fn collection_log_from_captures(captures: &Captures) -> CollectionLogBroadcast {
+        let name = captures.name("name").unwrap().as_str();
+        let item = captures.name("item").unwrap().as_str();
+        let number = captures.name("number").unwrap().as_str();
 
-            Some(name.to_string())
-        } else {
-            None
+        CollectionLogBroadcast {
+            player_it_happened_to: name.to_string(),
+            item_name: item.to_string(),
+            log_slots: number.parse().unwrap(),
+            item_icon: Some(get_wiki_image_url(item.to_string())),
         }
     }
 
-    pub fn expelled_from_clan_broadcast_extractor(message: String) -> Option<String> {
-        if let Some(captures) = EXPELLED_FROM_CLAN_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let name = captures.name("player").unwrap().as_str();
+    pub fn left_the_clan_broadcast_extractor(message: String) -> Option<String> {
+        LEFT_THE_CLAN_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|captures| captures["player"].to_string())
+    }
 
-            Some(name.to_string())
-        } else{
-            None
-        } 
+    pub fn expelled_from_clan_broadcast_extractor(message: String) -> Option<String> {
+        EXPELLED_FROM_CLAN_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|captures| captures["player"].to_string())
     }
 
     pub fn coffer_donation_broadcast_extractor(
         message: String,
     ) -> Option<CofferTransactionBroadcast> {
-        if let Some(captures) = COFFER_DONATION_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player = captures.name("player").unwrap().as_str();
-            let gp = captures
-                .name("gp")
-                .unwrap()
-                .as_str()
-                .replace(",", "")
-                .parse()
-                .unwrap();
-
-            Some(CofferTransactionBroadcast {
-                player: player.to_string(),
-                gp,
-                transaction_type: CofferTransaction::Donation,
+        COFFER_DONATION_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|captures| {
+                coffer_transaction_from_captures(&captures, CofferTransaction::Donation)
             })
-        } else {
-            None
-        }
     }
 
     pub fn coffer_withdrawal_broadcast_extractor(
         message: String,
     ) -> Option<CofferTransactionBroadcast> {
-        if let Some(captures) = COFFER_WITHDRAWAL_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player = captures.name("player").unwrap().as_str();
-            let gp = captures
-                .name("gp")
-                .unwrap()
-                .as_str()
-                .replace(",", "")
-                .parse()
-                .unwrap();
-
-            Some(CofferTransactionBroadcast {
-                player: player.to_string(),
-                gp,
-                transaction_type: CofferTransaction::Withdrawal,
+        COFFER_WITHDRAWAL_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|captures| {
+                coffer_transaction_from_captures(&captures, CofferTransaction::Withdrawal)
             })
-        } else {
-            None
+    }
+
+    fn coffer_transaction_from_captures(
+        captures: &Captures,
+        transaction_type: CofferTransaction,
+    ) -> CofferTransactionBroadcast {
+        let player = captures.name("player").unwrap().as_str();
+        let gp = captures
+            .name("gp")
+            .unwrap()
+            .as_str()
+            .replace(",", "")
+            .parse()
+            .unwrap();
+
+        CofferTransactionBroadcast {
+            player: player.to_string(),
+            gp,
+            transaction_type,
         }
     }
 
     pub fn personal_best_broadcast_extractor(message: String) -> Option<PersonalBestBroadcast> {
         if let Some(captures) = PERSONAL_BEST_BROADCAST_EXTRACTOR.captures(message.as_str()) {
-            let player = captures.name("player").unwrap().as_str();
-            let activity = captures.name("activity").unwrap().as_str();
-            let time = captures.name("time").unwrap().as_str();
-
-            return Some(PersonalBestBroadcast {
-                player: player.to_string(),
-                activity: activity.to_string(),            
-                time_in_seconds: osrs_time_parser(time),
-                //Will prob need to look at hallow sepulchre and other activities that have variants here
-                variant: None,
-            })
+            return Some(personal_best_from_captures(&captures));
         }
 
-        if let Some(captures) = PERSONAL_BEST_BROADCAST_EXTRACTOR_RAID.captures(message.as_str()) {
-            let player = captures.name("player").unwrap().as_str();
-            let raid = captures.name("raid").unwrap().as_str();            
-            let time = captures.name("time").unwrap().as_str();
-            let team_size = captures.name("team_size").map_or("", |m| m.as_str());
-            let variant = captures.name("variant").map_or("", |m| m.as_str());
-            
-            let full_raid: String;
-            if variant.is_empty() {
-                full_raid = raid.to_string();
-            } else {
-                full_raid = format!("{} {}", raid, variant.trim());
-            }
+        PERSONAL_BEST_BROADCAST_EXTRACTOR_RAID
+            .captures(message.as_str())
+            .map(|captures| raid_personal_best_from_captures(&captures))
+    }
 
-            let raid_name = match raid {
-                x if x.contains("Xeric") => "Chambers of Xeric",
-                x if x.contains("Blood") => "Theatre of Blood",
-                x if x.contains("Tombs") => "Tombs of Amascut",
-                _ => raid,
-            };
+    fn personal_best_from_captures(captures: &Captures) -> PersonalBestBroadcast {
+        let player = captures.name("player").unwrap().as_str();
+        let activity = captures.name("activity").unwrap().as_str();
+        let time = captures.name("time").unwrap().as_str();
 
-            Some(PersonalBestBroadcast {
-                player: player.to_string(),
-                activity: raid_name.to_string(),
-                time_in_seconds: osrs_time_parser(time),
-                variant: raid_name_standardize(full_raid, team_size)
-            })
+        PersonalBestBroadcast {
+            player: player.to_string(),
+            activity: activity.to_string(),
+            time_in_seconds: osrs_time_parser(time),
+            //Will prob need to look at hallow sepulchre and other activities that have variants here
+            variant: None,
+        }
+    }
+
+    fn raid_personal_best_from_captures(captures: &Captures) -> PersonalBestBroadcast {
+        let player = captures.name("player").unwrap().as_str();
+        let raid = captures.name("raid").unwrap().as_str();
+        let time = captures.name("time").unwrap().as_str();
+        let team_size = captures.name("team_size").map_or("", |m| m.as_str());
+        let variant = captures.name("variant").map_or("", |m| m.as_str());
+
+        let full_raid: String;
+        if variant.is_empty() {
+            full_raid = raid.to_string();
         } else {
-            None
+            full_raid = format!("{} {}", raid, variant.trim());
+        }
+
+        let raid_name = match raid {
+            x if x.contains("Xeric") => "Chambers of Xeric",
+            x if x.contains("Blood") => "Theatre of Blood",
+            x if x.contains("Tombs") => "Tombs of Amascut",
+            _ => raid,
+        };
+
+        PersonalBestBroadcast {
+            player: player.to_string(),
+            activity: raid_name.to_string(),
+            time_in_seconds: osrs_time_parser(time),
+            variant: raid_name_standardize(full_raid, team_size),
+        }
+    }
+
+    pub fn loot_key_broadcast_extractor(message: String) -> Option<LootKey> {
+        LOOT_KEY_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| loot_key_from_captures(&caps))
+    }
+
+    fn loot_key_from_captures(caps: &Captures) -> LootKey {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let value = caps
+            .name("value")
+            .unwrap()
+            .as_str()
+            .replace(",", "")
+            .parse::<i64>()
+            .unwrap_or(0);
+
+        LootKey {
+            player: player_name.to_string(),
+            value,
+        }
+    }
+
+    pub fn combat_task_broadcast_extractor(message: String) -> Option<CombatTaskBroadcast> {
+        COMBAT_TASK_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| combat_task_from_captures(&caps))
+    }
+
+    fn combat_task_from_captures(caps: &Captures) -> CombatTaskBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let tier = caps.name("tier").unwrap().as_str();
+        let task_name = caps.name("task_name").unwrap().as_str();
+
+        CombatTaskBroadcast {
+            player: player_name.to_string(),
+            task_name: task_name.to_string(),
+            tier: CombatAchievementTier::from_string(tier.to_string()),
+        }
+    }
+
+    pub fn combat_achievement_tier_broadcast_extractor(
+        message: String,
+    ) -> Option<CombatAchievementTierBroadcast> {
+        COMBAT_ACHIEVEMENT_TIER_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| combat_achievement_tier_from_captures(&caps))
+    }
+
+    fn combat_achievement_tier_from_captures(caps: &Captures) -> CombatAchievementTierBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+        let tier = caps.name("tier").unwrap().as_str();
+
+        CombatAchievementTierBroadcast {
+            player: player_name.to_string(),
+            tier: CombatAchievementTier::from_string(tier.to_string()),
+        }
+    }
+
+    pub fn hardcore_death_broadcast_extractor(message: String) -> Option<HardcoreDeathBroadcast> {
+        HARDCORE_DEATH_BROADCAST_EXTRACTOR
+            .captures(message.as_str())
+            .map(|caps| hardcore_death_from_captures(&caps))
+    }
+
+    fn hardcore_death_from_captures(caps: &Captures) -> HardcoreDeathBroadcast {
+        let player_name = caps.name("player_name").unwrap().as_str();
+
+        HardcoreDeathBroadcast {
+            player: player_name.to_string(),
         }
     }
 
     pub fn leagues_catch_all_broadcast_extractor(
         message: String,
     ) -> Option<LeaguesBroadCastType> {
-        if message.contains("has earned") && message.contains("Combat mastery") {
+        //The same patterns the leagues extractors parse with, so a message is only sorted into a
+        //type its extractor can read
+        if COMBAT_MASTERIES_BROADCAST_EXTRACTOR.is_match(message.as_str()) {
             return Some(LeaguesBroadCastType::CombatMasteries);
         }
 
-        if message.contains("has unlocked") && message.contains("League area") {
+        if AREA_UNLOCK_BROADCAST_EXTRACTOR.is_match(message.as_str())
+            || AREA_UNLOCK_BY_NAME_BROADCAST_EXTRACTOR.is_match(message.as_str())
+        {
             return Some(LeaguesBroadCastType::AreaUnlock);
         }
 
-        if message.contains("has unlocked") && message.contains("League relic!") {
+        if RELIC_TIER_BROADCAST_EXTRACTOR.is_match(message.as_str()) {
             return Some(LeaguesBroadCastType::RelicTier);
         }
+
+        if LEAGUES_RANK_BROADCAST_EXTRACTOR.is_match(message.as_str()) {
+            return Some(LeaguesBroadCastType::LeaguesRank);
+        }
         None
     }
 
+    pub fn area_unlock_broadcast_extractor(message: String) -> Option<AreaUnlockBroadcast> {
+        if let Some(caps) = AREA_UNLOCK_BROADCAST_EXTRACTOR.captures(message.as_str()) {
+            let player = caps.name("player").unwrap().as_str();
+            let areas_unlocked = caps.name("areas_unlocked").unwrap().as_str();
+
+            return Some(AreaUnlockBroadcast {
+                player: player.to_string(),
+                area: caps.name("area").map(|area| area.as_str().to_string()),
+                areas_unlocked: areas_unlocked.parse().ok(),
+            });
+        }
+
+        if let Some(caps) = AREA_UNLOCK_BY_NAME_BROADCAST_EXTRACTOR.captures(message.as_str()) {
+            let player = caps.name("player").unwrap().as_str();
+            let area = caps.name("area").unwrap().as_str();
+
+            Some(AreaUnlockBroadcast {
+                player: player.to_string(),
+                area: Some(area.to_string()),
+                areas_unlocked: None,
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn leagues_rank_broadcast_extractor(message: String) -> Option<LeaguesRankBroadcast> {
+        if let Some(caps) = LEAGUES_RANK_BROADCAST_EXTRACTOR.captures(message.as_str()) {
+            let player = caps.name("player").unwrap().as_str();
+            let rank = caps.name("rank").unwrap().as_str();
+
+            Some(LeaguesRankBroadcast {
+                player: player.to_string(),
+                rank: rank.to_string(),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn combat_masteries_broadcast_extractor(
+        message: String,
+    ) -> Option<CombatMasteriesBroadcast> {
+        if let Some(caps) = COMBAT_MASTERIES_BROADCAST_EXTRACTOR.captures(message.as_str()) {
+            let player = caps.name("player").unwrap().as_str();
+            let rank = caps.name("rank").unwrap().as_str();
+
+            Some(CombatMasteriesBroadcast {
+                player: player.to_string(),
+                combat_mastery: caps
+                    .name("mastery")
+                    .map(|mastery| mastery.as_str().to_string()),
+                rank: rank.parse().unwrap_or(0),
+            })
+        } else {
+            None
+        }
+    }
+
+    pub fn relic_tier_broadcast_extractor(message: String) -> Option<RelicTierBroadcast> {
+        if let Some(caps) = RELIC_TIER_BROADCAST_EXTRACTOR.captures(message.as_str()) {
+            let player = caps.name("player").unwrap().as_str();
+            let tier = caps.name("tier").unwrap().as_str();
+
+            Some(RelicTierBroadcast {
+                player: player.to_string(),
+                tier: tier.parse().unwrap_or(0),
+                relic: caps.name("relic").map(|relic| relic.as_str().to_string()),
+            })
+        } else {
+            None
+        }
+    }
+
     /// Parses a time string in the format of `HH:MM:SS` or `MM:SS` and returns the time in seconds
     pub fn osrs_time_parser(time: &str) -> f64 {
         let split_sub_second: Vec<&str> = time.split(".").collect();
@@ -834,60 +1479,36 @@ pub mod osrs_broadcast_extractor {
     }
 
     pub fn get_broadcast_type(message_content: String) -> BroadcastType {
-        if message_content.contains("received a drop:") {
-            return BroadcastType::ItemDrop;
-        }
-        if message_content.contains("received special loot from a raid:") {
-            return BroadcastType::RaidDrop;
-        }
-        if message_content.contains("has a funny feeling") && message_content.contains("followed:")
-            || message_content.contains("feels something weird sneaking into")
-                && message_content.contains("backpack:")
-        {
-            return BroadcastType::PetDrop;
-        }
-        if message_content.contains("received a clue item:") {
-            return BroadcastType::ClueItem;
-        }
-        if message_content.contains("has completed a quest:") {
-            return BroadcastType::Quest;
-        }
-        if message_content.contains("has completed the") && message_content.contains("diary") {
-            return BroadcastType::Diary;
-        }
-        if message_content.contains("has defeated") || message_content.contains("defeated by") {
-            return BroadcastType::Pk;
-        }
-        if message_content.contains("has been invited") {
-            return BroadcastType::Invite;
-        }
-        if message_content.contains("has reached") && message_content.contains("level") {
-            return BroadcastType::LevelMilestone;
-        }
-        if message_content.contains("has reached") && message_content.contains("XP in") {
-            return BroadcastType::XPMilestone;
-        }
-        if message_content.contains("received a new collection log item:") {
-            return BroadcastType::CollectionLog;
-        }
-        if message_content.contains("has left the clan.") {
-            return BroadcastType::LeftTheClan;
-        }
-        if message_content.contains("has expelled") && message_content.contains("from the clan.") {
-            return BroadcastType::ExpelledFromClan;
-        }
-        if message_content.contains("deposited") && message_content.contains("the coffer.") {
-            return BroadcastType::CofferDonation;
-        }
-        if message_content.contains("withdrawn") && message_content.contains("from the coffer.") {
-            return BroadcastType::CofferWithdrawal;
-        }
-        if message_content.contains("has achieved a new")
-            && message_content.contains("personal best:")
-        {
-            return BroadcastType::PersonalBest;
+        parse_broadcast(message_content.as_str()).broadcast_type()
+    }
+
+    pub fn get_combat_achievement_tier_icon(tier: &CombatAchievementTier) -> String {
+        format!(
+            "https://oldschool.runescape.wiki/images/Combat_Achievements_-_{}_tier_icon.png",
+            tier.to_string().to_lowercase()
+        )
+    }
+
+    pub fn get_leagues_area_icon(area: String) -> String {
+        let image_name = format_wiki_image_name(area);
+        format!(
+            "https://oldschool.runescape.wiki/images/{}_Area_Badge.png",
+            image_name
+        )
+    }
+
+    pub fn get_leagues_relic_icon(relic: Option<String>, tier: i64) -> String {
+        match relic {
+            Some(relic) => get_wiki_image_url(relic),
+            None => format!(
+                "https://oldschool.runescape.wiki/images/Relic_tier_{}_icon.png",
+                tier
+            ),
         }
-        return BroadcastType::Unknown;
+    }
+
+    pub fn get_loot_key_icon() -> String {
+        get_wiki_image_url("Loot key".to_string())
     }
 
     fn format_wiki_image_name(item_name: String) -> String {
@@ -937,12 +1558,17 @@ pub mod osrs_broadcast_extractor {
 mod tests {
     use super::*;
     use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
-        get_wiki_clan_rank_image_url, CofferTransaction, CofferTransactionBroadcast,
-        CollectionLogBroadcast, DiaryCompletedBroadcast, DiaryTier, InviteBroadcast,
-        LevelMilestoneBroadcast, PersonalBestBroadcast, PetDropBroadcast, PkBroadcast,
-        QuestCompletedBroadcast, XPMilestoneBroadcast,
+        get_wiki_clan_rank_image_url, BroadcastType, CofferTransaction, CofferTransactionBroadcast,
+        CollectionLogBroadcast, CombatAchievementTier, CombatAchievementTierBroadcast,
+        CombatTaskBroadcast, DiaryCompletedBroadcast, DiaryTier, HardcoreDeathBroadcast,
+        InviteBroadcast, LevelMilestoneBroadcast, LevelMilestoneKind, LootKey,
+        PersonalBestBroadcast, PetDropBroadcast, PkBroadcast, QuestCompletedBroadcast,
+        XPMilestoneBroadcast,
+    };
+    use osrs_broadcast_extractor::{
+        AreaUnlockBroadcast, CombatMasteriesBroadcast, LeaguesBroadCastType, PetCountType,
+        RelicTierBroadcast,
     };
-    use osrs_broadcast_extractor::LeaguesBroadCastType;
     use rstest::rstest;
     use tracing::info;
 
@@ -1033,79 +1659,324 @@ mod tests {
         let possible_invite_broadcasts = get_invite_messages();
         for possible_invite_broadcast in possible_invite_broadcasts {
             let broadcast_type =
-                osrs_broadcast_extractor::get_broadcast_type(possible_invite_broadcast.message);
+                osrs_broadcast_extractor::get_broadcast_type(possible_invite_broadcast.message);
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::Invite
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_levelmilestone_type_broadcast() {
+        let possible_levelmilestone_broadcasts = get_levelmilestone_messages();
+        for possible_levelmilestone_broadcast in possible_levelmilestone_broadcasts {
+            let broadcast_type = osrs_broadcast_extractor::get_broadcast_type(
+                possible_levelmilestone_broadcast.message,
+            );
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::LevelMilestone
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_xpmilestone_type_broadcast() {
+        let possible_xpmilestone_broadcasts = get_xpmilestone_messages();
+        for possible_xpmilestone_broadcast in possible_xpmilestone_broadcasts {
+            let broadcast_type = osrs_broadcast_extractor::get_broadcast_type(
+                possible_xpmilestone_broadcast.message,
+            );
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::XPMilestone
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_collection_log_type_broadcast() {
+        let test_collection_logs = get_collection_log_messages();
+        for test_collection_log in test_collection_logs {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_collection_log.message);
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::CollectionLog
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_left_the_clan_type_broadcast() {
+        let test_left_the_clan = get_has_left_the_clan_messages();
+        for test_left_the_clan in test_left_the_clan {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_left_the_clan.message);
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::LeftTheClan
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_expelled_from_clan_type_broadcast() {
+        let test_expelled_from_clan = get_expelled_from_clan_messages();
+        for test_expelled_from_clan in test_expelled_from_clan {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_expelled_from_clan.message);
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::ExpelledFromClan
+            ));
+        }
+    }
+
+    #[test]
+    fn test_get_loot_key_type_broadcast() {
+        let test_loot_keys = get_loot_key_messages();
+        for test_loot_key in test_loot_keys {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_loot_key.message);
+            assert!(matches!(
+                broadcast_type,
+                osrs_broadcast_extractor::BroadcastType::LootKey
+            ));
+        }
+    }
+
+    #[test]
+    fn test_account_type_from_icon_id() {
+        assert_eq!(
+            osrs_broadcast_extractor::AccountType::from_icon_id(2),
+            Some(osrs_broadcast_extractor::AccountType::Ironman)
+        );
+        assert_eq!(
+            osrs_broadcast_extractor::AccountType::from_icon_id(3),
+            Some(osrs_broadcast_extractor::AccountType::UltimateIronman)
+        );
+        assert_eq!(
+            osrs_broadcast_extractor::AccountType::from_icon_id(10),
+            Some(osrs_broadcast_extractor::AccountType::HardcoreIronman)
+        );
+        //Moderator and league icons hide the account type
+        assert_eq!(osrs_broadcast_extractor::AccountType::from_icon_id(0), None);
+        assert_eq!(
+            osrs_broadcast_extractor::AccountType::from_icon_id(22),
+            None
+        );
+
+        let clan_message = osrs_broadcast_extractor::ClanMessage {
+            sender: "RuneScape Player".to_string(),
+            message: "Hello".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+        assert_eq!(
+            clan_message.sender_account_type(),
+            Some(osrs_broadcast_extractor::AccountType::Normal)
+        );
+    }
+
+    #[test]
+    fn test_get_hardcore_death_type_broadcast() {
+        let test_hardcore_deaths = get_hardcore_death_messages();
+        for test_hardcore_death in test_hardcore_deaths {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_hardcore_death.message);
             assert!(matches!(
                 broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::Invite
+                osrs_broadcast_extractor::BroadcastType::HardcoreDeath
             ));
         }
     }
 
     #[test]
-    fn test_get_levelmilestone_type_broadcast() {
-        let possible_levelmilestone_broadcasts = get_levelmilestone_messages();
-        for possible_levelmilestone_broadcast in possible_levelmilestone_broadcasts {
-            let broadcast_type = osrs_broadcast_extractor::get_broadcast_type(
-                possible_levelmilestone_broadcast.message,
-            );
+    fn test_get_combat_task_type_broadcast() {
+        let test_combat_tasks = get_combat_task_messages();
+        for test_combat_task in test_combat_tasks {
+            let broadcast_type =
+                osrs_broadcast_extractor::get_broadcast_type(test_combat_task.message);
             assert!(matches!(
                 broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::LevelMilestone
+                osrs_broadcast_extractor::BroadcastType::CombatTask
             ));
         }
     }
 
     #[test]
-    fn test_get_xpmilestone_type_broadcast() {
-        let possible_xpmilestone_broadcasts = get_xpmilestone_messages();
-        for possible_xpmilestone_broadcast in possible_xpmilestone_broadcasts {
-            let broadcast_type = osrs_broadcast_extractor::get_broadcast_type(
-                possible_xpmilestone_broadcast.message,
-            );
+    fn test_get_combat_achievement_tier_type_broadcast() {
+        let test_tiers = get_combat_achievement_tier_messages();
+        for test_tier in test_tiers {
+            let broadcast_type = osrs_broadcast_extractor::get_broadcast_type(test_tier.message);
             assert!(matches!(
                 broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::XPMilestone
+                osrs_broadcast_extractor::BroadcastType::CombatAchievementTier
             ));
         }
     }
 
     #[test]
-    fn test_get_collection_log_type_broadcast() {
-        let test_collection_logs = get_collection_log_messages();
-        for test_collection_log in test_collection_logs {
-            let broadcast_type =
-                osrs_broadcast_extractor::get_broadcast_type(test_collection_log.message);
-            assert!(matches!(
-                broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::CollectionLog
-            ));
+    fn test_parse_broadcast_milestones() {
+        for test_xpmilestone in get_xpmilestone_messages() {
+            match osrs_broadcast_extractor::parse_broadcast(test_xpmilestone.message.as_str()) {
+                osrs_broadcast_extractor::ParsedBroadcast::XPMilestone(xpmilestone) => {
+                    assert_eq!(
+                        xpmilestone.clan_mate,
+                        test_xpmilestone.xpmilestone_broadcast.clan_mate
+                    );
+                    assert_eq!(
+                        xpmilestone.skill,
+                        test_xpmilestone.xpmilestone_broadcast.skill
+                    );
+                }
+                _ => panic!("Expected an XP milestone: {}", test_xpmilestone.message),
+            }
+        }
+
+        for test_levelmilestone in get_levelmilestone_messages() {
+            match osrs_broadcast_extractor::parse_broadcast(test_levelmilestone.message.as_str()) {
+                osrs_broadcast_extractor::ParsedBroadcast::LevelMilestone(levelmilestone) => {
+                    assert_eq!(
+                        levelmilestone.clan_mate,
+                        test_levelmilestone.levelmilestone_broadcast.clan_mate
+                    );
+                    assert_eq!(
+                        levelmilestone.new_skill_level,
+                        test_levelmilestone.levelmilestone_broadcast.new_skill_level
+                    );
+                }
+                _ => panic!(
+                    "Expected a level milestone: {}",
+                    test_levelmilestone.message
+                ),
+            }
         }
     }
 
     #[test]
-    fn test_get_left_the_clan_type_broadcast() {
-        let test_left_the_clan = get_has_left_the_clan_messages();
-        for test_left_the_clan in test_left_the_clan {
-            let broadcast_type =
-                osrs_broadcast_extractor::get_broadcast_type(test_left_the_clan.message);
-            assert!(matches!(
-                broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::LeftTheClan
-            ));
-        }
+    fn test_broadcast_patterns_do_not_overlap() {
+        let mut messages: Vec<String> = Vec::new();
+        messages.extend(get_raid_messages().into_iter().map(|test| test.message));
+        messages.extend(get_pet_messages().into_iter().map(|test| test.message));
+        messages.extend(get_drop_messages().into_iter().map(|test| test.message));
+        messages.extend(
+            get_clue_item_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_quest_completed_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_diary_completed_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(get_pk_messages().into_iter().map(|test| test.message));
+        messages.extend(get_invite_messages().into_iter().map(|test| test.message));
+        messages.extend(
+            get_levelmilestone_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_xpmilestone_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_collection_log_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_has_left_the_clan_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_expelled_from_clan_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_clan_coffer_deposit_broadcast_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(get_loot_key_messages().into_iter().map(|test| test.message));
+        messages.extend(
+            get_hardcore_death_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_combat_task_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_combat_achievement_tier_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_pbs_broadcast_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_leagues_catch_all_broadcast_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_area_unlock_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_relic_tier_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+        messages.extend(
+            get_combat_masteries_messages()
+                .into_iter()
+                .map(|test| test.message),
+        );
+
+        let overlapping: Vec<String> = messages
+            .iter()
+            .filter_map(|message| {
+                let matching_types = osrs_broadcast_extractor::matching_broadcast_types(message);
+                match matching_types.len() > 1 {
+                    true => Some(format!("{} matches {:?}", message, matching_types)),
+                    false => None,
+                }
+            })
+            .collect();
+        assert!(
+            overlapping.is_empty(),
+            "Broadcasts match more than one pattern: {:#?}",
+            overlapping
+        );
     }
 
     #[test]
-    fn test_get_expelled_from_clan_type_broadcast() {
-        let test_expelled_from_clan = get_expelled_from_clan_messages();
-        for test_expelled_from_clan in test_expelled_from_clan {
-            let broadcast_type =
-                osrs_broadcast_extractor::get_broadcast_type(test_expelled_from_clan.message);
-            assert!(matches!(
-                broadcast_type,
-                osrs_broadcast_extractor::BroadcastType::ExpelledFromClan
-            ));
-        }
+    fn test_parse_broadcast_unknown() {
+        let parsed = osrs_broadcast_extractor::parse_broadcast("RuneScape Player says hello.");
+        assert!(matches!(
+            parsed,
+            osrs_broadcast_extractor::ParsedBroadcast::Unknown
+        ));
     }
 
     #[test]
@@ -1137,6 +2008,24 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_drop_extractor_source() {
+        let drop_with_source = osrs_broadcast_extractor::drop_broadcast_extractor(
+            "RuneScape Player received a drop: Voidwaker blade (39,648,370 coins) from Vet'ion."
+                .to_string(),
+        )
+        .unwrap();
+        assert_eq!(drop_with_source.item_name, "Voidwaker blade");
+        assert_eq!(drop_with_source.drop_source, Some("Vet'ion".to_string()));
+
+        let drop_without_source = osrs_broadcast_extractor::drop_broadcast_extractor(
+            "RuneScape Player received a drop: 587 x Cannonball (111,530 coins).".to_string(),
+        )
+        .unwrap();
+        assert_eq!(drop_without_source.item_name, "Cannonball");
+        assert_eq!(drop_without_source.drop_source, None);
+    }
+
     #[test]
     fn test_drop_extractor() {
         let possible_drop_broadcasts = get_drop_messages();
@@ -1202,6 +2091,14 @@ mod tests {
                         pet_broadcast.action_for_pet,
                         possible_pet_broadcast.pet_drop.action_for_pet
                     );
+                    assert_eq!(
+                        pet_broadcast.count_type,
+                        possible_pet_broadcast.pet_drop.count_type
+                    );
+                    assert_eq!(
+                        pet_broadcast.duplicate,
+                        possible_pet_broadcast.pet_drop.duplicate
+                    );
                 }
             }
         }
@@ -1416,6 +2313,14 @@ mod tests {
                             .levelmilestone_broadcast
                             .new_skill_level
                     );
+                    assert_eq!(
+                        levelmilestone_broadcast.kind,
+                        test_levelmilestone_broadcast.levelmilestone_broadcast.kind
+                    );
+                    assert_eq!(
+                        levelmilestone_broadcast.maxed,
+                        test_levelmilestone_broadcast.levelmilestone_broadcast.maxed
+                    );
                     assert_eq!(
                         levelmilestone_broadcast.skill_icon,
                         test_levelmilestone_broadcast
@@ -1427,6 +2332,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_milestone_numbers_that_can_not_be_read() {
+        let levelmilestone = osrs_broadcast_extractor::levelmilestone_broadcast_extractor(
+            "RuneScape Player has reached Attack level ninety.".to_string(),
+        )
+        .unwrap();
+        assert_eq!(levelmilestone.new_skill_level, None);
+
+        let xpmilestone = osrs_broadcast_extractor::xpmilestone_broadcast_extractor(
+            "RuneScape Player has reached 99,999,999,999,999,999,999 XP in Attack.".to_string(),
+        )
+        .unwrap();
+        assert_eq!(xpmilestone.new_skill_xp, None);
+    }
+
     #[test]
     fn test_xpmilestone_broadcast_extractor() {
         let test_xpmilestone_broadcasts = get_xpmilestone_messages();
@@ -1551,6 +2471,101 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_loot_key_extractor() {
+        let test_loot_keys = get_loot_key_messages();
+        for test_loot_key in test_loot_keys {
+            let possible_loot_key_extract = osrs_broadcast_extractor::loot_key_broadcast_extractor(
+                test_loot_key.message.clone(),
+            );
+            match possible_loot_key_extract {
+                None => {
+                    info!(
+                        "Failed to extract loot key from message: {}",
+                        test_loot_key.message.clone()
+                    );
+                    assert!(false);
+                }
+                Some(loot_key) => {
+                    assert_eq!(loot_key.player, test_loot_key.broadcast.player);
+                    assert_eq!(loot_key.value, test_loot_key.broadcast.value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_hardcore_death_extractor() {
+        let test_hardcore_deaths = get_hardcore_death_messages();
+        for test_hardcore_death in test_hardcore_deaths {
+            let possible_hardcore_death_extract =
+                osrs_broadcast_extractor::hardcore_death_broadcast_extractor(
+                    test_hardcore_death.message.clone(),
+                );
+            match possible_hardcore_death_extract {
+                None => {
+                    info!(
+                        "Failed to extract hardcore death from message: {}",
+                        test_hardcore_death.message.clone()
+                    );
+                    assert!(false);
+                }
+                Some(hardcore_death) => {
+                    assert_eq!(hardcore_death.player, test_hardcore_death.broadcast.player);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_combat_task_extractor() {
+        let test_combat_tasks = get_combat_task_messages();
+        for test_combat_task in test_combat_tasks {
+            let possible_combat_task_extract =
+                osrs_broadcast_extractor::combat_task_broadcast_extractor(
+                    test_combat_task.message.clone(),
+                );
+            match possible_combat_task_extract {
+                None => {
+                    info!(
+                        "Failed to extract combat task from message: {}",
+                        test_combat_task.message.clone()
+                    );
+                    assert!(false);
+                }
+                Some(combat_task) => {
+                    assert_eq!(combat_task.player, test_combat_task.broadcast.player);
+                    assert_eq!(combat_task.task_name, test_combat_task.broadcast.task_name);
+                    assert_eq!(combat_task.tier, test_combat_task.broadcast.tier);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_combat_achievement_tier_extractor() {
+        let test_tiers = get_combat_achievement_tier_messages();
+        for test_tier in test_tiers {
+            let possible_tier_extract =
+                osrs_broadcast_extractor::combat_achievement_tier_broadcast_extractor(
+                    test_tier.message.clone(),
+                );
+            match possible_tier_extract {
+                None => {
+                    info!(
+                        "Failed to extract combat achievement tier from message: {}",
+                        test_tier.message.clone()
+                    );
+                    assert!(false);
+                }
+                Some(tier) => {
+                    assert_eq!(tier.player, test_tier.broadcast.player);
+                    assert_eq!(tier.tier, test_tier.broadcast.tier);
+                }
+            }
+        }
+    }
+
     #[test]
     fn test_coffer_withdrawal_extractor() {
         let test_coffer_withdrawal = get_clan_coffer_withdraw_broadcast_messages();
@@ -1613,6 +2628,60 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_area_unlock_broadcast_extractor() {
+        for test_area_unlock in get_area_unlock_messages() {
+            let area_unlock = osrs_broadcast_extractor::area_unlock_broadcast_extractor(
+                test_area_unlock.message.clone(),
+            )
+            .unwrap();
+            assert_eq!(area_unlock.player, test_area_unlock.broadcast.player);
+            assert_eq!(area_unlock.area, test_area_unlock.broadcast.area);
+            assert_eq!(
+                area_unlock.areas_unlocked,
+                test_area_unlock.broadcast.areas_unlocked
+            );
+        }
+    }
+
+    #[test]
+    fn test_relic_tier_broadcast_extractor() {
+        for test_relic_tier in get_relic_tier_messages() {
+            let relic_tier = osrs_broadcast_extractor::relic_tier_broadcast_extractor(
+                test_relic_tier.message.clone(),
+            )
+            .unwrap();
+            assert_eq!(relic_tier.player, test_relic_tier.broadcast.player);
+            assert_eq!(relic_tier.tier, test_relic_tier.broadcast.tier);
+            assert_eq!(relic_tier.relic, test_relic_tier.broadcast.relic);
+        }
+    }
+
+    #[test]
+    fn test_combat_masteries_broadcast_extractor() {
+        for test_combat_mastery in get_combat_masteries_messages() {
+            let combat_mastery = osrs_broadcast_extractor::combat_masteries_broadcast_extractor(
+                test_combat_mastery.message.clone(),
+            )
+            .unwrap();
+            assert_eq!(combat_mastery.player, test_combat_mastery.broadcast.player);
+            assert_eq!(
+                combat_mastery.combat_mastery,
+                test_combat_mastery.broadcast.combat_mastery
+            );
+            assert_eq!(combat_mastery.rank, test_combat_mastery.broadcast.rank);
+        }
+    }
+
+    #[test]
+    fn test_leagues_rank_broadcast_extractor() {
+        let leagues_rank = osrs_broadcast_extractor::leagues_rank_broadcast_extractor(
+            "RuneScape Player has reached the Adamant League rank!".to_string(),
+        )
+        .unwrap();
+        assert_eq!(leagues_rank.player, "RuneScape Player");
+        assert_eq!(leagues_rank.rank, "Adamant");
+    }
 
     #[rstest]
     #[case("0:56.40", 56.40)]
@@ -1628,6 +2697,24 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    #[test]
+    fn test_broadcast_type_names_round_trip_through_registry() {
+        let broadcast_types = BroadcastType::iter();
+        assert!(broadcast_types.contains(&BroadcastType::ExpelledFromClan));
+        for broadcast_type in broadcast_types {
+            assert_eq!(
+                BroadcastType::from_string(broadcast_type.to_string()),
+                broadcast_type
+            );
+            assert_eq!(
+                BroadcastType::from_string(broadcast_type.to_slug().replace("_", " ")),
+                broadcast_type
+            );
+        }
+        assert!(!BroadcastType::Unknown.is_toggleable());
+        assert!(BroadcastType::CofferDonation.is_toggleable());
+    }
+
     //Test data setup
     struct ItemMessageTest {
         message: String,
@@ -1749,6 +2836,8 @@ mod tests {
                 ),
                 actions_optioned_at: Some(194),
                 action_for_pet: Some("kills".to_string()),
+                count_type: PetCountType::KillCount,
+                duplicate: false,
             },
         });
 
@@ -1762,6 +2851,8 @@ mod tests {
                 pet_icon: Some("https://oldschool.runescape.wiki/images/Heron_detail.png".to_string()),
                 actions_optioned_at: Some(11_212_255),
                 action_for_pet: Some("XP".to_string()),
+                count_type: PetCountType::Xp,
+                duplicate: true,
             },
         });
 
@@ -1773,6 +2864,8 @@ mod tests {
                 pet_icon: Some("https://oldschool.runescape.wiki/images/Abyssal_protector_detail.png".to_string()),
                 actions_optioned_at: Some(543),
                 action_for_pet: Some("rift searches".to_string()),
+                count_type: PetCountType::RiftSearches,
+                duplicate: false,
             },
         });
 
@@ -1784,6 +2877,8 @@ mod tests {
                 pet_icon: Some("https://oldschool.runescape.wiki/images/Tiny_tempor_detail.png".to_string()),
                 actions_optioned_at: Some(1_061),
                 action_for_pet: Some("permits".to_string()),
+                count_type: PetCountType::Permits,
+                duplicate: false,
             },
         });
 
@@ -1795,6 +2890,8 @@ mod tests {
                 pet_icon: Some("https://oldschool.runescape.wiki/images/Unknown_Pet_detail.png".to_string()),
                 actions_optioned_at: Some(1_061),
                 action_for_pet: Some("Fake Currency".to_string()),
+                count_type: PetCountType::Other("Fake Currency".to_string()),
+                duplicate: false,
             },
         });
         return possible_pet_broadcasts;
@@ -2176,7 +3273,9 @@ mod tests {
             levelmilestone_broadcast: LevelMilestoneBroadcast {
                 clan_mate: "Th3TRiPPyOn3".to_string(),
                 skill_levelled: "Defence".to_string(),
-                new_skill_level: "70".to_string(),
+                new_skill_level: Some(70),
+                kind: LevelMilestoneKind::Skill,
+                maxed: false,
                 skill_icon: Some(
                     "https://oldschool.runescape.wiki/images/Defence_icon_(detail).png".to_string(),
                 ),
@@ -2188,9 +3287,11 @@ mod tests {
             levelmilestone_broadcast: LevelMilestoneBroadcast {
                 clan_mate: "MechaPanzer".to_string(),
                 skill_levelled: "combat".to_string(),
-                new_skill_level: "104".to_string(),
+                new_skill_level: Some(104),
+                kind: LevelMilestoneKind::Combat,
+                maxed: false,
                 skill_icon: Some(
-                    "https://oldschool.runescape.wiki/images/combat_icon_(detail).png".to_string(),
+                    "https://oldschool.runescape.wiki/images/Combat_icon.png".to_string(),
                 ),
             },
         });
@@ -2200,9 +3301,11 @@ mod tests {
             levelmilestone_broadcast: LevelMilestoneBroadcast {
                 clan_mate: "I Vision I".to_string(),
                 skill_levelled: "total".to_string(),
-                new_skill_level: "2225".to_string(),
+                new_skill_level: Some(2225),
+                kind: LevelMilestoneKind::Total,
+                maxed: false,
                 skill_icon: Some(
-                    "https://oldschool.runescape.wiki/images/total_icon_(detail).png".to_string(),
+                    "https://oldschool.runescape.wiki/images/Stats_icon.png".to_string(),
                 ),
             },
         });
@@ -2213,9 +3316,11 @@ mod tests {
             levelmilestone_broadcast: LevelMilestoneBroadcast {
                 clan_mate: "Zillamanjaro".to_string(),
                 skill_levelled: "combat".to_string(),
-                new_skill_level: "126".to_string(),
+                new_skill_level: Some(126),
+                kind: LevelMilestoneKind::Combat,
+                maxed: true,
                 skill_icon: Some(
-                    "https://oldschool.runescape.wiki/images/combat_icon_(detail).png".to_string(),
+                    "https://oldschool.runescape.wiki/images/Combat_icon.png".to_string(),
                 ),
             },
         });
@@ -2225,9 +3330,11 @@ mod tests {
             levelmilestone_broadcast: LevelMilestoneBroadcast {
                 clan_mate: "Sad Bug".to_string(),
                 skill_levelled: "total".to_string(),
-                new_skill_level: "2277".to_string(),
+                new_skill_level: Some(2277),
+                kind: LevelMilestoneKind::Total,
+                maxed: true,
                 skill_icon: Some(
-                    "https://oldschool.runescape.wiki/images/total_icon_(detail).png".to_string(),
+                    "https://oldschool.runescape.wiki/images/Stats_icon.png".to_string(),
                 ),
             },
         });
@@ -2245,7 +3352,7 @@ mod tests {
             xpmilestone_broadcast: XPMilestoneBroadcast {
                 clan_mate: "Noble Five".to_string(),
                 skill: "Fishing".to_string(),
-                new_skill_xp: "78,000,000".to_string(),
+                new_skill_xp: Some(78_000_000),
                 skill_icon: Some(
                     "https://oldschool.runescape.wiki/images/Fishing_icon_(detail).png".to_string(),
                 ),
@@ -2257,7 +3364,7 @@ mod tests {
             xpmilestone_broadcast: XPMilestoneBroadcast {
                 clan_mate: "Matrese".to_string(),
                 skill: "Fishing".to_string(),
-                new_skill_xp: "15,000,000".to_string(),
+                new_skill_xp: Some(15_000_000),
                 skill_icon: Some(
                     "https://oldschool.runescape.wiki/images/Fishing_icon_(detail).png".to_string(),
                 ),
@@ -2269,7 +3376,7 @@ mod tests {
             xpmilestone_broadcast: XPMilestoneBroadcast {
                 clan_mate: "Marsel".to_string(),
                 skill: "Cooking".to_string(),
-                new_skill_xp: "200,000,000".to_string(),
+                new_skill_xp: Some(200_000_000),
                 skill_icon: Some(
                     "https://oldschool.runescape.wiki/images/Cooking_icon_(detail).png".to_string(),
                 ),
@@ -2385,6 +3492,96 @@ mod tests {
         test_clan_coffer_broadcast_messages
     }
 
+    fn get_loot_key_messages() -> Vec<TestBroadcast<LootKey>> {
+        let mut test_loot_key_messages: Vec<TestBroadcast<LootKey>> = Vec::new();
+        test_loot_key_messages.push(TestBroadcast {
+            message: "KANlEL OUTIS has opened a loot key worth 1,148,040 coins!".to_string(),
+            broadcast: LootKey {
+                player: "KANlEL OUTIS".to_string(),
+                value: 1_148_040,
+            },
+        });
+        test_loot_key_messages.push(TestBroadcast {
+            message: "Med-iocore has opened a loot key worth 489,181 coins!".to_string(),
+            broadcast: LootKey {
+                player: "Med-iocore".to_string(),
+                value: 489_181,
+            },
+        });
+        test_loot_key_messages
+    }
+
+    fn get_hardcore_death_messages() -> Vec<TestBroadcast<HardcoreDeathBroadcast>> {
+        let mut test_hardcore_death_messages: Vec<TestBroadcast<HardcoreDeathBroadcast>> =
+            Vec::new();
+        test_hardcore_death_messages.push(TestBroadcast {
+            message: "Hardcore Iron has died and lost their Hardcore Ironman status!".to_string(),
+            broadcast: HardcoreDeathBroadcast {
+                player: "Hardcore Iron".to_string(),
+            },
+        });
+        test_hardcore_death_messages.push(TestBroadcast {
+            message: "HC Btw has died and lost their Hardcore Ironman status.".to_string(),
+            broadcast: HardcoreDeathBroadcast {
+                player: "HC Btw".to_string(),
+            },
+        });
+        test_hardcore_death_messages
+    }
+
+    fn get_combat_task_messages() -> Vec<TestBroadcast<CombatTaskBroadcast>> {
+        let mut test_combat_task_messages: Vec<TestBroadcast<CombatTaskBroadcast>> = Vec::new();
+        test_combat_task_messages.push(TestBroadcast {
+            message: "RuneScape Player has completed a hard combat task: Whack-a-Mole.".to_string(),
+            broadcast: CombatTaskBroadcast {
+                player: "RuneScape Player".to_string(),
+                task_name: "Whack-a-Mole".to_string(),
+                tier: CombatAchievementTier::Hard,
+            },
+        });
+        test_combat_task_messages.push(TestBroadcast {
+            message: "Zezima has completed an elite combat task: Perfect Zulrah.".to_string(),
+            broadcast: CombatTaskBroadcast {
+                player: "Zezima".to_string(),
+                task_name: "Perfect Zulrah".to_string(),
+                tier: CombatAchievementTier::Elite,
+            },
+        });
+        test_combat_task_messages.push(TestBroadcast {
+            message: "Sad Bug has completed a grandmaster combat task: Inferno Speed-Runner."
+                .to_string(),
+            broadcast: CombatTaskBroadcast {
+                player: "Sad Bug".to_string(),
+                task_name: "Inferno Speed-Runner".to_string(),
+                tier: CombatAchievementTier::Grandmaster,
+            },
+        });
+        test_combat_task_messages
+    }
+
+    fn get_combat_achievement_tier_messages() -> Vec<TestBroadcast<CombatAchievementTierBroadcast>>
+    {
+        let mut test_tier_messages: Vec<TestBroadcast<CombatAchievementTierBroadcast>> = Vec::new();
+        test_tier_messages.push(TestBroadcast {
+            message:
+                "RuneScape Player has unlocked the Elite tier of rewards from Combat Achievements!"
+                    .to_string(),
+            broadcast: CombatAchievementTierBroadcast {
+                player: "RuneScape Player".to_string(),
+                tier: CombatAchievementTier::Elite,
+            },
+        });
+        test_tier_messages.push(TestBroadcast {
+            message: "Zezima has unlocked the master tier of rewards from Combat Achievements!"
+                .to_string(),
+            broadcast: CombatAchievementTierBroadcast {
+                player: "Zezima".to_string(),
+                tier: CombatAchievementTier::Master,
+            },
+        });
+        test_tier_messages
+    }
+
     fn get_clan_coffer_withdraw_broadcast_messages(
     ) -> Vec<TestBroadcast<CofferTransactionBroadcast>> {
         let mut test_clan_coffer_broadcast_messages: Vec<
@@ -2548,8 +3745,84 @@ mod tests {
             broadcast: LeaguesBroadCastType::RelicTier,
         });
 
-        //TODO add rank as we find more examples
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has reached the Adamant League rank!".to_string(),
+            broadcast: LeaguesBroadCastType::LeaguesRank,
+        });
+
+        messages
+    }
+
+    fn get_area_unlock_messages() -> Vec<TestBroadcast<AreaUnlockBroadcast>> {
+        let mut messages: Vec<TestBroadcast<AreaUnlockBroadcast>> = Vec::new();
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has unlocked their 3rd League area!".to_string(),
+            broadcast: AreaUnlockBroadcast {
+                player: "RuneScape Player".to_string(),
+                area: None,
+                areas_unlocked: Some(3),
+            },
+        });
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has unlocked their 2nd League area: Kandarin!".to_string(),
+            broadcast: AreaUnlockBroadcast {
+                player: "RuneScape Player".to_string(),
+                area: Some("Kandarin".to_string()),
+                areas_unlocked: Some(2),
+            },
+        });
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has unlocked the Kourend & Kebos area!".to_string(),
+            broadcast: AreaUnlockBroadcast {
+                player: "RuneScape Player".to_string(),
+                area: Some("Kourend & Kebos".to_string()),
+                areas_unlocked: None,
+            },
+        });
+        messages
+    }
+
+    fn get_relic_tier_messages() -> Vec<TestBroadcast<RelicTierBroadcast>> {
+        let mut messages: Vec<TestBroadcast<RelicTierBroadcast>> = Vec::new();
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has unlocked their tier 3 League relic!".to_string(),
+            broadcast: RelicTierBroadcast {
+                player: "RuneScape Player".to_string(),
+                tier: 3,
+                relic: None,
+            },
+        });
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has unlocked their tier 1 League relic: Endless Harvest!"
+                .to_string(),
+            broadcast: RelicTierBroadcast {
+                player: "RuneScape Player".to_string(),
+                tier: 1,
+                relic: Some("Endless Harvest".to_string()),
+            },
+        });
+        messages
+    }
 
+    fn get_combat_masteries_messages() -> Vec<TestBroadcast<CombatMasteriesBroadcast>> {
+        let mut messages: Vec<TestBroadcast<CombatMasteriesBroadcast>> = Vec::new();
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has earned their 6th Combat mastery point!".to_string(),
+            broadcast: CombatMasteriesBroadcast {
+                player: "RuneScape Player".to_string(),
+                combat_mastery: None,
+                rank: 6,
+            },
+        });
+        messages.push(TestBroadcast {
+            message: "RuneScape Player has earned their 2nd Melee combat mastery point!"
+                .to_string(),
+            broadcast: CombatMasteriesBroadcast {
+                player: "RuneScape Player".to_string(),
+                combat_mastery: Some("Melee".to_string()),
+                rank: 2,
+            },
+        });
         messages
     }
 }
@@ -1,24 +1,25 @@
+use crate::broadcast_processors::processor_for;
+use crate::broadcast_rules::{evaluate_broadcast_rules, BroadcastRuleContext, RuleAction};
 use crate::database::clan_mate_collection_log_totals::ClanMateCollectionLogTotals;
+use crate::database::clan_mate_leagues_progress::LeaguesProgressUpdate;
 use crate::database::clan_mates::ClanMates;
 use crate::database::drop_logs_db::DropLogs;
 use crate::database::guilds_db::RegisteredGuildModel;
-use crate::ge_api::ge_api::{get_item_value_by_id, GeItemMapping};
-use crate::jobs::new_pb_job::record_new_pb;
-use crate::jobs::{remove_clanmate_job, JobQueue};
+use crate::database::pb_records_db::PersonalBests;
+use crate::embed_templates::TemplateValues;
+use crate::ge_api::ge_api::{GeItemMapping, GeItemPrice, GePrices};
+use crate::jobs::leagues_progress_job::record_leagues_progress;
+use crate::jobs::JobQueue;
 use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
-    clue_item_broadcast_extractor, coffer_donation_broadcast_extractor,
-    coffer_withdrawal_broadcast_extractor, collection_log_broadcast_extractor,
-    diary_completed_broadcast_extractor, drop_broadcast_extractor,
-    expelled_from_clan_broadcast_extractor, get_broadcast_type, invite_broadcast_extractor,
-    leagues_catch_all_broadcast_extractor, left_the_clan_broadcast_extractor,
-    levelmilestone_broadcast_extractor, personal_best_broadcast_extractor, pet_broadcast_extractor,
-    pk_broadcast_extractor, quest_completed_broadcast_extractor, raid_broadcast_extractor,
-    xpmilestone_broadcast_extractor, BroadcastType, ClanMessage, LeaguesBroadCastType,
+    leagues_catch_all_broadcast_extractor, parse_broadcast, AccountType, BroadcastType,
+    ClanMessage, DropItemBroadcast, ParsedBroadcast,
 };
-use crate::wiki_api::wiki_api::{WikiClogs, WikiQuest};
+use crate::role_pings::role_to_ping;
+use crate::wiki_api::wiki_api::{format_drop_rate, WikiClogs, WikiDropRate, WikiQuest};
 use log::{error, info};
 use num_format::{Locale, ToFormattedString};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -31,6 +32,15 @@ pub struct BroadcastMessageToDiscord {
     // Since this is not for just drops any more can be anything
     //GP, xp, kc, etc
     pub item_quantity: Option<i64>,
+    //Set when a broadcast rule sends this to a different channel
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub route_to_channel: Option<u64>,
+    //Set when the guild mentions a role for this broadcast
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ping_role_id: Option<u64>,
+    //Fills the guilds embed template, only needed until the embed is made
+    #[serde(skip)]
+    pub template_values: TemplateValues,
 }
 
 #[derive(Clone)]
@@ -38,33 +48,46 @@ pub struct OSRSBroadcastHandler<
     T: DropLogs,
     CL: ClanMateCollectionLogTotals,
     CM: ClanMates,
+    PB: PersonalBests,
     J: JobQueue,
 > {
-    clan_message: ClanMessage,
-    item_mapping: Option<GeItemMapping>,
-    quests: Option<Vec<WikiQuest>>,
-    clogs: Option<Vec<WikiClogs>>,
-    registered_guild: RegisteredGuildModel,
-    leagues_message: bool,
-    drop_log_db: T,
-    collection_log_db: CL,
-    clan_mates_db: CM,
-    job_queue: Arc<J>,
+    pub(crate) clan_message: ClanMessage,
+    pub(crate) item_mapping: Option<GeItemMapping>,
+    pub(crate) prices: Option<GePrices>,
+    pub(crate) quests: Option<Vec<WikiQuest>>,
+    pub(crate) clogs: Option<Vec<WikiClogs>>,
+    /// The N in 1/N keyed by the lowercase item name
+    pub(crate) drop_rates: Option<HashMap<String, f64>>,
+    pub(crate) registered_guild: RegisteredGuildModel,
+    pub(crate) leagues_message: bool,
+    pub(crate) drop_log_db: T,
+    pub(crate) collection_log_db: CL,
+    pub(crate) clan_mates_db: CM,
+    pub(crate) pb_records_db: PB,
+    pub(crate) job_queue: Arc<J>,
 }
 
-impl<T: DropLogs, CL: ClanMateCollectionLogTotals, CM: ClanMates, J: JobQueue>
-    OSRSBroadcastHandler<T, CL, CM, J>
+impl<
+        T: DropLogs,
+        CL: ClanMateCollectionLogTotals,
+        CM: ClanMates,
+        PB: PersonalBests,
+        J: JobQueue,
+    > OSRSBroadcastHandler<T, CL, CM, PB, J>
 {
     pub fn new(
         clan_message: ClanMessage,
         item_mapping_from_state: Result<GeItemMapping, anyhow::Error>,
+        prices_from_state: Result<GePrices, anyhow::Error>,
         quests_from_state: Result<Vec<WikiQuest>, anyhow::Error>,
         clogs_from_state: Result<Vec<WikiClogs>, anyhow::Error>,
+        drop_rates_from_state: Result<Vec<WikiDropRate>, anyhow::Error>,
         register_guild: RegisteredGuildModel,
         leagues_message: bool,
         drop_log_db: T,
         collection_log_db: CL,
         clan_mates_db: CM,
+        pb_records_db: PB,
         job_queue: Arc<J>,
     ) -> Self {
         Self {
@@ -73,6 +96,10 @@ impl<T: DropLogs, CL: ClanMateCollectionLogTotals, CM: ClanMates, J: JobQueue>
                 Ok(item_mapping) => Some(item_mapping),
                 Err(_) => None,
             },
+            prices: match prices_from_state {
+                Ok(prices) => Some(prices),
+                Err(_) => None,
+            },
             quests: match quests_from_state {
                 Ok(quests) => Some(quests),
                 Err(_) => None,
@@ -81,1128 +108,200 @@ impl<T: DropLogs, CL: ClanMateCollectionLogTotals, CM: ClanMates, J: JobQueue>
                 Ok(clogs) => Some(clogs),
                 Err(_) => None,
             },
+            drop_rates: match drop_rates_from_state {
+                Ok(drop_rates) => Some(
+                    drop_rates
+                        .into_iter()
+                        .map(|drop_rate| (drop_rate.name.to_lowercase(), drop_rate.one_in))
+                        .collect(),
+                ),
+                Err(_) => None,
+            },
             registered_guild: register_guild,
             leagues_message,
             drop_log_db,
             collection_log_db,
             clan_mates_db,
+            pb_records_db,
             job_queue: job_queue,
         }
     }
 
     pub async fn extract_message(&self) -> Option<BroadcastMessageToDiscord> {
-        let broadcast_type = get_broadcast_type(self.clan_message.message.clone());
-
-        match broadcast_type {
-            BroadcastType::RaidDrop => {
-                let drop_item = raid_broadcast_extractor(self.clan_message.message.clone());
-                match drop_item {
-                    None => {
-                        error!(
-                            "Failed to extract drop item from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(mut drop_item) => {
-                        match &self.item_mapping {
-                            Some(item_mapping) => {
-                                for item in item_mapping {
-                                    if item.name == drop_item.item_name {
-                                        let price_check = get_item_value_by_id(item.id).await;
-                                        match price_check {
-                                            Ok(price) => {
-                                                if price.high > 0 {
-                                                    drop_item.item_value = Some(price.high);
-                                                }
-                                            }
-                                            Err(_) => {}
-                                        }
-                                    }
-                                }
-                            }
-                            None => {}
-                        }
-
-                        if !self.leagues_message {
-                            self.drop_log_db
-                                .new_drop_log(drop_item.clone(), self.registered_guild.guild_id)
-                                .await;
-                        }
-
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::RaidDrop = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-
-                        let title = match self.leagues_message {
-                            true => ":bar_chart: New Leagues raid drop!".to_string(),
-                            false => ":tada: New raid drop!".to_string(),
-                        };
-
-                        if self.check_if_filtered_broad_cast(
-                            BroadcastType::ItemDrop,
-                            drop_item.player_it_happened_to.clone(),
-                            drop_item.item_name.clone(),
-                        ) {
-                            return None;
-                        }
-
-                        Some(BroadcastMessageToDiscord {
-                            player_it_happened_to: drop_item.player_it_happened_to.clone(),
-                            type_of_broadcast: BroadcastType::RaidDrop,
-                            message: match drop_item.item_value {
-                                None => {
-                                    format!(
-                                        "{} received special loot from a raid: {}.",
-                                        drop_item.player_it_happened_to, drop_item.item_name
-                                    )
-                                }
-                                Some(item_value) => {
-                                    format!(
-                                        "{} received special loot from a raid: {} ({} coins).",
-                                        drop_item.player_it_happened_to,
-                                        drop_item.item_name,
-                                        item_value.to_formatted_string(&Locale::en)
-                                    )
-                                }
-                            },
-                            icon_url: drop_item.item_icon,
-                            title,
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::ItemDrop => self.drop_item_handler().await,
-            BroadcastType::ClueItem => self.clue_item_handler().await,
-            BroadcastType::PetDrop => {
-                let pet_drop_item = pet_broadcast_extractor(self.clan_message.message.clone());
-                match pet_drop_item {
-                    None => {
-                        error!(
-                            "Failed to extract pet drop from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(pet_drop) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::PetDrop = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-
-                        let title = match self.leagues_message {
-                            true => ":bar_chart: New Leagues Pet drop!".to_string(),
-                            false => ":tada: New Pet drop!".to_string(),
-                        };
-
-                        if self.check_if_filtered_broad_cast(
-                            BroadcastType::PetDrop,
-                            pet_drop.player_it_happened_to.clone(),
-                            pet_drop.pet_name.clone(),
-                        ) {
-                            return None;
-                        }
-
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::PetDrop,
-                            player_it_happened_to: pet_drop.player_it_happened_to,
-                            message: self.clan_message.message.clone(),
-                            icon_url: pet_drop.pet_icon,
-                            title,
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::Diary => self.diary_handler(),
-            BroadcastType::Quest => self.quest_handler(),
-            BroadcastType::Pk => self.pk_handler(),
-            BroadcastType::Invite => {
-                let possible_invite_broadcast =
-                    invite_broadcast_extractor(self.clan_message.message.clone());
-                match possible_invite_broadcast {
-                    None => {
-                        error!(
-                            "Failed to extract invite info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(invite_broadcast) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::Invite = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::Invite,
-                            player_it_happened_to: invite_broadcast.clan_mate,
-                            message: self.clan_message.message.clone(),
-                            icon_url: Some(
-                                "https://oldschool.runescape.wiki/images/Your_Clan_icon.png"
-                                    .to_string(),
-                            ),
-                            title: ":wave: New Invite!".to_string(),
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::ExpelledFromClan => {
-                let possible_expelled_broadcast =
-                    expelled_from_clan_broadcast_extractor(self.clan_message.message.clone());
-
-                match possible_expelled_broadcast {
-                    None => {
-                        error!(
-                            "Failed to extract left clan info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(clan_mate_who_got_kicked) => {
-                        let job = remove_clanmate_job::remove_clanmate::new(
-                            clan_mate_who_got_kicked.clone(),
-                            self.registered_guild.guild_id,
-                        );
-                        let _ = self.job_queue.send_task(job).await;
-
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::LeftTheClan = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::LeftTheClan,
-                            player_it_happened_to: clan_mate_who_got_kicked,
-                            message: self.clan_message.message.clone(),
-                            icon_url: Some(
-                                "https://oldschool.runescape.wiki/images/Your_Clan_icon.png"
-                                    .to_string(),
-                            ),
-                            title: ":boot: Someone has been expelled!".to_string(),
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::LeftTheClan => {
-                let possible_left_broadcast =
-                    left_the_clan_broadcast_extractor(self.clan_message.message.clone());
-
-                match possible_left_broadcast {
-                    None => {
-                        error!(
-                            "Failed to extract left clan info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(clan_mate_who_left) => {
-                        let job = remove_clanmate_job::remove_clanmate::new(
-                            clan_mate_who_left.clone(),
-                            self.registered_guild.guild_id,
-                        );
-                        let _ = self.job_queue.send_task(job).await;
-
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::LeftTheClan = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::LeftTheClan,
-                            player_it_happened_to: clan_mate_who_left,
-                            message: self.clan_message.message.clone(),
-                            icon_url: Some(
-                                "https://oldschool.runescape.wiki/images/Your_Clan_icon.png"
-                                    .to_string(),
-                            ),
-                            title: ":people_hugging: Someone has left the clan!".to_string(),
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::LevelMilestone => {
-                let possible_levelmilestone_broadcast =
-                    levelmilestone_broadcast_extractor(self.clan_message.message.clone());
-                match possible_levelmilestone_broadcast {
-                    None => {
-                        error!(
-                            "Failed to extract level milestone info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(levelmilestone_broadcast) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::LevelMilestone = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-                        let title = match self.leagues_message {
-                            true => ":bar_chart: New Leagues Level Milestone reached!".to_string(),
-                            false => ":tada: New Level Milestone reached!".to_string(),
-                        };
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::LevelMilestone,
-                            player_it_happened_to: levelmilestone_broadcast.clan_mate,
-                            message: self.clan_message.message.clone(),
-                            icon_url: levelmilestone_broadcast.skill_icon,
-                            title,
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::XPMilestone => {
-                let possible_xpmilestone_broadcast =
-                    xpmilestone_broadcast_extractor(self.clan_message.message.clone());
-                match possible_xpmilestone_broadcast {
-                    None => {
-                        error!(
-                            "Failed to extract xp milestone info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(xpmilestone_broadcast) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::XPMilestone = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-                        let title = match self.leagues_message {
-                            true => ":bar_chart: New Leagues XP Milestone reached!".to_string(),
-                            false => ":tada: New XP Milestone reached!".to_string(),
-                        };
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::XPMilestone,
-                            player_it_happened_to: xpmilestone_broadcast.clan_mate,
-                            message: self.clan_message.message.clone(),
-                            icon_url: xpmilestone_broadcast.skill_icon,
-                            title,
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::CollectionLog => self.collection_log_handler().await,
-            BroadcastType::CofferDonation => {
-                let possible_coffer_donation =
-                    coffer_donation_broadcast_extractor(self.clan_message.message.clone());
-
-                match possible_coffer_donation {
-                    None => {
-                        error!(
-                            "Failed to extract coffer donation info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(coffer_donation) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::CofferDonation = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::CofferDonation,
-                            player_it_happened_to: coffer_donation.player,
-                            message: self.clan_message.message.clone(),
-                            icon_url: Some(
-                                "https://oldschool.runescape.wiki/images/thumb/Clan_Coffer.png/943px-Clan_Coffer.png"
-                                    .to_string(),
-                            ),
-                            title: ":coin: New Donation!".to_string(),
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::CofferWithdrawal => {
-                let possible_coffer_withdrawal =
-                    coffer_withdrawal_broadcast_extractor(self.clan_message.message.clone());
-
-                match possible_coffer_withdrawal {
-                    None => {
-                        error!(
-                            "Failed to extract coffer withdrawal info from message: {}",
-                            self.clan_message.message.clone()
-                        );
-                        None
-                    }
-                    Some(coffer_withdrawal) => {
-                        let is_disallowed = self
-                            .registered_guild
-                            .disallowed_broadcast_types
-                            .iter()
-                            .find(|&x| {
-                                if let BroadcastType::CofferWithdrawal = x {
-                                    return true;
-                                }
-                                false
-                            });
-                        if is_disallowed.is_some() {
-                            return None;
-                        }
-
-                        Some(BroadcastMessageToDiscord {
-                            type_of_broadcast: BroadcastType::CofferWithdrawal,
-                            player_it_happened_to: coffer_withdrawal.player,
-                            message: self.clan_message.message.clone(),
-                            icon_url: Some(
-                                "https://oldschool.runescape.wiki/images/thumb/Clan_Coffer.png/943px-Clan_Coffer.png"
-                                    .to_string(),
-                            ),
-                            title: ":person_running: New Clan Coffer Withdrawal!".to_string(),
-                            item_quantity: None,
-                        })
-                    }
-                }
-            }
-            BroadcastType::PersonalBest => self.personal_best_handler().await,
-            _ => None,
-        }
+        let parsed_broadcast = parse_broadcast(self.clan_message.message.as_str());
+        let processor = processor_for(&parsed_broadcast.broadcast_type())?;
+        processor.process(self, parsed_broadcast).await
     }
 
-    async fn drop_item_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let drop_item = drop_broadcast_extractor(self.clan_message.message.clone());
-
-        match drop_item {
-            None => {
-                error!(
-                    "Failed to extract drop item from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-            Some(drop_item) => {
-                if !self.leagues_message {
-                    self.drop_log_db
-                        .new_drop_log(drop_item.clone(), self.registered_guild.guild_id)
-                        .await;
-                }
-                let is_disallowed = self.check_if_allowed_broad_cast(BroadcastType::ItemDrop);
-                if is_disallowed {
-                    return None;
-                }
-                if self.registered_guild.drop_price_threshold.is_some() {
-                    if drop_item.item_value.is_some() {
-                        if self.registered_guild.drop_price_threshold.unwrap()
-                            > drop_item.item_value.unwrap()
-                        {
-                            return None;
-                        }
-                    }
-                }
-
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues High Value drop!".to_string(),
-                    false => ":tada: New High Value drop!".to_string(),
-                };
-
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::ItemDrop,
-                    drop_item.player_it_happened_to.clone(),
-                    drop_item.item_name.clone(),
-                ) {
-                    return None;
-                }
-
-                Some(BroadcastMessageToDiscord {
-                    player_it_happened_to: drop_item.player_it_happened_to.clone(),
-                    type_of_broadcast: BroadcastType::ItemDrop,
-                    title,
-                    message: match drop_item.item_quantity {
-                        //If there is only one of the items dropped
-                        1 => match drop_item.item_value {
-                            //If the item has a value with it
-                            None => format!(
-                                "{} received a drop: {}.",
-                                drop_item.player_it_happened_to, drop_item.item_name
-                            ),
-                            _ => format!(
-                                "{} received a drop: {} ({} coins).",
-                                drop_item.player_it_happened_to,
-                                drop_item.item_name,
-                                drop_item
-                                    .item_value
-                                    .unwrap()
-                                    .to_formatted_string(&Locale::en)
-                            ),
-                        },
-                        _ => match drop_item.item_value {
-                            //If the item has a value with it
-                            None => format!(
-                                "{} received a drop: {} x {}",
-                                drop_item.player_it_happened_to,
-                                drop_item.item_name,
-                                drop_item.item_quantity
-                            ),
-                            _ => format!(
-                                "{} received a drop: {} x {} ({} coins).",
-                                drop_item.player_it_happened_to,
-                                drop_item.item_quantity,
-                                drop_item.item_name,
-                                drop_item
-                                    .item_value
-                                    .unwrap()
-                                    .to_formatted_string(&Locale::en)
-                            ),
-                        },
-                    },
-                    icon_url: drop_item.item_icon,
-                    item_quantity: drop_item.item_value,
-                })
-            }
-        }
+    pub async fn extract_leagues_message(&self) -> Option<BroadcastMessageToDiscord> {
+        let leagues_broadcast_type =
+            leagues_catch_all_broadcast_extractor(self.clan_message.message.clone())?;
+        info!("Leagues broadcast type: {:?}", leagues_broadcast_type);
+
+        //Ideally previous broadcast logic should catch normal broadcasts and we just come to leagues for the new types,
+        //those fall to the unknown processor which sends nothing
+        let processor = processor_for(&leagues_broadcast_type.to_broadcast_type())?;
+        //Progress is recorded by the processors even if the rules stop the broadcast
+        processor.process(self, ParsedBroadcast::Unknown).await
     }
 
-    async fn clue_item_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let clue_item = clue_item_broadcast_extractor(self.clan_message.message.clone());
-
-        match clue_item {
-            None => {
-                error!(
-                    "Failed to extract clue item from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-            Some(clue_item) => {
-                if !self.leagues_message {
-                    self.drop_log_db
-                        .new_drop_log(clue_item.clone(), self.registered_guild.guild_id)
-                        .await;
-                }
-                let is_disallowed = self.check_if_allowed_broad_cast(BroadcastType::ClueItem);
-                if is_disallowed {
-                    return None;
-                }
-                if self.registered_guild.drop_price_threshold.is_some() {
-                    if clue_item.item_value.is_some() {
-                        if self.registered_guild.drop_price_threshold.unwrap()
-                            > clue_item.item_value.unwrap()
-                        {
-                            return None;
-                        }
-                    }
-                }
-
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues High Value drop!".to_string(),
-                    false => ":tada: New High Value drop!".to_string(),
-                };
-
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::ItemDrop,
-                    clue_item.player_it_happened_to.clone(),
-                    clue_item.item_name.clone(),
-                ) {
-                    return None;
-                }
-
-                Some(BroadcastMessageToDiscord {
-                    player_it_happened_to: clue_item.player_it_happened_to.clone(),
-                    type_of_broadcast: BroadcastType::ClueItem,
-                    title,
-                    message: match clue_item.item_quantity {
-                        //If there is only one of the items dropped
-                        1 => match clue_item.item_value {
-                            //If the item has a value with it
-                            None => format!(
-                                "{} received a clue item: {}.",
-                                clue_item.player_it_happened_to, clue_item.item_name
-                            ),
-                            _ => format!(
-                                "{} received a clue item: {} ({} coins).",
-                                clue_item.player_it_happened_to,
-                                clue_item.item_name,
-                                clue_item
-                                    .item_value
-                                    .unwrap()
-                                    .to_formatted_string(&Locale::en)
-                            ),
-                        },
-                        _ => match clue_item.item_value {
-                            //If the item has a value with it
-                            None => format!(
-                                "{} received a clue item: {}",
-                                clue_item.player_it_happened_to, clue_item.item_name,
-                            ),
-                            _ => format!(
-                                "{} received a clue item: {} ({} coins).",
-                                clue_item.player_it_happened_to,
-                                clue_item.item_name,
-                                clue_item
-                                    .item_value
-                                    .unwrap()
-                                    .to_formatted_string(&Locale::en)
-                            ),
-                        },
-                    },
-                    icon_url: clue_item.item_icon,
-                    item_quantity: clue_item.item_value,
-                })
-            }
-        }
+    pub(crate) async fn record_leagues_progress(&self, leagues_progress: LeaguesProgressUpdate) {
+        let job = record_leagues_progress::new(leagues_progress, self.registered_guild.guild_id);
+        let _ = self.job_queue.send_task(job).await;
     }
 
-    fn pk_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let possible_pk_broadcast = pk_broadcast_extractor(self.clan_message.message.clone());
-        match possible_pk_broadcast {
-            None => {
-                error!(
-                    "Failed to extract pk info from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-            Some(pk_broadcast) => {
-                if self.registered_guild.pk_value_threshold.is_some() {
-                    if pk_broadcast.gp_exchanged.is_some() {
-                        if self.registered_guild.pk_value_threshold.unwrap()
-                            > pk_broadcast.gp_exchanged.unwrap()
-                        {
-                            return None;
-                        }
-                    }
-                }
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues PK!".to_string(),
-                    false => ":crossed_swords: New PK!".to_string(),
-                };
-                Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::Pk,
-                    player_it_happened_to: pk_broadcast.winner,
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some("https://oldschool.runescape.wiki/images/Skull.png".to_string()),
-                    title,
-                    item_quantity: None,
-                })
-            }
-        }
+    pub(crate) fn get_item_id(&self, item_name: &str) -> Option<i64> {
+        self.item_mapping
+            .as_ref()?
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(item_name))
+            .map(|item| item.id)
     }
 
-    fn quest_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let quest_completed =
-            quest_completed_broadcast_extractor(self.clan_message.message.clone());
-
-        let possible_quests = self.quests.clone();
-
-        match quest_completed {
-            None => {
-                error!(
-                    "Failed to extract Quest info from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-
-            Some(exported_data) => {
-                let is_disallowed = self
-                    .registered_guild
-                    .disallowed_broadcast_types
-                    .iter()
-                    .find(|&x| {
-                        if let BroadcastType::Quest = x {
-                            return true;
-                        }
-                        false
-                    });
-                if is_disallowed.is_some() {
-                    return None;
-                }
-
-                if self.registered_guild.min_quest_difficulty.is_some()
-                    && possible_quests.is_some()
-                    && self.quests.is_some()
-                {
-                    let quest_name = exported_data.quest_name.clone();
-
-                    let quests = &possible_quests.unwrap();
-                    let possible_difficulty = quests.iter().find(|&x| x.name == quest_name);
-                    if possible_difficulty.is_none() {
-                        return None;
-                    }
-                    let min_quest_difficulty =
-                        self.registered_guild.clone().min_quest_difficulty.unwrap();
-                    if possible_difficulty.unwrap().difficulty.ranking()
-                        < min_quest_difficulty.ranking()
-                    {
-                        return None;
-                    }
-                }
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues quest completed!".to_string(),
-                    false => ":tada: New quest completed!".to_string(),
-                };
-
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::Quest,
-                    exported_data.player_it_happened_to.clone(),
-                    exported_data.quest_name.clone(),
-                ) {
-                    return None;
-                }
-
-                Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::Quest,
-                    player_it_happened_to: exported_data.player_it_happened_to,
-                    message: self.clan_message.message.clone(),
-                    icon_url: exported_data.quest_reward_scroll_icon,
-                    title,
-                    item_quantity: None,
-                })
-            }
-        }
+    pub(crate) fn get_item_price(&self, item_id: Option<i64>) -> Option<&GeItemPrice> {
+        self.prices.as_ref()?.get(&item_id?)
     }
 
-    fn diary_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let diary_completed =
-            diary_completed_broadcast_extractor(self.clan_message.message.clone());
-        match diary_completed {
-            None => {
-                error!(
-                    "Failed to extract Diary info from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-            Some(exported_data) => {
-                let is_disallowed = self
-                    .registered_guild
-                    .disallowed_broadcast_types
-                    .iter()
-                    .find(|&x| {
-                        if let BroadcastType::Diary = x {
-                            return true;
-                        }
-                        false
-                    });
-                if is_disallowed.is_some() {
-                    return None;
-                }
-                if self.registered_guild.min_diary_tier.is_some() {
-                    let min_diary_tier = self.registered_guild.clone().min_diary_tier.unwrap();
-                    if exported_data.diary_tier.ranking() < min_diary_tier.ranking() {
-                        return None;
-                    }
-                }
-
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues diary completed!".to_string(),
-                    false => ":tada: New diary completed!".to_string(),
-                };
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::Diary,
-                    exported_data.player_it_happened_to.clone(),
-                    exported_data.diary_name.clone()
-                        + " "
-                        + exported_data.diary_tier.to_string().as_str(),
-                ) {
-                    return None;
-                }
-                Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::Diary,
-                    player_it_happened_to: exported_data.player_it_happened_to,
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some(
-                        "https://oldschool.runescape.wiki/images/Achievement_Diaries.png"
-                            .to_string(),
-                    ),
-                    title,
-                    item_quantity: None,
-                })
-            }
-        }
+    /// The drops value from the guilds price source, used for the thresholds and rules
+    pub(crate) fn drop_value(
+        &self,
+        drop_item: &DropItemBroadcast,
+        ge_price: Option<&GeItemPrice>,
+    ) -> Option<i64> {
+        let item = self.item_mapping.as_ref().and_then(|item_mapping| {
+            item_mapping
+                .iter()
+                .find(|item| item.name.eq_ignore_ascii_case(&drop_item.item_name))
+        });
+        self.registered_guild
+            .drop_price_source
+            .clone()
+            .unwrap_or_default()
+            .drop_value(drop_item.item_value, item, ge_price, drop_item.item_quantity)
     }
 
-    async fn collection_log_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let possible_collection_log =
-            collection_log_broadcast_extractor(self.clan_message.message.clone());
-
-        let possible_clogs = self.clogs.clone();
-        match possible_collection_log {
-            None => {
-                error!(
-                    "Failed to extract collection log info from message: {}",
-                    self.clan_message.message.clone()
-                );
-                None
-            }
-            Some(collection_log_broadcast) => {
-                if !self.leagues_message {
-                    let possible_clan_mate = self
-                        .clan_mates_db
-                        .find_or_create_clan_mate(
-                            self.registered_guild.guild_id,
-                            collection_log_broadcast.player_it_happened_to.clone(),
-                        )
-                        .await;
-                    let _ = match possible_clan_mate {
-                        Ok(clan_mate) => {
-                            self.collection_log_db
-                                .update_or_create(
-                                    clan_mate.guild_id,
-                                    clan_mate.id,
-                                    collection_log_broadcast.log_slots,
-                                )
-                                .await
-                        }
-                        Err(error) => {
-                            error!("{:?}", error);
-                            Err(error)
-                        }
-                    };
-                }
-                let is_disallowed = self.check_if_allowed_broad_cast(BroadcastType::CollectionLog);
-                if is_disallowed {
-                    return None;
-                }
-                if self
-                    .registered_guild
-                    .collection_log_max_percentage
-                    .is_some()
-                    && possible_clogs.is_some()
-                    && self.clogs.is_some()
-                {
-                    let collection_log_name = collection_log_broadcast.item_name.clone();
-
-                    let clogs = &possible_clogs.unwrap();
-                    let possible_percentage = clogs.iter().find(|&x| x.name == collection_log_name);
-                    if possible_percentage.is_none() {
-                        println!("No cached percentage found for {}", collection_log_name);
-                        return None;
-                    }
-                    println!(
-                        "Cached clog matched successfully: {:?}, Completion percentage: {:?}",
-                        possible_percentage.unwrap().name.clone(),
-                        possible_percentage.unwrap().percentage.clone()
-                    );
-                    let collection_log_max_percentage = self
-                        .registered_guild
-                        .clone()
-                        .collection_log_max_percentage
-                        .unwrap();
-                    if possible_percentage.unwrap().percentage > collection_log_max_percentage {
-                        println!(
-                            "Collection log completion percentage {} for {} is greater than the guild max of {}",
-                            possible_percentage.unwrap().percentage, collection_log_name, collection_log_max_percentage
-                        );
-                        return None;
-                    }
-                }
-                let title = match self.leagues_message {
-                    true => ":bar_chart: New Leagues collection log item!".to_string(),
-                    false => ":tada: New collection log item!".to_string(),
-                };
-
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::ItemDrop,
-                    collection_log_broadcast.player_it_happened_to.clone(),
-                    collection_log_broadcast.item_name.clone(),
-                ) {
-                    return None;
-                }
-                Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::CollectionLog,
-                    player_it_happened_to: collection_log_broadcast.player_it_happened_to,
-                    message: self.clan_message.message.clone(),
-                    icon_url: collection_log_broadcast.item_icon,
-                    title,
-                    item_quantity: None,
-                })
-            }
-        }
+    /// The N in the items 1/N wiki drop rate
+    pub(crate) fn get_drop_rate(&self, item_name: &str) -> Option<f64> {
+        self.drop_rates
+            .as_ref()?
+            .get(&item_name.to_lowercase())
+            .copied()
     }
 
-    async fn personal_best_handler(&self) -> Option<BroadcastMessageToDiscord> {
-        let personal_best = personal_best_broadcast_extractor(self.clan_message.message.clone());
-        match personal_best {
-            None => {
-                error!(
-                    "Failed to extract Personal Best info from message: {}",
-                    self.clan_message.message.clone()
-                );
+    /// The broadcast icon is used if it shows an ironman type, otherwise falls back to the
+    /// account type saved from the clan mates own chats
+    async fn get_account_type(&self, player: String) -> Option<AccountType> {
+        if let Some(account_type) = self
+            .clan_message
+            .icon_id
+            .and_then(AccountType::from_icon_id)
+        {
+            return Some(account_type);
+        }
+        match self
+            .clan_mates_db
+            .find_by_current_name(self.registered_guild.guild_id, player)
+            .await
+        {
+            Ok(possible_clan_mate) => {
+                possible_clan_mate.and_then(|clan_mate| clan_mate.account_type)
+            }
+            Err(error) => {
+                error!("{:?}", error);
                 None
             }
-            Some(exported_data) => {
-                let is_disallowed = self
-                    .registered_guild
-                    .disallowed_broadcast_types
-                    .iter()
-                    .find(|&x| {
-                        if let BroadcastType::PersonalBest = x {
-                            return true;
-                        }
-                        false
-                    });
-                if is_disallowed.is_some() {
-                    return None;
-                }
-
-                let job = record_new_pb::new(exported_data.clone(), self.registered_guild.guild_id);
-                let _ = self.job_queue.send_task(job).await;
-
-                if self.check_if_filtered_broad_cast(
-                    BroadcastType::PersonalBest,
-                    exported_data.player.clone(),
-                    exported_data.activity.clone(),
-                ) {
-                    return None;
-                }
-
-                Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::PersonalBest,
-                    player_it_happened_to: exported_data.player,
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some(self.best_guest_pb_icon(exported_data.activity).to_string()),
-                    title: ":stopwatch: New Personal Best!".to_string(),
-                    item_quantity: None,
-                })
-            }
         }
     }
 
-    pub async fn extract_leagues_message(&self) -> Option<BroadcastMessageToDiscord> {
-        let possible_leagues_broadcast_type =
-            leagues_catch_all_broadcast_extractor(self.clan_message.message.clone());
-
-        if let Some(leagues_broadcast_type) = possible_leagues_broadcast_type {
-            //Bit odd to have same name enums in each but did it to keep commands
-            let full_broadcast = leagues_broadcast_type.to_broadcast_type();
-            let is_disallowed = self.check_if_allowed_broad_cast(full_broadcast);
-            info!(
-                "Leagues broadcast type: {:?} is disallowed: {}",
-                leagues_broadcast_type, is_disallowed
+    /// Runs the guilds broadcast rules, None when a rule denies the broadcast
+    pub(crate) async fn apply_broadcast_rules(
+        &self,
+        mut context: BroadcastRuleContext,
+        mut broadcast: BroadcastMessageToDiscord,
+    ) -> Option<BroadcastMessageToDiscord> {
+        if broadcast.template_values.item.is_none() {
+            broadcast.template_values.item = context.item_name.clone();
+        }
+        if broadcast.template_values.value.is_none() {
+            broadcast.template_values.value = context.value;
+        }
+        if let (Some(ge_high), Some(ge_low)) = (
+            broadcast.template_values.ge_high,
+            broadcast.template_values.ge_low,
+        ) {
+            broadcast.message = format!(
+                "{}\nGE price: {} high, {} low",
+                broadcast.message,
+                ge_high.to_formatted_string(&Locale::en),
+                ge_low.to_formatted_string(&Locale::en)
             );
-            if is_disallowed {
-                return None;
-            }
-
-            match leagues_broadcast_type {
-                LeaguesBroadCastType::AreaUnlock => Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::AreaUnlock,
-                    player_it_happened_to: "".to_string(),
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some("https://oldschool.runescape.wiki/images/Leagues_V-_Raging_Echoes_-_Summer_Summit_2024_%282%29.png?3877a".to_string()),
-                    title: ":new: New Leagues Area Unlock!".to_string(),
-                    item_quantity: None,
-                }),
-                LeaguesBroadCastType::LeaguesRank => Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::LeaguesRank,
-                    player_it_happened_to: "".to_string(),
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some("https://oldschool.runescape.wiki/images/thumb/Leagues_icon.png/260px-Leagues_icon.png?0570b".to_string()),
-                    title: ":new: New Leagues Rank Unlock!".to_string(),
-                    item_quantity: None,
-                }),
-                LeaguesBroadCastType::CombatMasteries => Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::CombatMasteries,
-                    player_it_happened_to: "".to_string(),
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some("https://oldschool.runescape.wiki/images/Raging_Echoes_League_combat_masteries_icon.png?4e2c2".to_string()),
-                    title: ":new: New Leagues Combat Mastery earned!".to_string(),
-                    item_quantity: None,
-                }),
-                LeaguesBroadCastType::RelicTier => Some(BroadcastMessageToDiscord {
-                    type_of_broadcast: BroadcastType::RelicTier,
-                    player_it_happened_to: "".to_string(),
-                    message: self.clan_message.message.clone(),
-                    icon_url: Some("https://oldschool.runescape.wiki/images/thumb/Leagues_icon.png/260px-Leagues_icon.png?0570b".to_string()),
-                    title: ":new: New Leagues Relic unlocked!".to_string(),
-                    item_quantity: None,
-                }),
-                //Ideally previous broadcast logic should catch this and we just come to leagues for the new types
-                LeaguesBroadCastType::NormalBroadCast => None,
-            }
-        } else {
-            None
         }
-    }
-
-    fn best_guest_pb_icon(&self, activity: String) -> String {
-        match activity.as_str().to_lowercase() {
-            x if x.contains("theatre of blood") => {
-                "https://oldschool.runescape.wiki/images/Theatre_of_Blood_logo.png".to_string()
-            }
-            x if x.contains("chambers of xeric") => {
-                "https://oldschool.runescape.wiki/images/Chambers_of_Xeric_logo.png".to_string()
-            }
-            x if x.contains("tombs of amascut") => {
-                "https://oldschool.runescape.wiki/images/Tombs_of_Amascut.png".to_string()
-            }
-            x if x.contains("colosseum") => {
-                "https://oldschool.runescape.wiki/images/Sol_Heredit.png".to_string()
-            }
-            x if x.contains("hueycoatl") => {
-                "https://oldschool.runescape.wiki/images/The_Hueycoatl.png".to_string()
-            }
-            x if x.contains("whisperer") => {
-                "https://oldschool.runescape.wiki/images/The_Whisperer.png".to_string()
-            }
-            x if x.contains("leviathan") => {
-                "https://oldschool.runescape.wiki/images/The_Leviathan.png".to_string()
-            }
-            x if x.contains("mimic") => {
-                "https://oldschool.runescape.wiki/images/The_Mimic.png".to_string()
-            }
-            x if x.contains("zulrah") => {
-                "https://oldschool.runescape.wiki/images/Zulrah_(serpentine).png".to_string()
-            }
-            x if x.contains("corrupted gauntlet") => {
-                "https://oldschool.runescape.wiki/images/Corrupted_Hunllef.png".to_string()
-            }
-            x if x.contains("gauntlet") => {
-                "https://oldschool.runescape.wiki/images/Crystalline_Hunllef.png".to_string()
-            }
-            x if x.contains("inferno") => {
-                "https://oldschool.runescape.wiki/images/TzKal-Zuk.png".to_string()
-            }
-            x if x.contains("fight caves") => {
-                "https://oldschool.runescape.wiki/images/TzTok-Jad.png".to_string()
-            }
-            x if x.contains("duke sucellus") => {
-                "https://oldschool.runescape.wiki/images/Duke_Sucellus.png".to_string()
-            }
-            x if x.contains("phantom muspah") => {
-                "https://oldschool.runescape.wiki/images/Phantom_Muspah_(ranged).png".to_string()
-            }
-            x if x.contains("royal titans") => {
-                "https://oldschool.runescape.wiki/images/Eldric_the_Ice_King.png".to_string()
-            }
-            x if x.contains("grotesque guardians") => {
-                "https://oldschool.runescape.wiki/images/Dusk_(2nd_form).png".to_string()
-            }
-            _ => format!("https://oldschool.runescape.wiki/images/{}.png", activity).to_string(),
+        if let Some(drop_rate) = context.drop_rate {
+            let drop_rate = format_drop_rate(drop_rate);
+            broadcast.message = format!("{}\nDrop rate: {}", broadcast.message, drop_rate);
+            broadcast.template_values.rate = Some(drop_rate);
         }
-    }
-
-    fn check_if_allowed_broad_cast(&self, broadcast_type: BroadcastType) -> bool {
-        let is_disallowed = self
+        let rules = self
             .registered_guild
-            .disallowed_broadcast_types
-            .iter()
-            .find(|&x| {
-                if broadcast_type.to_string() == x.to_string() {
-                    return true;
-                }
-                false
-            });
-        if is_disallowed.is_some() {
-            return true;
+            .broadcast_rules
+            .clone()
+            .unwrap_or_default();
+        if rules.iter().any(|rule| rule.uses_account_type()) {
+            context.account_type = self.get_account_type(context.player.clone()).await;
+        }
+        broadcast.ping_role_id = role_to_ping(&self.registered_guild, &context);
+        match evaluate_broadcast_rules(&rules, &context) {
+            RuleAction::Allow => Some(broadcast),
+            RuleAction::Deny => {
+                println!(
+                    "Filtered out {} broadcast by the guild rules: Player={}, Item={:?}",
+                    broadcast.type_of_broadcast.to_string(),
+                    broadcast.player_it_happened_to,
+                    context.item_name
+                );
+                None
+            }
+            RuleAction::Route(channel_id) => {
+                broadcast.route_to_channel = Some(channel_id);
+                Some(broadcast)
+            }
         }
-        false
     }
 
-    fn check_if_filtered_broad_cast(
+    /// For broadcasts that only have a type and player for the rules to check
+    pub(crate) async fn apply_basic_broadcast_rules(
         &self,
-        broadcast_type: BroadcastType,
-        broadcast_player: String,
-        broadcast_element: String,
-    ) -> bool {
-        if let Some(ref filter_map) = self.registered_guild.custom_drop_broadcast_filter {
-            if let Some(filter_list) = filter_map.get(&broadcast_type) {
-                let broadcast_element_lower = broadcast_element.to_lowercase();
-                for filter in filter_list {
-                    if broadcast_element_lower.contains(&filter.to_lowercase()) {
-                        println!(
-                            "Filtered out {} broadcast: Player={}, Item={}",
-                            broadcast_type.to_string(),
-                            broadcast_player.clone(),
-                            broadcast_element.clone()
-                        );
-                        return true;
-                    }
-                }
-            } else {
-                return false;
-            }
-        }
-        false
+        broadcast: BroadcastMessageToDiscord,
+    ) -> Option<BroadcastMessageToDiscord> {
+        let context = BroadcastRuleContext::new(
+            broadcast.type_of_broadcast.clone(),
+            broadcast.player_it_happened_to.clone(),
+        );
+        self.apply_broadcast_rules(context, broadcast).await
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::broadcast_processors::achievements::clan_record_broadcast;
+    use crate::broadcast_rules::{
+        account_type_filter_rules, drop_rarity_rules, min_tier_rules, toggle_rules,
+        value_threshold_rules, BroadcastRule, RuleCondition, RuleSetting,
+    };
     use crate::database::clan_mate_collection_log_totals::MockClanMateCollectionLogTotals;
-    use crate::database::clan_mates::MockClanMates;
+    use crate::database::clan_mates::{ClanMateModel, MockClanMates};
     use crate::database::drop_logs_db::MockDropLogs;
-    use crate::ge_api::ge_api::GetItem;
-    use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{DiaryTier, QuestDifficulty};
+    use crate::database::pb_records_db::{ClanRecord, MockPersonalBests};
+    use crate::embed_templates::{render_guild_broadcast_embed, EmbedTemplate, TemplateImage};
+    use crate::ge_api::ge_api::{DropPriceSource, GetItem};
+    use crate::osrs_broadcast_extractor::osrs_broadcast_extractor::{
+        diary_completed_broadcast_extractor, drop_broadcast_extractor,
+        loot_key_broadcast_extractor, pk_broadcast_extractor, quest_completed_broadcast_extractor,
+        CombatAchievementTier, DiaryTier, PersonalBestBroadcast, QuestDifficulty,
+        HARDCORE_IRONMAN_ICON_ID, IRONMAN_ICON_ID,
+    };
+    use crate::role_pings::RolePing;
     use async_trait::async_trait;
     use celery::error::CeleryError;
     use celery::prelude::Task;
@@ -1227,6 +326,7 @@ mod tests {
             })
         }
     }
+
     #[tokio::test]
     async fn test_drop_item_handler_no_message_sent() {
         let clan_message = ClanMessage {
@@ -1240,7 +340,10 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.drop_price_threshold = Some(20_000_000);
+        registered_guild.set_setting_rules(
+            RuleSetting::DropPriceThreshold,
+            value_threshold_rules(RuleSetting::DropPriceThreshold, 20_000_000),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1260,8 +363,9 @@ mod tests {
         let clogs = Ok(Vec::new());
 
         let mut drop_log_db_mock = MockDropLogs::new();
-        drop_log_db_mock.expect_new_drop_log().returning(|_, _| {
+        drop_log_db_mock.expect_new_drop_log().returning(|_, _, _, _| {
             info!("Should not be calling this function");
+            Ok(())
         });
 
         let mock_job_queue = MockJobQueue::new();
@@ -1269,17 +373,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             drop_log_db_mock,
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.drop_item_handler().await;
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1305,7 +412,10 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.drop_price_threshold = Some(20_000_000);
+        registered_guild.set_setting_rules(
+            RuleSetting::DropPriceThreshold,
+            value_threshold_rules(RuleSetting::DropPriceThreshold, 20_000_000),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1324,8 +434,9 @@ mod tests {
         let clogs = Ok(Vec::new());
 
         let mut drop_log_db_mock = MockDropLogs::new();
-        drop_log_db_mock.expect_new_drop_log().returning(|_, _| {
+        drop_log_db_mock.expect_new_drop_log().returning(|_, _, _, _| {
             info!("Should not be calling this function");
+            Ok(())
         });
 
         let mock_job_queue = MockJobQueue::new();
@@ -1333,17 +444,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             drop_log_db_mock,
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.drop_item_handler().await;
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1356,6 +470,161 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_drop_item_handler_account_type_threshold() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player received a drop: Dragon pickaxe (5,203,114 coins)."
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::DropPriceThreshold,
+            value_threshold_rules(RuleSetting::DropPriceThreshold, 20_000_000),
+        );
+        let setting = RuleSetting::AccountTypeDropPriceThreshold(AccountType::Ironman);
+        registered_guild
+            .set_setting_rules(setting.clone(), value_threshold_rules(setting, 1_000_000));
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .returning(|_, _, _, _| Ok(()));
+
+        //No icon on the broadcast so the saved account type is used
+        let mut clan_mates_mock = MockClanMates::new();
+        clan_mates_mock
+            .expect_find_by_current_name()
+            .returning(|guild_id, player_name| {
+                let mut clan_mate = ClanMateModel::new(guild_id, player_name, None);
+                clan_mate.account_type = Some(AccountType::Ironman);
+                Ok(Some(clan_mate))
+            });
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            clan_mates_mock,
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        match extracted_message {
+            None => {
+                println!("Ironman threshold should of been used. Should be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(message) => {
+                assert_eq!(message.player_it_happened_to, "RuneScape Player");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_account_type_filter_do_not_send() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player received a drop: Dragon pickaxe (5,203,114 coins)."
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: Some(IRONMAN_ICON_ID),
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::AccountTypeFilter(BroadcastType::ItemDrop),
+            account_type_filter_rules(BroadcastType::ItemDrop, vec![AccountType::UltimateIronman]),
+        );
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .returning(|_, _, _, _| Ok(()));
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        match extracted_message {
+            None => {
+                assert_eq!(true, true);
+            }
+            Some(_) => {
+                println!("Only ultimate ironmen drops should be sent.");
+                assert_eq!(true, false);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pet_drop_handler_duplicate() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "Runescape Vision has a funny feeling like she would have been followed: Heron at 11,212,255 XP.".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            RegisteredGuildModel::new(123),
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        match extracted_message {
+            None => {
+                println!("Should be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(message) => {
+                assert_eq!(message.title, ":dog: New duplicate Pet drop!");
+                assert_eq!(message.item_quantity, Some(11_212_255));
+            }
+        }
+    }
+
     #[tokio::test]
     async fn check_disallowed_do_not_send() {
         let clan_message = ClanMessage {
@@ -1369,7 +638,10 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.disallowed_broadcast_types = vec![BroadcastType::ItemDrop];
+        registered_guild.set_setting_rules(
+            RuleSetting::Toggle,
+            toggle_rules(vec![BroadcastType::ItemDrop]),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1388,8 +660,9 @@ mod tests {
         let clogs = Ok(Vec::new());
 
         let mut drop_log_db_mock = MockDropLogs::new();
-        drop_log_db_mock.expect_new_drop_log().returning(|_, _| {
+        drop_log_db_mock.expect_new_drop_log().returning(|_, _, _, _| {
             info!("Should not be calling this function");
+            Ok(())
         });
 
         let mock_job_queue = MockJobQueue::new();
@@ -1397,17 +670,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             drop_log_db_mock,
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.drop_item_handler().await;
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1453,8 +729,9 @@ mod tests {
         let clogs = Ok(Vec::new());
 
         let mut drop_log_db_mock = MockDropLogs::new();
-        drop_log_db_mock.expect_new_drop_log().returning(|_, _| {
+        drop_log_db_mock.expect_new_drop_log().returning(|_, _, _, _| {
             info!("Should not be calling this function");
+            Ok(())
         });
 
         let mock_job_queue = MockJobQueue::new();
@@ -1462,17 +739,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             drop_log_db_mock,
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.drop_item_handler().await;
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1499,7 +779,13 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.min_quest_difficulty = Some(QuestDifficulty::Intermediate);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinQuestDifficulty,
+            min_tier_rules(
+                RuleSetting::MinQuestDifficulty,
+                QuestDifficulty::Intermediate.ranking(),
+            ),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1526,17 +812,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             MockDropLogs::new(),
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.quest_handler();
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1562,7 +851,13 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.min_quest_difficulty = Some(QuestDifficulty::Master);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinQuestDifficulty,
+            min_tier_rules(
+                RuleSetting::MinQuestDifficulty,
+                QuestDifficulty::Master.ranking(),
+            ),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1590,17 +885,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             MockDropLogs::new(),
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.quest_handler();
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -1626,7 +924,10 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.pk_value_threshold = Some(1_000_000);
+        registered_guild.set_setting_rules(
+            RuleSetting::PkValueThreshold,
+            value_threshold_rules(RuleSetting::PkValueThreshold, 1_000_000),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1649,17 +950,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             MockDropLogs::new(),
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.pk_handler();
+        let extracted_message = handler.extract_message().await;
         println!("Extracted message: {:?}", extracted_message);
 
         match extracted_message {
@@ -1674,10 +978,10 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_diary_handler_message_sent() {
+    async fn test_loot_key_handler_low() {
         let clan_message = ClanMessage {
             sender: "Insomniacs".to_string(),
-            message: "RuneScape Player has completed the Hard Ardougne diary.".to_string(),
+            message: "Med-iocore has opened a loot key worth 489,181 coins!".to_string(),
             clan_name: "Insomniacs".to_string(),
             rank: "Recruit".to_string(),
             icon_id: None,
@@ -1685,58 +989,70 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.min_diary_tier = Some(DiaryTier::Hard);
+        registered_guild.set_setting_rules(
+            RuleSetting::LootKeyValueThreshold,
+            value_threshold_rules(RuleSetting::LootKeyValueThreshold, 1_000_000),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
-        let quests = Ok(Vec::new());
-        let clogs = Ok(Vec::new());
         //Saintly checker do not know how to do mock in rust yet. So this makes sure the above message
         //Is valid to trip the extractor and give the expect result
-        let sanity_check = diary_completed_broadcast_extractor(clan_message.message.clone());
+        let sanity_check = loot_key_broadcast_extractor(clan_message.message.clone());
         match sanity_check {
             None => {
-                info!("Sanity check failed. The message is not valid or the extractor is broken and that unit test should also be failing");
+                println!("Sanity check failed. The message is not valid or the extractor is broken and that unit test should also be failing");
                 assert_eq!(true, false);
             }
             Some(_) => {
-                assert_eq!(true, true);
+                println!("Sanity check success");
             }
         }
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .returning(|_, _, _, _| Ok(()));
 
         let mock_job_queue = MockJobQueue::new();
 
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
-            MockDropLogs::new(),
+            drop_log_db_mock,
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.diary_handler();
-        info!("Extracted message: {:?}", extracted_message);
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
         match extracted_message {
             None => {
-                info!("Threshold should of not been hit. Should  be sending a message.");
-                assert_eq!(true, false);
+                println!("Successfully stopped message from sending");
             }
             Some(_) => {
-                assert_eq!(true, true);
+                println!("Should not be sending a message.");
+                assert_eq!(true, false);
             }
         }
     }
 
     #[tokio::test]
-    async fn test_diary_handler_message_not_sent() {
+    async fn test_loot_key_handler_message_sent() {
         let clan_message = ClanMessage {
             sender: "Insomniacs".to_string(),
-            message: "RuneScape Player has completed the Easy Ardougne diary.".to_string(),
+            message: "KANlEL OUTIS has opened a loot key worth 1,148,040 coins!".to_string(),
             clan_name: "Insomniacs".to_string(),
             rank: "Recruit".to_string(),
             icon_id: None,
@@ -1744,7 +1060,1025 @@ mod tests {
         };
 
         let mut registered_guild = RegisteredGuildModel::new(123);
-        registered_guild.min_diary_tier = Some(DiaryTier::Hard);
+        registered_guild.set_setting_rules(
+            RuleSetting::LootKeyValueThreshold,
+            value_threshold_rules(RuleSetting::LootKeyValueThreshold, 1_000_000),
+        );
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
+        match extracted_message {
+            None => {
+                println!("Threshold should of not been hit. Should be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(message) => {
+                assert_eq!(message.item_quantity, Some(1));
+                assert_eq!(message.template_values.value, Some(1_148_040));
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_clue_item_logged_to_loot_ledger() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message:
+                "RuneScape Player received a clue item: Saradomin d'hide body (136,662 coins)."
+                    .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .withf(|clue_item, broadcast_type, ge_price, guild_id| {
+                clue_item.item_name == "Saradomin d'hide body"
+                    && *broadcast_type == BroadcastType::ClueItem
+                    && ge_price.is_none()
+                    && *guild_id == 123
+            })
+            .times(1)
+            .returning(|_, _, _, _| Ok(()));
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            RegisteredGuildModel::new(123),
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        assert!(extracted_message.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_rule_allows_before_threshold() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player: bob received a drop: Rune scimitar (15,000 coins)."
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.broadcast_rules = Some(vec![BroadcastRule {
+            conditions: vec![
+                RuleCondition::BroadcastType(vec![BroadcastType::ItemDrop]),
+                RuleCondition::ItemMatches("^rune ".to_string()),
+            ],
+            action: RuleAction::Allow,
+            setting: None,
+        }]);
+        registered_guild.set_setting_rules(
+            RuleSetting::DropPriceThreshold,
+            value_threshold_rules(RuleSetting::DropPriceThreshold, 1_000_000),
+        );
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .returning(|_, _, _, _| Ok(()));
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        assert!(extracted_message.is_some());
+        assert_eq!(extracted_message.unwrap().route_to_channel, None);
+    }
+
+    #[tokio::test]
+    async fn test_embed_template_fills_drop_placeholders() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player received a drop: Cool Item (20,456,814 coins).".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        let mut templates = std::collections::HashMap::new();
+        templates.insert(
+            BroadcastType::ItemDrop,
+            EmbedTemplate {
+                title: Some("{player} got {item}!".to_string()),
+                description: Some("Worth {value} gp{kc}".to_string()),
+                color: Some(0xFF0000),
+                image: Some(TemplateImage::Thumbnail),
+            },
+        );
+        registered_guild.embed_templates = Some(templates);
+
+        let mut drop_log_db_mock = MockDropLogs::new();
+        drop_log_db_mock
+            .expect_new_drop_log()
+            .returning(|_, _, _, _| Ok(()));
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            registered_guild.clone(),
+            false,
+            drop_log_db_mock,
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await.unwrap();
+        let rendered_embed = render_guild_broadcast_embed(&registered_guild, &extracted_message);
+        assert_eq!(rendered_embed.title, "RuneScape Player got Cool Item!");
+        assert_eq!(rendered_embed.description, "Worth 20,456,814 gp");
+        assert_eq!(rendered_embed.color, 0xFF0000);
+        assert_eq!(rendered_embed.image_url, None);
+        assert_eq!(rendered_embed.thumbnail_url, extracted_message.icon_url);
+    }
+
+    #[tokio::test]
+    async fn test_drop_threshold_uses_guild_price_source() {
+        for (price_source, expected_message) in [
+            (
+                DropPriceSource::GeHigh,
+                Some("RuneScape Player received a drop: Cool Item (20,456 coins).\nGE price: 2,000,000 high, 500,000 low"),
+            ),
+            (DropPriceSource::GeLow, None),
+        ] {
+            let mut registered_guild = RegisteredGuildModel::new(123);
+            registered_guild.set_setting_rules(
+                RuleSetting::DropPriceThreshold,
+                value_threshold_rules(RuleSetting::DropPriceThreshold, 1_000_000),
+            );
+            registered_guild.drop_price_source = Some(price_source);
+
+            let clan_message = ClanMessage {
+                sender: "Insomniacs".to_string(),
+                message: "RuneScape Player received a drop: Cool Item (20,456 coins).".to_string(),
+                clan_name: "Insomniacs".to_string(),
+                rank: "Recruit".to_string(),
+                icon_id: None,
+                is_league_world: None,
+            };
+            //The ledger keeps the GE price whatever price source the guild uses
+            let mut drop_log_db_mock = MockDropLogs::new();
+            drop_log_db_mock
+                .expect_new_drop_log()
+                .withf(|_, _, ge_price, _| *ge_price == Some(2_000_000))
+                .times(1)
+                .returning(|_, _, _, _| Ok(()));
+            let mut prices = std::collections::HashMap::new();
+            prices.insert(
+                1,
+                GeItemPrice {
+                    high: 2_000_000,
+                    high_time: 0,
+                    low: 500_000,
+                    low_time: 0,
+                },
+            );
+
+            let handler = OSRSBroadcastHandler::new(
+                clan_message,
+                Ok(vec![GetItem {
+                    name: "Cool Item".to_string(),
+                    id: 1,
+                    ..GetItem::default()
+                }]),
+                Ok(prices),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                registered_guild,
+                false,
+                drop_log_db_mock,
+                MockClanMateCollectionLogTotals::new(),
+                MockClanMates::new(),
+                MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+            );
+
+            let extracted_message = handler.extract_message().await;
+            assert_eq!(
+                extracted_message.map(|broadcast| broadcast.message),
+                expected_message.map(|message| message.to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_raid_drop_price_threshold() {
+        for (price_source, ge_high, expected_title) in [
+            (DropPriceSource::Broadcast, 2_000_000, Some(":tada: New raid drop!")),
+            (DropPriceSource::Broadcast, 500_000, None),
+            (DropPriceSource::GeHigh, 2_000_000, Some(":tada: New raid drop!")),
+            (DropPriceSource::GeLow, 2_000_000, None),
+        ] {
+            let mut registered_guild = RegisteredGuildModel::new(123);
+            registered_guild.set_setting_rules(
+                RuleSetting::DropPriceThreshold,
+                value_threshold_rules(RuleSetting::DropPriceThreshold, 1_000_000),
+            );
+            registered_guild.drop_price_source = Some(price_source);
+
+            let clan_message = ClanMessage {
+                sender: "Insomniacs".to_string(),
+                message: "RuneScape Player received special loot from a raid: Cool Item."
+                    .to_string(),
+                clan_name: "Insomniacs".to_string(),
+                rank: "Recruit".to_string(),
+                icon_id: None,
+                is_league_world: None,
+            };
+            let mut drop_log_db_mock = MockDropLogs::new();
+            drop_log_db_mock
+                .expect_new_drop_log()
+                .returning(|_, _, _, _| Ok(()));
+            let mut prices = std::collections::HashMap::new();
+            prices.insert(
+                1,
+                GeItemPrice {
+                    high: ge_high,
+                    high_time: 0,
+                    low: ge_high / 4,
+                    low_time: 0,
+                },
+            );
+
+            let handler = OSRSBroadcastHandler::new(
+                clan_message,
+                Ok(vec![GetItem {
+                    name: "Cool Item".to_string(),
+                    id: 1,
+                    ..GetItem::default()
+                }]),
+                Ok(prices),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                registered_guild,
+                false,
+                drop_log_db_mock,
+                MockClanMateCollectionLogTotals::new(),
+                MockClanMates::new(),
+                MockPersonalBests::new(),
+                Arc::from(MockJobQueue::new()),
+            );
+
+            let extracted_message = handler.extract_message().await;
+            assert_eq!(
+                extracted_message.map(|broadcast| broadcast.title),
+                expected_title.map(|title| title.to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_drop_rarity_threshold_overrides_value_threshold() {
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::DropPriceThreshold,
+            value_threshold_rules(RuleSetting::DropPriceThreshold, 1_000_000),
+        );
+        registered_guild
+            .set_setting_rules(RuleSetting::DropRarityThreshold, drop_rarity_rules(1000.0));
+
+        for (message, expected_message) in [
+            (
+                "RuneScape Player received a drop: Rare Item (20,456 coins).",
+                Some("RuneScape Player received a drop: Rare Item (20,456 coins).\nDrop rate: 1/5,000"),
+            ),
+            (
+                "RuneScape Player received a drop: Common Item (20,456,814 coins).",
+                None,
+            ),
+        ] {
+            let clan_message = ClanMessage {
+                sender: "Insomniacs".to_string(),
+                message: message.to_string(),
+                clan_name: "Insomniacs".to_string(),
+                rank: "Recruit".to_string(),
+                icon_id: None,
+                is_league_world: None,
+            };
+            let mut drop_log_db_mock = MockDropLogs::new();
+            drop_log_db_mock
+                .expect_new_drop_log()
+                .returning(|_, _, _, _| Ok(()));
+
+            let handler = OSRSBroadcastHandler::new(
+                clan_message,
+                Ok(Vec::new()),
+                Ok(std::collections::HashMap::new()),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                Ok(vec![
+                    WikiDropRate {
+                        name: "Rare Item".to_string(),
+                        one_in: 5000.0,
+                    },
+                    WikiDropRate {
+                        name: "Common Item".to_string(),
+                        one_in: 128.0,
+                    },
+                ]),
+                registered_guild.clone(),
+                false,
+                drop_log_db_mock,
+                MockClanMateCollectionLogTotals::new(),
+                MockClanMates::new(),
+                MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+            );
+
+            let extracted_message = handler.extract_message().await;
+            assert_eq!(
+                extracted_message.map(|broadcast| broadcast.message),
+                expected_message.map(|message| message.to_string())
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_role_ping_only_set_when_conditions_match() {
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        let mut role_pings = std::collections::HashMap::new();
+        role_pings.insert(
+            BroadcastType::ItemDrop,
+            RolePing {
+                role_id: 456,
+                conditions: vec![RuleCondition::ValueBetween {
+                    min: Some(10_000_000),
+                    max: None,
+                }],
+                cooldown_seconds: None,
+            },
+        );
+        registered_guild.role_pings = Some(role_pings);
+
+        for (message, expected_role) in [
+            (
+                "RuneScape Player received a drop: Cool Item (20,456,814 coins).",
+                Some(456),
+            ),
+            (
+                "RuneScape Player received a drop: Cool Item (20,456 coins).",
+                None,
+            ),
+        ] {
+            let clan_message = ClanMessage {
+                sender: "Insomniacs".to_string(),
+                message: message.to_string(),
+                clan_name: "Insomniacs".to_string(),
+                rank: "Recruit".to_string(),
+                icon_id: None,
+                is_league_world: None,
+            };
+            let mut drop_log_db_mock = MockDropLogs::new();
+            drop_log_db_mock
+                .expect_new_drop_log()
+                .returning(|_, _, _, _| Ok(()));
+
+            let handler = OSRSBroadcastHandler::new(
+                clan_message,
+                Ok(Vec::new()),
+                Ok(std::collections::HashMap::new()),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                Ok(Vec::new()),
+                registered_guild.clone(),
+                false,
+                drop_log_db_mock,
+                MockClanMateCollectionLogTotals::new(),
+                MockClanMates::new(),
+                MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+            );
+
+            let extracted_message = handler.extract_message().await.unwrap();
+            assert_eq!(extracted_message.ping_role_id, expected_role);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_rule_routes_player() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has completed a quest: Cook's Assistant".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.broadcast_rules = Some(vec![
+            BroadcastRule {
+                conditions: vec![RuleCondition::Player(vec!["someone else".to_string()])],
+                action: RuleAction::Deny,
+                setting: None,
+            },
+            BroadcastRule {
+                conditions: vec![RuleCondition::Player(vec!["runescape player".to_string()])],
+                action: RuleAction::Route(456),
+                setting: None,
+            },
+        ]);
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        assert_eq!(extracted_message.unwrap().route_to_channel, Some(456));
+    }
+
+    fn personal_best_handler(
+        personal_bests_mock: MockPersonalBests,
+    ) -> OSRSBroadcastHandler<
+        MockDropLogs,
+        MockClanMateCollectionLogTotals,
+        MockClanMates,
+        MockPersonalBests,
+        MockJobQueue,
+    > {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has achieved a new Vorkath personal best: 2:28".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut clan_mates_mock = MockClanMates::new();
+        clan_mates_mock
+            .expect_find_by_current_name()
+            .returning(|guild_id, player_name| {
+                Ok(Some(ClanMateModel::new(guild_id, player_name, None)))
+            });
+
+        OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            RegisteredGuildModel::new(123),
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            clan_mates_mock,
+            personal_bests_mock,
+            Arc::from(MockJobQueue::new()),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_personal_best_handler_shows_time_change() {
+        let mut personal_bests_mock = MockPersonalBests::new();
+        personal_bests_mock
+            .expect_get_personal_best()
+            .times(1)
+            .withf(|_, activity_name| activity_name == "Vorkath")
+            .returning(|_, _| Ok(Some(151.4)));
+
+        let handler = personal_best_handler(personal_bests_mock);
+        let message = handler.extract_message().await.unwrap();
+        assert_eq!(message.type_of_broadcast, BroadcastType::PersonalBest);
+        assert_eq!(
+            message.message,
+            "RuneScape Player has achieved a new Vorkath personal best: 2:28\n2:31.40 → 2:28.00 (-3.40s)"
+        );
+        assert_eq!(
+            message.template_values.previous_time,
+            Some("2:31.40".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_personal_best_handler_hides_slower_time() {
+        let mut personal_bests_mock = MockPersonalBests::new();
+        personal_bests_mock
+            .expect_get_personal_best()
+            .times(1)
+            .returning(|_, _| Ok(Some(140.0)));
+
+        let handler = personal_best_handler(personal_bests_mock);
+        let message = handler.extract_message().await.unwrap();
+        assert_eq!(
+            message.message,
+            "RuneScape Player has achieved a new Vorkath personal best: 2:28"
+        );
+        assert_eq!(message.template_values.previous_time, None);
+    }
+
+    #[tokio::test]
+    async fn test_clan_record_broadcast() {
+        let handler = personal_best_handler(MockPersonalBests::new());
+        let personal_best = PersonalBestBroadcast {
+            player: "RuneScape Player".to_string(),
+            activity: "Vorkath".to_string(),
+            variant: None,
+            time_in_seconds: 148.0,
+        };
+        let clan_record = ClanRecord {
+            player_name: "Zezima".to_string(),
+            time_in_seconds: 150.0,
+        };
+
+        let message = clan_record_broadcast(&handler, &personal_best, &clan_record)
+            .await
+            .unwrap();
+        assert_eq!(message.type_of_broadcast, BroadcastType::ClanRecord);
+        assert_eq!(message.title, ":trophy: New Clan Record!");
+        assert!(message
+            .message
+            .ends_with("Clan record: Zezima 2:30.00 → RuneScape Player 2:28.00 (-2.00s)"));
+    }
+
+    #[tokio::test]
+    async fn test_hardcore_death_handler_marks_clan_mate() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "Hardcore Iron has died and lost their Hardcore Ironman status!".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: Some(HARDCORE_IRONMAN_ICON_ID),
+            is_league_world: None,
+        };
+
+        let registered_guild = RegisteredGuildModel::new(123);
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+
+        let mut clan_mates_mock = MockClanMates::new();
+        clan_mates_mock
+            .expect_find_or_create_clan_mate()
+            .times(1)
+            .returning(|guild_id, player_name| Ok(ClanMateModel::new(guild_id, player_name, None)));
+        clan_mates_mock
+            .expect_update_clan_mate()
+            .times(1)
+            .withf(|clan_mate| {
+                clan_mate.lost_hardcore_status_at.is_some()
+                    && clan_mate.account_type == Some(AccountType::Ironman)
+            })
+            .returning(|clan_mate| Ok(clan_mate));
+
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            clan_mates_mock,
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
+        match extracted_message {
+            None => {
+                println!("Should be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(message) => {
+                assert_eq!(message.player_it_happened_to, "Hardcore Iron");
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_hardcore_death_handler_wrong_icon() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "Hardcore Iron has died and lost their Hardcore Ironman status!".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: Some(2),
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::Toggle,
+            toggle_rules(vec![BroadcastType::HardcoreDeath]),
+        );
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+
+        //No expectations set, the clan mate should not be touched
+        let clan_mates_mock = MockClanMates::new();
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            clan_mates_mock,
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
+        match extracted_message {
+            None => {
+                println!("Successfully stopped message from sending");
+            }
+            Some(_) => {
+                println!("Should not be sending a message.");
+                assert_eq!(true, false);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_combat_task_handler_message_sent() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has completed an elite combat task: Perfect Zulrah."
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinCombatAchievementTier,
+            min_tier_rules(
+                RuleSetting::MinCombatAchievementTier,
+                CombatAchievementTier::Hard.ranking(),
+            ),
+        );
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
+        match extracted_message {
+            None => {
+                println!("Tier is above the min. Should be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(_) => {}
+        }
+    }
+
+    #[tokio::test]
+    async fn test_combat_achievement_tier_handler_message_not_sent() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message:
+                "RuneScape Player has unlocked the Medium tier of rewards from Combat Achievements!"
+                    .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinCombatAchievementTier,
+            min_tier_rules(
+                RuleSetting::MinCombatAchievementTier,
+                CombatAchievementTier::Elite.ranking(),
+            ),
+        );
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        println!("Extracted message: {:?}", extracted_message);
+
+        match extracted_message {
+            None => {
+                println!("Successfully stopped message from sending");
+            }
+            Some(_) => {
+                println!("Tier is below the min. Should not be sending a message.");
+                assert_eq!(true, false);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_level_milestone_handler_max_combat_level() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "Zillamanjaro has reached the highest possible combat level of 126!"
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            Ok(Vec::new()),
+            Ok(std::collections::HashMap::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            Ok(Vec::new()),
+            RegisteredGuildModel::new(123),
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(MockJobQueue::new()),
+        );
+
+        let extracted_message = handler
+            .extract_message()
+            .await
+            .expect("A combat level milestone should be sent");
+        assert_eq!(extracted_message.title, ":trophy: Max combat level reached!");
+        assert_eq!(
+            extracted_message.icon_url,
+            Some("https://oldschool.runescape.wiki/images/Combat_icon.png".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relic_tier_handler_message_sent() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has unlocked their tier 3 League relic: Fairy's Flight!"
+                .to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: Some(true),
+        };
+
+        let registered_guild = RegisteredGuildModel::new(123);
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            true,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_leagues_message().await;
+        match extracted_message {
+            None => {
+                println!("Relic tier broadcast should be sent.");
+                assert_eq!(true, false);
+            }
+            Some(message) => {
+                assert_eq!(message.type_of_broadcast, BroadcastType::RelicTier);
+                assert_eq!(message.player_it_happened_to, "RuneScape Player");
+                assert_eq!(message.item_quantity, Some(3));
+                assert_eq!(
+                    message.icon_url,
+                    Some(
+                        "https://oldschool.runescape.wiki/images/Fairy%27s_Flight_detail.png"
+                            .to_string()
+                    )
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diary_handler_message_sent() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has completed the Hard Ardougne diary.".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinDiaryTier,
+            min_tier_rules(RuleSetting::MinDiaryTier, DiaryTier::Hard.ranking()),
+        );
+        let ge_item_mapping: Vec<GetItem> = Vec::new();
+        let get_item_mapping = Ok(ge_item_mapping);
+
+        let quests = Ok(Vec::new());
+        let clogs = Ok(Vec::new());
+        //Saintly checker do not know how to do mock in rust yet. So this makes sure the above message
+        //Is valid to trip the extractor and give the expect result
+        let sanity_check = diary_completed_broadcast_extractor(clan_message.message.clone());
+        match sanity_check {
+            None => {
+                info!("Sanity check failed. The message is not valid or the extractor is broken and that unit test should also be failing");
+                assert_eq!(true, false);
+            }
+            Some(_) => {
+                assert_eq!(true, true);
+            }
+        }
+
+        let mock_job_queue = MockJobQueue::new();
+
+        let handler = OSRSBroadcastHandler::new(
+            clan_message,
+            get_item_mapping,
+            Ok(std::collections::HashMap::new()),
+            quests,
+            clogs,
+            Ok(Vec::new()),
+            registered_guild,
+            false,
+            MockDropLogs::new(),
+            MockClanMateCollectionLogTotals::new(),
+            MockClanMates::new(),
+            MockPersonalBests::new(),
+            Arc::from(mock_job_queue),
+        );
+
+        let extracted_message = handler.extract_message().await;
+        info!("Extracted message: {:?}", extracted_message);
+        match extracted_message {
+            None => {
+                info!("Threshold should of not been hit. Should  be sending a message.");
+                assert_eq!(true, false);
+            }
+            Some(_) => {
+                assert_eq!(true, true);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_diary_handler_message_not_sent() {
+        let clan_message = ClanMessage {
+            sender: "Insomniacs".to_string(),
+            message: "RuneScape Player has completed the Easy Ardougne diary.".to_string(),
+            clan_name: "Insomniacs".to_string(),
+            rank: "Recruit".to_string(),
+            icon_id: None,
+            is_league_world: None,
+        };
+
+        let mut registered_guild = RegisteredGuildModel::new(123);
+        registered_guild.set_setting_rules(
+            RuleSetting::MinDiaryTier,
+            min_tier_rules(RuleSetting::MinDiaryTier, DiaryTier::Hard.ranking()),
+        );
         let ge_item_mapping: Vec<GetItem> = Vec::new();
         let get_item_mapping = Ok(ge_item_mapping);
 
@@ -1768,17 +2102,20 @@ mod tests {
         let handler = OSRSBroadcastHandler::new(
             clan_message,
             get_item_mapping,
+            Ok(std::collections::HashMap::new()),
             quests,
             clogs,
+            Ok(Vec::new()),
             registered_guild,
             false,
             MockDropLogs::new(),
             MockClanMateCollectionLogTotals::new(),
             MockClanMates::new(),
+            MockPersonalBests::new(),
             Arc::from(mock_job_queue),
         );
 
-        let extracted_message = handler.diary_handler();
+        let extracted_message = handler.extract_message().await;
         info!("Extracted message: {:?}", extracted_message);
         match extracted_message {
             None => {
@@ -0,0 +1,228 @@
+use crate::database::clan_mates::{ClanMateModel, ClanMates};
+use crate::database::guilds_db::RegisteredGuildModel;
+use crate::database::BotMongoDb;
+use crate::discord_api::DiscordApiClient;
+use log::error;
+use std::collections::HashMap;
+
+/// The roles a discord member needs added or removed to match their clan ranks
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoleChange {
+    pub discord_user_id: u64,
+    pub player_names: Vec<String>,
+    pub add_roles: Vec<u64>,
+    pub remove_roles: Vec<u64>,
+}
+
+impl RoleChange {
+    pub fn is_empty(&self) -> bool {
+        self.add_roles.is_empty() && self.remove_roles.is_empty()
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut changes: Vec<String> = Vec::new();
+        if !self.add_roles.is_empty() {
+            changes.push(format!("add {}", role_mentions(&self.add_roles)));
+        }
+        if !self.remove_roles.is_empty() {
+            changes.push(format!("remove {}", role_mentions(&self.remove_roles)));
+        }
+        let player_names = match self.player_names.is_empty() {
+            true => "not linked".to_string(),
+            false => self.player_names.join(", "),
+        };
+        format!(
+            "<@{}> ({}): {}",
+            self.discord_user_id,
+            player_names,
+            changes.join(", ")
+        )
+    }
+}
+
+fn role_mentions(role_ids: &[u64]) -> String {
+    role_ids
+        .iter()
+        .map(|role_id| format!("<@&{}>", role_id))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Works out the role changes for one discord member from every clan mate they have linked.
+/// Only roles in the rank mapping are ever added or removed
+pub fn plan_member_role_changes(
+    rank_roles: &HashMap<String, u64>,
+    discord_user_id: u64,
+    linked_clan_mates: &[ClanMateModel],
+    member_roles: &[u64],
+) -> RoleChange {
+    let mut wanted_roles: Vec<u64> = linked_clan_mates
+        .iter()
+        .filter_map(|clan_mate| clan_mate.rank.as_ref())
+        .filter_map(|rank| {
+            rank_roles
+                .iter()
+                .find(|(rank_title, _)| rank_title.to_lowercase() == rank.to_lowercase())
+                .map(|(_, role_id)| *role_id)
+        })
+        .collect();
+    wanted_roles.sort();
+    wanted_roles.dedup();
+
+    let mut managed_roles: Vec<u64> = rank_roles.values().copied().collect();
+    managed_roles.sort();
+    managed_roles.dedup();
+
+    RoleChange {
+        discord_user_id,
+        player_names: linked_clan_mates
+            .iter()
+            .map(|clan_mate| clan_mate.player_name.clone())
+            .collect(),
+        add_roles: wanted_roles
+            .iter()
+            .filter(|role_id| !member_roles.contains(role_id))
+            .copied()
+            .collect(),
+        remove_roles: managed_roles
+            .iter()
+            .filter(|role_id| member_roles.contains(role_id) && !wanted_roles.contains(role_id))
+            .copied()
+            .collect(),
+    }
+}
+
+/// Checks every member of the discord server and fixes any roles that drifted from their ranks.
+/// Members without a linked clan mate lose the rank roles they have.
+/// With dry_run nothing is changed, it only returns what would be
+pub async fn reconcile_guild_rank_roles(
+    registered_guild: &RegisteredGuildModel,
+    mongodb: &BotMongoDb,
+    discord_api_client: &DiscordApiClient,
+    dry_run: bool,
+) -> Result<Vec<RoleChange>, anyhow::Error> {
+    let rank_roles = match &registered_guild.rank_roles {
+        Some(rank_roles) if !rank_roles.is_empty() => rank_roles,
+        _ => return Ok(Vec::new()),
+    };
+
+    let clan_mates = mongodb
+        .clan_mates
+        .get_clan_mates_by_guild_id(registered_guild.guild_id)
+        .await?;
+    let mut linked_clan_mates: HashMap<u64, Vec<ClanMateModel>> = HashMap::new();
+    for clan_mate in clan_mates {
+        if let Some(discord_user_id) = clan_mate.discord_user_id {
+            linked_clan_mates
+                .entry(discord_user_id)
+                .or_default()
+                .push(clan_mate);
+        }
+    }
+
+    //Linked clan mates that left the discord server are not in the list and are skipped
+    let members = discord_api_client
+        .list_guild_members(registered_guild.guild_id)
+        .await?;
+    let mut role_changes: Vec<RoleChange> = Vec::new();
+    for member in members {
+        let clan_mates = linked_clan_mates
+            .remove(&member.discord_user_id)
+            .unwrap_or_default();
+        let role_change = plan_member_role_changes(
+            rank_roles,
+            member.discord_user_id,
+            &clan_mates,
+            &member.roles,
+        );
+        if role_change.is_empty() {
+            continue;
+        }
+        if !dry_run {
+            let result =
+                apply_role_change(registered_guild.guild_id, &role_change, discord_api_client)
+                    .await;
+            //One member failing should not stop the rest from syncing
+            if let Err(e) = result {
+                error!(
+                    "Failed to sync rank roles for {}: {:?}",
+                    member.discord_user_id, e
+                );
+                continue;
+            }
+        }
+        role_changes.push(role_change);
+    }
+    Ok(role_changes)
+}
+
+/// Updates a single members roles, used when their rank is seen changing in clan chat or
+/// they unlink a clan mate
+pub async fn sync_linked_member_rank_roles(
+    registered_guild: &RegisteredGuildModel,
+    mongodb: &BotMongoDb,
+    discord_api_client: &DiscordApiClient,
+    discord_user_id: u64,
+) -> Result<Option<RoleChange>, anyhow::Error> {
+    if !registered_guild.rank_role_sync_enabled.unwrap_or(false) {
+        return Ok(None);
+    }
+    let rank_roles = match &registered_guild.rank_roles {
+        Some(rank_roles) if !rank_roles.is_empty() => rank_roles,
+        _ => return Ok(None),
+    };
+    let clan_mates = mongodb
+        .clan_mates
+        .find_by_discord_user_id(registered_guild.guild_id, discord_user_id)
+        .await?;
+    sync_member_rank_roles(
+        registered_guild.guild_id,
+        rank_roles,
+        discord_user_id,
+        &clan_mates,
+        discord_api_client,
+    )
+    .await
+}
+
+async fn sync_member_rank_roles(
+    guild_id: u64,
+    rank_roles: &HashMap<String, u64>,
+    discord_user_id: u64,
+    clan_mates: &[ClanMateModel],
+    discord_api_client: &DiscordApiClient,
+) -> Result<Option<RoleChange>, anyhow::Error> {
+    let member_roles = match discord_api_client
+        .get_member_roles(guild_id, discord_user_id)
+        .await?
+    {
+        Some(member_roles) => member_roles,
+        //They left the discord server
+        None => return Ok(None),
+    };
+    let role_change =
+        plan_member_role_changes(rank_roles, discord_user_id, clan_mates, &member_roles);
+    if role_change.is_empty() {
+        return Ok(None);
+    }
+    apply_role_change(guild_id, &role_change, discord_api_client).await?;
+    Ok(Some(role_change))
+}
+
+async fn apply_role_change(
+    guild_id: u64,
+    role_change: &RoleChange,
+    discord_api_client: &DiscordApiClient,
+) -> Result<(), anyhow::Error> {
+    for role_id in &role_change.add_roles {
+        discord_api_client
+            .add_member_role(guild_id, role_change.discord_user_id, *role_id)
+            .await?;
+    }
+    for role_id in &role_change.remove_roles {
+        discord_api_client
+            .remove_member_role(guild_id, role_change.discord_user_id, *role_id)
+            .await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,101 @@
+use crate::broadcast_rules::{BroadcastRuleContext, RuleCondition};
+use crate::database::guilds_db::RegisteredGuildModel;
+use crate::osrs_broadcast_handler::BroadcastMessageToDiscord;
+use log::error;
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_ROLE_PING_COOLDOWN_SECONDS: u64 = 300;
+
+/// Mentions a discord role on a broadcast type when every condition matches
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RolePing {
+    pub role_id: u64,
+    pub conditions: Vec<RuleCondition>,
+    /// The role is not mentioned again for this broadcast type until this has passed
+    pub cooldown_seconds: Option<u64>,
+}
+
+impl RolePing {
+    pub fn cooldown_seconds(&self) -> u64 {
+        self.cooldown_seconds
+            .unwrap_or(DEFAULT_ROLE_PING_COOLDOWN_SECONDS)
+    }
+
+    pub fn to_string(&self) -> String {
+        let mut description = format!("<@&{}>", self.role_id);
+        if !self.conditions.is_empty() {
+            description.push_str(&format!(
+                " when {}",
+                self.conditions
+                    .iter()
+                    .map(|condition| condition.to_string())
+                    .collect::<Vec<String>>()
+                    .join(" and ")
+            ));
+        }
+        description.push_str(&format!(", at most every {}s", self.cooldown_seconds()));
+        description
+    }
+}
+
+/// The role to mention for the broadcast. The guilds own id is its @everyone role so it is never pinged
+pub fn role_to_ping(
+    registered_guild: &RegisteredGuildModel,
+    context: &BroadcastRuleContext,
+) -> Option<u64> {
+    let role_ping = registered_guild
+        .role_pings
+        .as_ref()?
+        .get(&context.broadcast_type)?;
+    if role_ping.role_id == registered_guild.guild_id {
+        return None;
+    }
+    match role_ping
+        .conditions
+        .iter()
+        .all(|condition| condition.matches(context))
+    {
+        true => Some(role_ping.role_id),
+        false => None,
+    }
+}
+
+/// The role to ping for the broadcast, None while the role is on cooldown for this broadcast type
+pub async fn role_ping_off_cooldown(
+    redis_connection: &mut redis::Connection,
+    registered_guild: &RegisteredGuildModel,
+    broadcast: &BroadcastMessageToDiscord,
+) -> Option<u64> {
+    let role_id = broadcast.ping_role_id?;
+    let role_ping = registered_guild
+        .role_pings
+        .as_ref()?
+        .get(&broadcast.type_of_broadcast)?;
+    //Redis does not take an expiry of 0, without a cooldown every broadcast pings
+    if role_ping.cooldown_seconds() == 0 {
+        return Some(role_id);
+    }
+    let redis_key = format!(
+        "RolePingCooldown:{}:{}:{}",
+        registered_guild.guild_id,
+        broadcast.type_of_broadcast.to_slug(),
+        role_id
+    );
+    //Only sets the key if it is not there, so when broadcasts come in at the same time only the
+    //one that starts the cooldown pings
+    let started_cooldown: redis::RedisResult<Option<String>> = redis::cmd("SET")
+        .arg(&redis_key)
+        .arg(1)
+        .arg("NX")
+        .arg("EX")
+        .arg(role_ping.cooldown_seconds())
+        .query(redis_connection);
+    match started_cooldown {
+        Ok(Some(_)) => Some(role_id),
+        Ok(None) => None,
+        Err(error) => {
+            error!("Failed to start the role ping cooldown: {:?}", error);
+            None
+        }
+    }
+}
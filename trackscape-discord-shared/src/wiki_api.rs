@@ -5,10 +5,12 @@ pub mod wiki_api {
         redis_helpers::{fetch_redis_json_object, write_to_cache_with_seconds},
     };
     use anyhow::anyhow;
+    use num_format::{Locale, ToFormattedString};
     use redis::Connection;
     use scraper::Html;
     use serde::{Deserialize, Serialize};
     use serde_json::Value;
+    use std::collections::HashMap;
 
     const BASE_URL: &str = "https://oldschool.runescape.wiki/api.php";
 
@@ -18,6 +20,13 @@ pub mod wiki_api {
 
     const CLOGS_FAILURE_KEY: &str = "failure:clogs";
 
+    const DROP_RATES_CACHE_KEY: &str = "drop_rates";
+
+    const DROP_RATES_FAILURE_KEY: &str = "failure:drop_rates";
+
+    //Most rows the wiki returns for a single bucket query
+    const DROP_RATES_PAGE_SIZE: usize = 5000;
+
     static APP_USER_AGENT: &str = concat!(
         env!("CARGO_PKG_NAME"),
         "/",
@@ -38,6 +47,14 @@ pub mod wiki_api {
         pub percentage: f64,
     }
 
+    /// How rare an item is as a drop, kept as the N in 1/N. Only the most common rate of all the
+    /// monsters that drop it is kept
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct WikiDropRate {
+        pub name: String,
+        pub one_in: f64,
+    }
+
     fn build_quest_url(difficulty: QuestDifficulty) -> String {
         format!(
             "{}?format=json&action=parse&page=Quests%2F{}&section=1",
@@ -53,6 +70,42 @@ pub mod wiki_api {
         )
     }
 
+    fn build_drop_rates_url(offset: usize) -> String {
+        let query = format!(
+            "bucket('dropsline').select('item_name','rarity').limit({}).offset({}).run()",
+            DROP_RATES_PAGE_SIZE, offset
+        );
+        format!(
+            "{}?format=json&action=bucket&query={}",
+            BASE_URL,
+            urlencoding::encode(&query)
+        )
+    }
+
+    /// Reads the wiki rarity text like 1/128, ~1/5,000 or 3/256 as the N in 1/N.
+    /// Always is 1/1, anything else like Varies or Rare has no rate
+    pub fn parse_drop_rarity(rarity: &str) -> Option<f64> {
+        let rarity = rarity.trim().trim_start_matches('~').replace(",", "");
+        if rarity.eq_ignore_ascii_case("always") {
+            return Some(1.0);
+        }
+        let (numerator, denominator) = rarity.split_once('/')?;
+        let numerator = numerator.trim().parse::<f64>().ok()?;
+        let denominator = denominator.trim().parse::<f64>().ok()?;
+        if numerator <= 0.0 || denominator <= 0.0 {
+            return None;
+        }
+        Some(denominator / numerator)
+    }
+
+    /// Shows the rate the way the wiki does, 1/5,000
+    pub fn format_drop_rate(one_in: f64) -> String {
+        match one_in.fract() == 0.0 {
+            true => format!("1/{}", (one_in as i64).to_formatted_string(&Locale::en)),
+            false => format!("1/{:.2}", one_in),
+        }
+    }
+
     pub async fn get_quests_and_difficulties(
         redis_connection: &mut Connection,
     ) -> Result<Vec<WikiQuest>, anyhow::Error> {
@@ -195,6 +248,106 @@ pub mod wiki_api {
         }
     }
 
+    /// The drop rates saved in the cache, never goes to the wiki so it is safe to call for every
+    /// broadcast. The cache is filled when the API starts and refreshed by the cron worker
+    pub async fn get_cached_drop_rates(
+        redis_connection: &mut Connection,
+    ) -> Result<Vec<WikiDropRate>, anyhow::Error> {
+        fetch_redis_json_object::<Vec<WikiDropRate>>(redis_connection, DROP_RATES_CACHE_KEY)
+            .await
+            .map_err(|_| anyhow!("The drop rates are not cached yet"))
+    }
+
+    /// The cached drop rates, going to the wiki for them if they are not cached yet
+    pub async fn get_drop_rates(
+        redis_connection: &mut Connection,
+    ) -> Result<Vec<WikiDropRate>, anyhow::Error> {
+        if let Ok(drop_rates) = get_cached_drop_rates(redis_connection).await {
+            return Ok(drop_rates);
+        }
+
+        // Same as the clogs, don't hammer the wiki if it failed recently
+        if redis_exists(redis_connection, DROP_RATES_FAILURE_KEY).await {
+            return Err(anyhow!(
+                "Failed to fetch drop rates previously; skipping external API call for up to an hour"
+            ));
+        }
+        write_to_cache_with_seconds(redis_connection, DROP_RATES_FAILURE_KEY, true, 3_600).await;
+        refresh_drop_rates(redis_connection).await
+    }
+
+    /// Gets every drop rate on the wiki and caches them. The rates are kept per item, not per
+    /// monster, so an item dropped by more than one monster only keeps its most common rate.
+    /// That way a drop is never called rarer than it is, but a rare source of a common item
+    /// shows the common rate
+    pub async fn refresh_drop_rates(
+        redis_connection: &mut Connection,
+    ) -> Result<Vec<WikiDropRate>, anyhow::Error> {
+        let client = reqwest::Client::builder()
+            .user_agent(APP_USER_AGENT)
+            .build()?;
+        let mut drop_rates: HashMap<String, f64> = HashMap::new();
+        let mut offset = 0;
+        loop {
+            let url = build_drop_rates_url(offset);
+            println!("Getting drop rates from {}", url.as_str());
+            let wiki_result = match client.get(url.as_str()).send().await {
+                Ok(ok_resp) => match ok_resp.json::<BucketRoot<DropsLine>>().await {
+                    Ok(wiki_result) => wiki_result,
+                    Err(e) => {
+                        println!("Failed to parse drop rates from wiki: {}", e);
+                        return Err(e.into());
+                    }
+                },
+                Err(e) => {
+                    return Err(e.into());
+                }
+            };
+            let rows = wiki_result.bucket.len();
+            for drops_line in wiki_result.bucket {
+                let (Some(name), Some(one_in)) = (
+                    drops_line.item_name,
+                    drops_line.rarity.as_deref().and_then(parse_drop_rarity),
+                ) else {
+                    continue;
+                };
+                let rate = drop_rates.entry(name).or_insert(one_in);
+                if one_in < *rate {
+                    *rate = one_in;
+                }
+            }
+            if rows < DROP_RATES_PAGE_SIZE {
+                break;
+            }
+            offset += DROP_RATES_PAGE_SIZE;
+        }
+
+        let drop_rates: Vec<WikiDropRate> = drop_rates
+            .into_iter()
+            .map(|(name, one_in)| WikiDropRate { name, one_in })
+            .collect();
+        write_to_cache_with_seconds(
+            redis_connection,
+            DROP_RATES_CACHE_KEY,
+            drop_rates.clone(),
+            604800,
+        )
+        .await;
+
+        Ok(drop_rates)
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct BucketRoot<T> {
+        pub bucket: Vec<T>,
+    }
+
+    #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub struct DropsLine {
+        pub item_name: Option<String>,
+        pub rarity: Option<String>,
+    }
+
     #[derive(Default, Debug, Clone, PartialEq, Serialize, Deserialize)]
     #[serde(rename_all = "camelCase")]
     pub struct Root {